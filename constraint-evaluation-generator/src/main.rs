@@ -166,6 +166,20 @@ fn gen<T: TableChallenges, SII: InputIndicator, DII: InputIndicator>(
     let transition_constraint_strings = turn_circuits_into_string(transition_constraint_circuits);
     let terminal_constraint_strings = turn_circuits_into_string(terminal_constraint_circuits);
 
+    // Over the B-field, constraints on base columns only evaluate to B-field elements, which are
+    // lifted. Over the X-field, all constraints evaluate to X-field elements.
+    let lift_and_concatenate_constraints = "base_constraints
+            .map(|v| BFieldElement::lift(&v))
+            .iter()
+            .chain(ext_constraints.iter())
+            .cloned()
+            .collect()";
+    let concatenate_constraints = "base_constraints
+            .iter()
+            .chain(ext_constraints.iter())
+            .cloned()
+            .collect()";
+
     format!(
         "
 use ndarray::ArrayView1;
@@ -183,7 +197,7 @@ use crate::table::{table_name_snake}::{challenge_enum_name}::*;
 // This file has been auto-generated. Any modifications _will_ be lost.
 // To re-generate, execute:
 // `cargo run --bin constraint-evaluation-generator`
-impl Evaluable for {table_mod_name} {{
+impl Evaluable<BFieldElement> for {table_mod_name} {{
     #[inline]
     #[allow(unused_variables)]
     fn evaluate_initial_constraints(
@@ -193,6 +207,7 @@ impl Evaluable for {table_mod_name} {{
     ) -> Vec<XFieldElement> {{
         let challenges = &challenges.{table_name_snake}_challenges;
         {initial_constraint_strings}
+        {lift_and_concatenate_constraints}
     }}
 
     #[inline]
@@ -204,6 +219,7 @@ impl Evaluable for {table_mod_name} {{
     ) -> Vec<XFieldElement> {{
         let challenges = &challenges.{table_name_snake}_challenges;
         {consistency_constraint_strings}
+        {lift_and_concatenate_constraints}
     }}
 
     #[inline]
@@ -217,6 +233,7 @@ impl Evaluable for {table_mod_name} {{
     ) -> Vec<XFieldElement> {{
         let challenges = &challenges.{table_name_snake}_challenges;
         {transition_constraint_strings}
+        {lift_and_concatenate_constraints}
     }}
 
     #[inline]
@@ -228,6 +245,59 @@ impl Evaluable for {table_mod_name} {{
     ) -> Vec<XFieldElement> {{
         let challenges = &challenges.{table_name_snake}_challenges;
         {terminal_constraint_strings}
+        {lift_and_concatenate_constraints}
+    }}
+}}
+
+impl Evaluable<XFieldElement> for {table_mod_name} {{
+    #[inline]
+    #[allow(unused_variables)]
+    fn evaluate_initial_constraints(
+        base_row: ArrayView1<XFieldElement>,
+        ext_row: ArrayView1<XFieldElement>,
+        challenges: &AllChallenges,
+    ) -> Vec<XFieldElement> {{
+        let challenges = &challenges.{table_name_snake}_challenges;
+        {initial_constraint_strings}
+        {concatenate_constraints}
+    }}
+
+    #[inline]
+    #[allow(unused_variables)]
+    fn evaluate_consistency_constraints(
+        base_row: ArrayView1<XFieldElement>,
+        ext_row: ArrayView1<XFieldElement>,
+        challenges: &AllChallenges,
+    ) -> Vec<XFieldElement> {{
+        let challenges = &challenges.{table_name_snake}_challenges;
+        {consistency_constraint_strings}
+        {concatenate_constraints}
+    }}
+
+    #[inline]
+    #[allow(unused_variables)]
+    fn evaluate_transition_constraints(
+        current_base_row: ArrayView1<XFieldElement>,
+        current_ext_row: ArrayView1<XFieldElement>,
+        next_base_row: ArrayView1<XFieldElement>,
+        next_ext_row: ArrayView1<XFieldElement>,
+        challenges: &AllChallenges,
+    ) -> Vec<XFieldElement> {{
+        let challenges = &challenges.{table_name_snake}_challenges;
+        {transition_constraint_strings}
+        {concatenate_constraints}
+    }}
+
+    #[inline]
+    #[allow(unused_variables)]
+    fn evaluate_terminal_constraints(
+        base_row: ArrayView1<XFieldElement>,
+        ext_row: ArrayView1<XFieldElement>,
+        challenges: &AllChallenges,
+    ) -> Vec<XFieldElement> {{
+        let challenges = &challenges.{table_name_snake}_challenges;
+        {terminal_constraint_strings}
+        {concatenate_constraints}
     }}
 }}
 
@@ -354,14 +424,7 @@ fn turn_circuits_into_string<T: TableChallenges, II: InputIndicator>(
         "{shared_declarations}
 
         let base_constraints = [{base_constraint_evaluations_joined}];
-        let ext_constraints = [{ext_constraint_evaluations_joined}];
-
-        base_constraints
-            .map(|v| BFieldElement::lift(&v))
-            .iter()
-            .chain(ext_constraints.iter())
-            .cloned()
-            .collect()"
+        let ext_constraints = [{ext_constraint_evaluations_joined}];"
     )
 }
 
@@ -0,0 +1,273 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::instruction::AnInstruction::*;
+use crate::instruction::Instruction;
+use crate::instruction::LabelledInstruction;
+use crate::ord_n::Ord16;
+use crate::ord_n::Ord16::*;
+use crate::program::Program;
+
+use DisassemblerError::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisassemblerError {
+    InvalidOpcode {
+        offset: usize,
+        opcode: BFieldElement,
+    },
+    MissingArgument {
+        offset: usize,
+        instruction: Instruction,
+    },
+    InvalidArgument {
+        offset: usize,
+        instruction: Instruction,
+        argument: BFieldElement,
+    },
+    InvalidCallTarget {
+        offset: usize,
+        target: BFieldElement,
+    },
+}
+
+impl Error for DisassemblerError {}
+
+impl fmt::Display for DisassemblerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Cannot disassemble program: {self:?}")
+    }
+}
+
+/// Decode a program from its encoding as produced by [`Program::to_bwords`].
+///
+/// Errors report the offset of the offending word in `words`.
+pub fn decode(words: &[BFieldElement]) -> Result<Program, DisassemblerError> {
+    let mut instructions = Vec::with_capacity(words.len());
+    let mut offset = 0;
+    while offset < words.len() {
+        let opcode = words[offset];
+        let instruction =
+            Instruction::try_from(opcode.value()).map_err(|_| InvalidOpcode { offset, opcode })?;
+
+        let instruction = if instruction.size() == 2 {
+            let argument = *words.get(offset + 1).ok_or(MissingArgument {
+                offset,
+                instruction,
+            })?;
+            with_argument(instruction, argument).ok_or(InvalidArgument {
+                offset,
+                instruction,
+                argument,
+            })?
+        } else {
+            instruction
+        };
+
+        instructions.extend(vec![instruction; instruction.size()]);
+        offset += instruction.size();
+    }
+
+    Ok(Program { instructions })
+}
+
+/// Replace the default argument of a double-word `instruction` by `argument`, if it is valid.
+fn with_argument(instruction: Instruction, argument: BFieldElement) -> Option<Instruction> {
+    let stack_register = || {
+        let n = u32::try_from(argument.value()).ok()?;
+        Ord16::try_from(n).ok()
+    };
+
+    match instruction {
+        Push(_) => Some(Push(argument)),
+        Dup(_) => stack_register().map(Dup),
+        Swap(_) => stack_register()
+            .filter(|&register| register != ST0)
+            .map(Swap),
+        Call(_) => Some(Call(argument)),
+        _ => None,
+    }
+}
+
+/// Turn a `Program` back into Triton assembly that [`crate::parser::parse`] accepts.
+///
+/// Labels are lost when a program is built, so every `call` target gets a synthetic label
+/// `_addr_<address>:`. Fails if a `call` targets an address that is not the start of an
+/// instruction, since no label can be placed there.
+pub fn disassemble(program: &Program) -> Result<String, DisassemblerError> {
+    let labelled_instructions = labelled_instructions(program)?;
+    let code = labelled_instructions
+        .iter()
+        .map(|labelled_instruction| match labelled_instruction {
+            LabelledInstruction::Label(..) => format!("{labelled_instruction}\n"),
+            _ => format!("    {labelled_instruction}\n"),
+        })
+        .collect();
+    Ok(code)
+}
+
+/// The instructions of `program`, with a synthetic label at every `call` target.
+pub fn labelled_instructions(
+    program: &Program,
+) -> Result<Vec<LabelledInstruction<'static>>, DisassemblerError> {
+    let mut instruction_addresses = BTreeSet::new();
+    let mut address = 0;
+    for instruction in program.clone() {
+        instruction_addresses.insert(address);
+        address += instruction.size();
+    }
+    instruction_addresses.insert(program.len());
+
+    let mut call_targets = BTreeSet::new();
+    for &offset in instruction_addresses.iter() {
+        if let Some(Call(target)) = program.instructions.get(offset) {
+            let target_address = usize::try_from(target.value()).ok();
+            match target_address.filter(|address| instruction_addresses.contains(address)) {
+                Some(address) => call_targets.insert(address),
+                None => {
+                    return Err(InvalidCallTarget {
+                        offset,
+                        target: *target,
+                    })
+                }
+            };
+        }
+    }
+
+    let mut labelled_instructions = vec![];
+    for &address in instruction_addresses.iter() {
+        if call_targets.contains(&address) {
+            labelled_instructions.push(LabelledInstruction::Label(label_name(address), ""));
+        }
+        if let Some(instruction) = program.instructions.get(address) {
+            let instruction =
+                instruction.map_call_address(|target| label_name(target.value() as usize));
+            labelled_instructions.push(LabelledInstruction::Instruction(instruction, ""));
+        }
+    }
+
+    Ok(labelled_instructions)
+}
+
+fn label_name(address: usize) -> String {
+    format!("_addr_{address}")
+}
+
+#[cfg(test)]
+mod disassembler_tests {
+    use crate::instruction::sample_programs;
+    use crate::parser::parse;
+
+    use super::*;
+
+    fn assert_round_trip(code: &str) {
+        let program = Program::from_code(code).unwrap();
+        let decoded_program = decode(&program.to_bwords()).unwrap();
+        assert_eq!(program, decoded_program);
+
+        let disassembled_code = disassemble(&decoded_program).unwrap();
+        let reassembled_program = Program::new(&parse(&disassembled_code).unwrap());
+        assert_eq!(program, reassembled_program, "{disassembled_code}");
+    }
+
+    #[test]
+    fn round_trip_test() {
+        assert_round_trip("");
+        assert_round_trip(sample_programs::PUSH_PUSH_ADD_POP_S);
+        assert_round_trip(sample_programs::READ_X3_WRITE_X14);
+        assert_round_trip(sample_programs::HASH_HASH_HASH_HALT);
+        assert_round_trip(&format!(
+            "{} foo: return",
+            sample_programs::ALL_INSTRUCTIONS
+        ));
+        assert_round_trip("push -1 dup15 swap15 neg call end nop end:");
+    }
+
+    #[test]
+    fn disassemble_places_labels_at_call_targets_test() {
+        let program = Program::from_code("foo: push 1 call bar halt bar: call foo return").unwrap();
+        let expected_code = "\
+            _addr_0:\n    push 1\n    call _addr_5\n    halt\n\
+            _addr_5:\n    call _addr_0\n    return\n";
+        assert_eq!(expected_code, disassemble(&program).unwrap());
+    }
+
+    #[test]
+    fn decode_reports_offset_of_invalid_words_test() {
+        let push = Instruction::Push(BFieldElement::new(0)).opcode_b();
+        let dup = Instruction::Dup(ST0).opcode_b();
+        let swap = Instruction::Swap(ST1).opcode_b();
+        let halt = Instruction::Halt.opcode_b();
+
+        let invalid_opcode = BFieldElement::new(3);
+        assert_eq!(
+            Err(InvalidOpcode {
+                offset: 3,
+                opcode: invalid_opcode
+            }),
+            decode(&[push, invalid_opcode, halt, invalid_opcode])
+        );
+
+        let truncated_opcode = BFieldElement::new((1 << 32) + halt.value());
+        assert_eq!(
+            Err(InvalidOpcode {
+                offset: 0,
+                opcode: truncated_opcode
+            }),
+            decode(&[truncated_opcode])
+        );
+
+        assert_eq!(
+            Err(MissingArgument {
+                offset: 1,
+                instruction: Push(BFieldElement::new(0))
+            }),
+            decode(&[halt, push])
+        );
+
+        let sixteen = BFieldElement::new(16);
+        assert_eq!(
+            Err(InvalidArgument {
+                offset: 0,
+                instruction: Dup(ST0),
+                argument: sixteen,
+            }),
+            decode(&[dup, sixteen])
+        );
+
+        let zero = BFieldElement::new(0);
+        assert_eq!(
+            Err(InvalidArgument {
+                offset: 0,
+                instruction: Swap(ST0),
+                argument: zero,
+            }),
+            decode(&[swap, zero])
+        );
+    }
+
+    #[test]
+    fn disassemble_rejects_invalid_call_targets_test() {
+        let call = Instruction::Call(BFieldElement::new(0)).opcode_b();
+        let push = Instruction::Push(BFieldElement::new(0)).opcode_b();
+        let halt = Instruction::Halt.opcode_b();
+
+        for target in [1, 3, 6] {
+            let target = BFieldElement::new(target);
+            let program = decode(&[push, call, call, target, halt]).unwrap();
+            assert_eq!(
+                Err(InvalidCallTarget { offset: 2, target }),
+                disassemble(&program)
+            );
+        }
+
+        let program = decode(&[call, BFieldElement::new(3), halt]).unwrap();
+        assert_eq!(
+            "    call _addr_3\n    halt\n_addr_3:\n",
+            disassemble(&program).unwrap()
+        );
+    }
+}
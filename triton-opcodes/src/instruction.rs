@@ -11,6 +11,8 @@ use strum_macros::Display as DisplayMacro;
 use strum_macros::EnumCount as EnumCountMacro;
 use strum_macros::EnumIter;
 
+use num_traits::One;
+use num_traits::Zero;
 use twenty_first::shared_math::b_field_element::BFieldElement;
 
 use AnInstruction::*;
@@ -31,6 +33,12 @@ pub enum LabelledInstruction<'a> {
     /// https://triton-vm.org/spec/isa.html
     Instruction(AnInstruction<String>, &'a str),
 
+    /// Pseudo-instructions are not part of the ISA. They are expanded into instructions when
+    /// the `Program` is built:
+    ///
+    /// https://triton-vm.org/spec/pseudo-instructions.html
+    PseudoInstruction(PseudoInstruction, &'a str),
+
     /// Labels look like "`<name>:`" and are translated into absolute addresses.
     Label(String, &'a str),
 }
@@ -40,6 +48,9 @@ impl<'a> PartialEq for LabelledInstruction<'a> {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Instruction(instr1, _), Self::Instruction(instr2, _)) => instr1 == instr2,
+            (Self::PseudoInstruction(pseudo1, _), Self::PseudoInstruction(pseudo2, _)) => {
+                pseudo1 == pseudo2
+            }
             (Self::Label(label1, _), Self::Label(label2, _)) => label1 == label2,
             _ => false,
         }
@@ -50,20 +61,91 @@ impl<'a> Display for LabelledInstruction<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             LabelledInstruction::Instruction(instr, _) => write!(f, "{instr}"),
+            LabelledInstruction::PseudoInstruction(pseudo, _) => write!(f, "{pseudo}"),
             LabelledInstruction::Label(label_name, _) => write!(f, "{label_name}:"),
         }
     }
 }
 
+impl<'a> LabelledInstruction<'a> {
+    /// The number of words this `LabelledInstruction` occupies in the `Program`.
+    pub fn size(&self) -> usize {
+        match self {
+            LabelledInstruction::Instruction(instr, _) => instr.size(),
+            LabelledInstruction::PseudoInstruction(pseudo, _) => pseudo.size(),
+            LabelledInstruction::Label(_, _) => 0,
+        }
+    }
+}
+
 pub fn token_str<'a>(instruction: &LabelledInstruction<'a>) -> &'a str {
     match instruction {
         LabelledInstruction::Instruction(_, token_str) => token_str,
+        LabelledInstruction::PseudoInstruction(_, token_str) => token_str,
         LabelledInstruction::Label(_, token_str) => token_str,
     }
 }
 
-#[derive(Debug, DisplayMacro, Clone, Copy, PartialEq, Eq, Hash, EnumCountMacro)]
-pub enum DivinationHint {}
+/// A hint for instruction `divine`, written `divine_<hint>`, e.g., `divine_inverse`. Instead of
+/// reading the next element of secret input, a hinted `divine` pushes the element the hint
+/// describes, computed by the host from the state of the VM.
+///
+/// A hinted `divine` has the same opcode as `divine`. The hint is not part of the program's
+/// encoding, and the AIR does not know about it: to the verifier, it is any other `divine`.
+#[derive(Debug, DisplayMacro, Clone, Copy, PartialEq, Eq, Hash, EnumCountMacro, EnumIter)]
+pub enum DivinationHint {
+    /// The multiplicative inverse of `st0`.
+    Inverse,
+
+    /// The least significant bit of `st0`. Divining it repeatedly allows decomposing `st0` into
+    /// its bits.
+    Lsb,
+}
+
+/// A pseudo-instruction is simulated using a fixed sequence of instructions.
+///
+/// The pseudo-instructions and their expansions are defined at:
+///
+/// https://triton-vm.org/spec/pseudo-instructions.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumCountMacro, EnumIter)]
+pub enum PseudoInstruction {
+    Neg,
+    Sub,
+    IsU32,
+    Lsb,
+}
+
+impl Display for PseudoInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PseudoInstruction::Neg => write!(f, "neg"),
+            PseudoInstruction::Sub => write!(f, "sub"),
+            PseudoInstruction::IsU32 => write!(f, "is_u32"),
+            PseudoInstruction::Lsb => write!(f, "lsb"),
+        }
+    }
+}
+
+impl PseudoInstruction {
+    /// The instructions this pseudo-instruction expands to.
+    pub fn expansion<Dest: PartialEq + Default>(&self) -> Vec<AnInstruction<Dest>> {
+        let minus_one = -BFieldElement::one();
+        match self {
+            PseudoInstruction::Neg => vec![Push(minus_one), Mul],
+            PseudoInstruction::Sub => vec![Swap(ST1), Push(minus_one), Mul, Add],
+            PseudoInstruction::IsU32 => vec![Split, Pop, Push(BFieldElement::zero()), Eq],
+            PseudoInstruction::Lsb => vec![Push(BFieldElement::new(2)), Swap(ST1), Div],
+        }
+    }
+
+    /// The size of the expansion, _i.e._, the number of words it occupies in the `Program`.
+    pub fn size(&self) -> usize {
+        self.expansion::<BFieldElement>()
+            .iter()
+            .map(|instruction| instruction.size())
+            .sum()
+    }
+}
 
 /// A Triton VM instruction
 ///
@@ -305,7 +387,10 @@ impl<Dest: PartialEq + Default> AnInstruction<Dest> {
         ((opcode >> bit_number) & 1).into()
     }
 
-    fn map_call_address<F, NewDest: PartialEq + Default>(&self, f: F) -> AnInstruction<NewDest>
+    pub(crate) fn map_call_address<F, NewDest: PartialEq + Default>(
+        &self,
+        f: F,
+    ) -> AnInstruction<NewDest>
     where
         F: Fn(&Dest) -> NewDest,
     {
@@ -382,7 +467,8 @@ impl TryFrom<u64> for Instruction {
     type Error = anyhow::Error;
 
     fn try_from(opcode: u64) -> Result<Self> {
-        (opcode as u32).try_into()
+        let opcode = u32::try_from(opcode)?;
+        opcode.try_into()
     }
 }
 
@@ -390,7 +476,8 @@ impl TryFrom<usize> for Instruction {
     type Error = anyhow::Error;
 
     fn try_from(opcode: usize) -> Result<Self> {
-        (opcode as u32).try_into()
+        let opcode = u32::try_from(opcode)?;
+        opcode.try_into()
     }
 }
 
@@ -418,27 +505,49 @@ impl Error for TokenError {}
 
 /// Convert a program with labels to a program with absolute positions
 pub fn convert_labels(program: &[LabelledInstruction]) -> Vec<Instruction> {
+    convert_labels_with_origins(program)
+        .into_iter()
+        .map(|(instruction, _)| instruction)
+        .collect()
+}
+
+/// Like [`convert_labels`], but pairs every `Instruction` with the index in `program` of the
+/// `LabelledInstruction` it stems from. All `Instruction`s a pseudo-instruction expands to have
+/// the same origin.
+pub fn convert_labels_with_origins(program: &[LabelledInstruction]) -> Vec<(Instruction, usize)> {
+    // 1. Add all labels to a map
+    let label_map = label_addresses(program);
+
+    // 2. Convert every label to the lookup value of that map
+    program
+        .iter()
+        .enumerate()
+        .flat_map(|(origin, labelled_instruction)| {
+            convert_labels_helper(labelled_instruction, &label_map)
+                .into_iter()
+                .map(move |instruction| (instruction, origin))
+        })
+        .collect()
+}
+
+/// Map every label to the absolute address of the instruction following it.
+pub fn label_addresses(program: &[LabelledInstruction]) -> HashMap<String, usize> {
     let mut label_map = HashMap::<String, usize>::new();
     let mut instruction_pointer: usize = 0;
 
-    // 1. Add all labels to a map
     for labelled_instruction in program.iter() {
         match labelled_instruction {
             LabelledInstruction::Label(label_name, _) => {
                 label_map.insert(label_name.clone(), instruction_pointer);
             }
 
-            LabelledInstruction::Instruction(instr, _) => {
-                instruction_pointer += instr.size();
+            instruction => {
+                instruction_pointer += instruction.size();
             }
         }
     }
 
-    // 2. Convert every label to the lookup value of that map
-    program
-        .iter()
-        .flat_map(|labelled_instruction| convert_labels_helper(labelled_instruction, &label_map))
-        .collect()
+    label_map
 }
 
 fn convert_labels_helper(
@@ -457,6 +566,8 @@ fn convert_labels_helper(
 
             vec![unlabelled_instruction]
         }
+
+        LabelledInstruction::PseudoInstruction(pseudo, _) => pseudo.expansion(),
     }
 }
 
@@ -465,6 +576,8 @@ pub fn is_instruction_name(s: &str) -> bool {
         "pop" => true,
         "push" => true,
         "divine" => true,
+        "divine_inverse" => true,
+        "divine_lsb" => true,
         "dup0" => true,
         "dup1" => true,
         "dup2" => true,
@@ -534,6 +647,10 @@ pub fn is_instruction_name(s: &str) -> bool {
     }
 }
 
+pub fn is_pseudo_instruction_name(s: &str) -> bool {
+    PseudoInstruction::iter().any(|pseudo| pseudo.to_string() == s)
+}
+
 pub fn all_instructions_without_args() -> Vec<Instruction> {
     let all_instructions: [_; Instruction::COUNT] = [
         Pop,
@@ -1,4 +1,6 @@
+pub mod disassembler;
 pub mod instruction;
 pub mod ord_n;
 pub mod parser;
 pub mod program;
+pub mod source_map;
@@ -1,6 +1,15 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::bail;
+use anyhow::Context;
+use anyhow::Result;
+use itertools::Itertools;
+use regex::Regex;
 
 use twenty_first::shared_math::b_field_element::BFieldElement;
 
@@ -8,10 +17,12 @@ use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::bytes::complete::take_while;
 use nom::bytes::complete::take_while1;
+use nom::bytes::complete::take_while_m_n;
 use nom::character::complete::digit1;
 use nom::combinator::cut;
 use nom::combinator::eof;
 use nom::combinator::fail;
+use nom::combinator::map;
 use nom::combinator::opt;
 use nom::error::context;
 use nom::error::convert_error;
@@ -20,16 +31,21 @@ use nom::error::VerboseError;
 use nom::error::VerboseErrorKind;
 use nom::multi::many0;
 use nom::multi::many1;
+use nom::multi::separated_list0;
 use nom::Finish;
 use nom::IResult;
 
 use crate::instruction::is_instruction_name;
+use crate::instruction::is_pseudo_instruction_name;
 use crate::instruction::token_str;
 use crate::instruction::AnInstruction;
 use crate::instruction::AnInstruction::*;
+use crate::instruction::DivinationHint;
 use crate::instruction::LabelledInstruction;
+use crate::instruction::PseudoInstruction;
 use crate::ord_n::Ord16;
 use crate::ord_n::Ord16::*;
+use crate::source_map::LineOrigin;
 
 #[derive(Debug, PartialEq)]
 pub struct ParseError<'a> {
@@ -65,17 +81,129 @@ pub fn pretty_print_error(s: &str, mut e: VerboseError<&str>) -> String {
 }
 
 /// Parse a program
+///
+/// Besides instructions, pseudo-instructions and labels, a program can define
+///
+/// - named constants, _e.g._, `const N = 42`, usable in place of `push`'s argument, and
+/// - macros with parameters, _e.g._, `macro push_twice(x) { push x push x }`, which are
+///   expanded wherever they are invoked, _e.g._, `push_twice(N)`.
+///
+/// Constants and macros must be defined at the top level. A macro's parameters shadow
+/// constants of the same name within the macro's body. Macros can invoke other macros, but
+/// not recursively. Labels in a macro's body are defined anew for every invocation, which
+/// makes them duplicates if the macro is invoked more than once.
+///
+/// Including other files is resolved before parsing; see [`resolve_includes`].
 pub fn parse(input: &str) -> Result<Vec<LabelledInstruction>, ParseError> {
-    let instructions = match program(input).finish() {
-        Ok((_s, instructions)) => Ok(instructions),
+    let statements = match program(input).finish() {
+        Ok((_s, statements)) => Ok(statements),
         Err(errors) => Err(ParseError { input, errors }),
     }?;
 
+    let instructions = MacroExpander::new(&statements)
+        .and_then(|expander| expander.expand_program(&statements))
+        .map_err(|(token, message)| {
+            let errors = VerboseError {
+                errors: vec![(token, VerboseErrorKind::Context(message))],
+            };
+            ParseError { input, errors }
+        })?;
+
     scan_missing_duplicate_labels(input, &instructions)?;
 
     Ok(instructions)
 }
 
+/// Like [`parse`], but also returns the byte offset in `input` at which every
+/// `LabelledInstruction` starts. Instructions stemming from a macro's body start where they
+/// appear in the macro's definition, and `push <constant>` starts at `push`.
+pub fn parse_with_offsets(
+    input: &str,
+) -> Result<(Vec<LabelledInstruction<'_>>, Vec<usize>), ParseError<'_>> {
+    let instructions = parse(input)?;
+
+    // Every token is the input left over when the parser reached the token, i.e., a suffix of
+    // `input`. The token's length therefore determines where in `input` it starts.
+    let offsets = instructions
+        .iter()
+        .map(|instruction| input.len() - token_str(instruction).len())
+        .collect();
+
+    Ok((instructions, offsets))
+}
+
+/// Read the program in the file at `path`, replacing every line `include "<file>"` with the
+/// contents of `<file>`. Paths are relative to the directory of the including file.
+///
+/// Every file is included at most once, so that shared libraries can be included from several
+/// places. Including a file from itself, directly or transitively, is an error.
+pub fn resolve_includes(path: &Path) -> Result<String> {
+    let (code, _) = resolve_includes_with_origins(path)?;
+    Ok(code)
+}
+
+/// Like [`resolve_includes`], but also returns the origin of every line of the resolved code: the
+/// file it was read from, and its line number there. See
+/// [`SourceMap::with_line_origins`](crate::source_map::SourceMap::with_line_origins).
+pub fn resolve_includes_with_origins(path: &Path) -> Result<(String, Vec<LineOrigin>)> {
+    let mut included = HashSet::new();
+    let mut include_stack = vec![];
+    let mut line_origins = vec![];
+    let code = resolve_includes_helper(path, &mut included, &mut include_stack, &mut line_origins)?;
+    Ok((code, line_origins))
+}
+
+fn resolve_includes_helper(
+    path: &Path,
+    included: &mut HashSet<PathBuf>,
+    include_stack: &mut Vec<PathBuf>,
+    line_origins: &mut Vec<LineOrigin>,
+) -> Result<String> {
+    let canonical_path = path
+        .canonicalize()
+        .with_context(|| format!("cannot read program from {}", path.display()))?;
+    if include_stack.contains(&canonical_path) {
+        let cycle = include_stack
+            .iter()
+            .skip_while(|&p| p != &canonical_path)
+            .chain(std::iter::once(&canonical_path))
+            .map(|p| p.display().to_string())
+            .join(" -> ");
+        bail!("include cycle: {cycle}");
+    }
+    if !included.insert(canonical_path.clone()) {
+        return Ok(String::new());
+    }
+
+    let code = fs::read_to_string(path)
+        .with_context(|| format!("cannot read program from {}", path.display()))?;
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    let include_directive = Regex::new(r#"^\s*include\s+"([^"]+)"\s*(//.*)?$"#).unwrap();
+
+    include_stack.push(canonical_path);
+    let mut resolved_code = String::with_capacity(code.len());
+    for (line_index, line) in code.lines().enumerate() {
+        if let Some(captures) = include_directive.captures(line) {
+            let included_path = directory.join(&captures[1]);
+            let included_code =
+                resolve_includes_helper(&included_path, included, include_stack, line_origins)?;
+            resolved_code.push_str(&included_code);
+        } else {
+            resolved_code.push_str(line);
+        }
+
+        // The line break after an include directive marks an empty line of its own.
+        resolved_code.push('\n');
+        line_origins.push(LineOrigin {
+            file: path.to_path_buf(),
+            line: line_index + 1,
+        });
+    }
+    include_stack.pop();
+
+    Ok(resolved_code)
+}
+
 fn scan_missing_duplicate_labels<'a>(
     input: &'a str,
     instructions: &[LabelledInstruction<'a>],
@@ -137,12 +265,166 @@ fn scan_missing_duplicate_labels<'a>(
 /// error type, but we want `nom::error::VerboseError` as it allows `context()`.
 type ParseResult<'input, Out> = IResult<&'input str, Out, VerboseError<&'input str>>;
 
-fn program(s: &str) -> ParseResult<Vec<LabelledInstruction>> {
+/// A `Statement` is a `LabelledInstruction` before constants and macros are resolved.
+#[derive(Debug, Clone)]
+enum Statement<'a> {
+    LabelledInstruction(LabelledInstruction<'a>),
+
+    /// `push <name>`, where `<name>` is a constant or a macro parameter.
+    PushConstant(String, &'a str),
+
+    /// `const <name> = <value>`
+    ConstantDefinition(String, BFieldElement, &'a str),
+
+    /// `macro <name>(<parameters>) { <body> }`
+    MacroDefinition(MacroDefinition<'a>),
+
+    /// `<name>(<arguments>)`
+    MacroInvocation(String, Vec<MacroArgument>, &'a str),
+}
+
+#[derive(Debug, Clone)]
+struct MacroDefinition<'a> {
+    name: String,
+    parameters: Vec<String>,
+    body: Vec<Statement<'a>>,
+    token: &'a str,
+}
+
+#[derive(Debug, Clone)]
+enum MacroArgument {
+    Value(BFieldElement),
+    Constant(String),
+}
+
+/// An error found while expanding macros: the offending token and a message.
+type ExpansionError<'a> = (&'a str, &'static str);
+
+/// Resolves constants and expands macros, turning `Statement`s into `LabelledInstruction`s.
+struct MacroExpander<'a, 's> {
+    constants: HashMap<String, BFieldElement>,
+    macros: HashMap<String, &'s MacroDefinition<'a>>,
+}
+
+impl<'a, 's> MacroExpander<'a, 's> {
+    /// Collect the constants and macros defined in the program.
+    fn new(statements: &'s [Statement<'a>]) -> Result<Self, ExpansionError<'a>> {
+        let mut constants = HashMap::new();
+        let mut macros = HashMap::new();
+        for statement in statements.iter() {
+            match statement {
+                Statement::ConstantDefinition(name, value, token) => {
+                    if is_reserved_name(name) {
+                        return Err((token, "constant cannot be named after instruction"));
+                    }
+                    if constants.insert(name.clone(), *value).is_some() {
+                        return Err((token, "duplicate constant"));
+                    }
+                }
+                Statement::MacroDefinition(definition) => {
+                    if is_reserved_name(&definition.name) {
+                        return Err((definition.token, "macro cannot be named after instruction"));
+                    }
+                    if macros.insert(definition.name.clone(), definition).is_some() {
+                        return Err((definition.token, "duplicate macro"));
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(Self { constants, macros })
+    }
+
+    fn expand_program(
+        &self,
+        statements: &[Statement<'a>],
+    ) -> Result<Vec<LabelledInstruction<'a>>, ExpansionError<'a>> {
+        let mut instructions = vec![];
+        self.expand(statements, &self.constants, &mut vec![], &mut instructions)?;
+        Ok(instructions)
+    }
+
+    fn expand(
+        &self,
+        statements: &[Statement<'a>],
+        scope: &HashMap<String, BFieldElement>,
+        invocation_stack: &mut Vec<String>,
+        instructions: &mut Vec<LabelledInstruction<'a>>,
+    ) -> Result<(), ExpansionError<'a>> {
+        for statement in statements.iter() {
+            match statement {
+                Statement::LabelledInstruction(instruction) => {
+                    instructions.push(instruction.clone());
+                }
+                Statement::PushConstant(name, token) => {
+                    let value = scope.get(name).ok_or((*token, "unknown constant"))?;
+                    instructions.push(LabelledInstruction::Instruction(Push(*value), token));
+                }
+                Statement::ConstantDefinition(..) | Statement::MacroDefinition(_) => (),
+                Statement::MacroInvocation(name, arguments, token) => {
+                    let definition = self.macros.get(name).ok_or((*token, "unknown macro"))?;
+                    if invocation_stack.contains(name) {
+                        return Err((token, "recursive macro invocation"));
+                    }
+                    if definition.parameters.len() != arguments.len() {
+                        return Err((token, "wrong number of macro arguments"));
+                    }
+
+                    let mut macro_scope = self.constants.clone();
+                    for (parameter, argument) in definition.parameters.iter().zip(arguments) {
+                        let value = match argument {
+                            MacroArgument::Value(value) => *value,
+                            MacroArgument::Constant(name) => {
+                                *scope.get(name).ok_or((*token, "unknown constant"))?
+                            }
+                        };
+                        macro_scope.insert(parameter.clone(), value);
+                    }
+
+                    invocation_stack.push(name.clone());
+                    self.expand(
+                        &definition.body,
+                        &macro_scope,
+                        invocation_stack,
+                        instructions,
+                    )?;
+                    invocation_stack.pop();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Constants and macros cannot be named after instructions, pseudo-instructions, or keywords.
+fn is_reserved_name(name: &str) -> bool {
+    is_instruction_name(name)
+        || is_pseudo_instruction_name(name)
+        || matches!(name, "const" | "macro" | "include")
+}
+
+fn program(s: &str) -> ParseResult<Vec<Statement>> {
     let (s, _) = comment_or_whitespace0(s)?;
-    let (s, instructions) = many0(alt((label, labelled_instruction)))(s)?;
+    let (s, statements) = many0(alt((
+        constant_definition,
+        macro_definition,
+        include_directive,
+        statement,
+    )))(s)?;
     let (s, _) = context("expecting label, instruction or eof", eof)(s)?;
 
-    Ok((s, instructions))
+    Ok((s, statements))
+}
+
+/// Parse a statement that can occur both at the top level and in the body of a macro.
+fn statement(s: &str) -> ParseResult<Statement> {
+    alt((
+        map(label, Statement::LabelledInstruction),
+        map(labelled_instruction, Statement::LabelledInstruction),
+        map(pseudo_instruction, Statement::LabelledInstruction),
+        push_constant,
+        macro_invocation,
+    ))(s)
 }
 
 fn labelled_instruction(s_instr: &str) -> ParseResult<LabelledInstruction> {
@@ -150,6 +432,95 @@ fn labelled_instruction(s_instr: &str) -> ParseResult<LabelledInstruction> {
     Ok((s, LabelledInstruction::Instruction(instr, s_instr)))
 }
 
+fn pseudo_instruction(s_instr: &str) -> ParseResult<LabelledInstruction> {
+    let neg = pseudo_instruction_token("neg", PseudoInstruction::Neg);
+    let sub = pseudo_instruction_token("sub", PseudoInstruction::Sub);
+    let is_u32 = pseudo_instruction_token("is_u32", PseudoInstruction::IsU32);
+    let lsb = pseudo_instruction_token("lsb", PseudoInstruction::Lsb);
+
+    let (s, pseudo) = alt((neg, sub, is_u32, lsb))(s_instr)?;
+    Ok((s, LabelledInstruction::PseudoInstruction(pseudo, s_instr)))
+}
+
+fn pseudo_instruction_token<'a>(
+    name: &'a str,
+    pseudo: PseudoInstruction,
+) -> impl Fn(&'a str) -> ParseResult<PseudoInstruction> {
+    move |s: &'a str| {
+        let (s, _) = token1(name)(s)?; // require space after pseudo-instruction name
+        Ok((s, pseudo))
+    }
+}
+
+fn push_constant(s_instr: &str) -> ParseResult<Statement> {
+    let (s, _) = token1("push")(s_instr)?; // require space before constant name
+    let (s, name) = identifier(s)?;
+    let (s, _) = comment_or_whitespace1(s)?; // require space after constant name
+
+    Ok((s, Statement::PushConstant(name, s_instr)))
+}
+
+fn constant_definition(s_const: &str) -> ParseResult<Statement> {
+    let (s, _) = token1("const")(s_const)?;
+    let (s, name) = cut(context("expecting constant name", identifier))(s)?;
+    let (s, _) = comment_or_whitespace0(s)?;
+    let (s, _) = cut(context("expecting '='", token0("=")))(s)?;
+    let (s, value) = cut(context("expecting constant value", field_element))(s)?;
+    let (s, _) = comment_or_whitespace1(s)?; // require space after constant value
+
+    Ok((s, Statement::ConstantDefinition(name, value, s_const)))
+}
+
+fn macro_definition(s_macro: &str) -> ParseResult<Statement> {
+    let (s, _) = token1("macro")(s_macro)?;
+    let (s, name) = cut(context("expecting macro name", identifier))(s)?;
+    let (s, _) = comment_or_whitespace0(s)?;
+    let (s, _) = cut(context("expecting '('", token0("(")))(s)?;
+    let parameter = |s| {
+        let (s, parameter) = identifier(s)?;
+        let (s, _) = comment_or_whitespace0(s)?;
+        Ok((s, parameter))
+    };
+    let (s, parameters) = separated_list0(token0(","), parameter)(s)?;
+    let (s, _) = cut(context("expecting ')'", token0(")")))(s)?;
+    let (s, _) = cut(context("expecting '{'", token0("{")))(s)?;
+    let (s, body) = many0(statement)(s)?;
+    let (s, _) = cut(context("expecting '}' or instruction", token0("}")))(s)?;
+
+    let definition = MacroDefinition {
+        name,
+        parameters,
+        body,
+        token: s_macro,
+    };
+    Ok((s, Statement::MacroDefinition(definition)))
+}
+
+fn macro_invocation(s_invocation: &str) -> ParseResult<Statement> {
+    let (s, name) = identifier(s_invocation)?;
+    let (s, _) = token0("(")(s)?;
+    let argument = |s| {
+        let value = map(field_element, MacroArgument::Value);
+        let constant = map(identifier, MacroArgument::Constant);
+        let (s, argument) = alt((value, constant))(s)?;
+        let (s, _) = comment_or_whitespace0(s)?;
+        Ok((s, argument))
+    };
+    let (s, arguments) = separated_list0(token0(","), argument)(s)?;
+    let (s, _) = cut(context("expecting ')'", token0(")")))(s)?;
+
+    Ok((s, Statement::MacroInvocation(name, arguments, s_invocation)))
+}
+
+/// Includes are resolved before parsing; see [`resolve_includes`].
+fn include_directive(s: &str) -> ParseResult<Statement> {
+    let (s, _) = token1("include")(s)?;
+    cut(context(
+        "include is only possible when reading from a file",
+        fail,
+    ))(s)
+}
+
 fn label(label_s: &str) -> ParseResult<LabelledInstruction> {
     let (s, addr) = label_addr(label_s)?;
     let (s, _) = token0(":")(s)?; // don't require space after ':'
@@ -169,6 +540,8 @@ fn an_instruction(s: &str) -> ParseResult<AnInstruction<String>> {
     let pop = instruction("pop", Pop);
     let push = push_instruction();
     let divine = instruction("divine", Divine(None));
+    let divine_inverse = instruction("divine_inverse", Divine(Some(DivinationHint::Inverse)));
+    let divine_lsb = instruction("divine_lsb", Divine(Some(DivinationHint::Lsb)));
     let dup = dup_instruction();
     let swap = swap_instruction();
 
@@ -238,7 +611,7 @@ fn an_instruction(s: &str) -> ParseResult<AnInstruction<String>> {
     // Successfully parsing "assert" before trying "assert_vector" can lead to
     // picking the wrong one. By trying them in the order of longest first, less
     // backtracking is necessary.
-    let syntax_ambiguous = alt((assert_vector, assert_, divine));
+    let syntax_ambiguous = alt((assert_vector, assert_, divine_inverse, divine_lsb, divine));
 
     alt((
         opstack_manipulation,
@@ -375,6 +748,17 @@ fn stack_register(s: &str) -> ParseResult<Ord16> {
     Ok((s, stack_register))
 }
 
+/// Parse the name of a constant, macro, or macro parameter
+///
+/// Unlike label addresses, names cannot start with a digit, so that they can be told apart from
+/// field elements.
+fn identifier(s_orig: &str) -> ParseResult<String> {
+    let (s, first) = take_while_m_n(1, 1, |c: char| c.is_alphabetic() || c == '_')(s_orig)?;
+    let (s, rest) = take_while(is_label_char)(s)?;
+
+    Ok((s, format!("{first}{rest}")))
+}
+
 /// Parse a label address
 ///
 /// This is used in "`<label>:`" and in "`call <label>`".
@@ -446,6 +830,8 @@ fn token1<'a>(token: &'a str) -> impl Fn(&'a str) -> ParseResult<()> {
 mod parser_tests {
     use itertools::Itertools;
 
+    use num_traits::One;
+    use num_traits::Zero;
     use rand::distributions::WeightedIndex;
     use rand::prelude::*;
     use rand::Rng;
@@ -572,6 +958,10 @@ mod parser_tests {
             "xbmul",
             "read_io",
             "write_io",
+            "neg",
+            "sub",
+            "is_u32",
+            "lsb",
         ];
 
         // Test simple instructions, dup* and swap* less frequently.
@@ -867,6 +1257,274 @@ mod parser_tests {
         })
     }
 
+    #[test]
+    fn parse_program_pseudo_instruction_test() {
+        let minus_one = -BFieldElement::one();
+        parse_program_prop(TestCase {
+            input: "neg sub is_u32 lsb",
+            expected: Program::new(&[
+                Instruction(Push(minus_one), ""),
+                Instruction(Mul, ""),
+                Instruction(Swap(ST1), ""),
+                Instruction(Push(minus_one), ""),
+                Instruction(Mul, ""),
+                Instruction(Add, ""),
+                Instruction(Split, ""),
+                Instruction(Pop, ""),
+                Instruction(Push(BFieldElement::zero()), ""),
+                Instruction(Eq, ""),
+                Instruction(Push(BFieldElement::new(2)), ""),
+                Instruction(Swap(ST1), ""),
+                Instruction(Div, ""),
+            ]),
+            message: "pseudo-instructions are expanded",
+        });
+
+        parse_program_prop(TestCase {
+            input: "call lsb lsb: lsb return",
+            expected: Program::new(&[
+                Instruction(Call("lsb".to_string()), ""),
+                Label("lsb".to_string(), ""),
+                PseudoInstruction(crate::instruction::PseudoInstruction::Lsb, ""),
+                Instruction(Return, ""),
+            ]),
+            message: "labels can be named after pseudo-instructions",
+        });
+
+        let program = parse("neg halt").unwrap();
+        assert_eq!(
+            vec![
+                PseudoInstruction(crate::instruction::PseudoInstruction::Neg, ""),
+                Instruction(Halt, "")
+            ],
+            program
+        );
+    }
+
+    #[test]
+    fn parse_program_divination_hint_test() {
+        parse_program_prop(TestCase {
+            input: "divine_inverse divine_lsb divine",
+            expected: Program::new(&[
+                Instruction(Divine(Some(DivinationHint::Inverse)), ""),
+                Instruction(Divine(Some(DivinationHint::Lsb)), ""),
+                Instruction(Divine(None), ""),
+            ]),
+            message: "hinted divines are parsed",
+        });
+
+        let program = Program::from_code("divine_inverse divine_lsb divine").unwrap();
+        let divine = Divine::<BFieldElement>(None).opcode_b();
+        assert_eq!(vec![divine; 3], program.to_bwords());
+        assert_eq!("divine_inverse", program.instructions[0].to_string());
+
+        parse_program_neg_prop(NegativeTestCase {
+            input: "divine_lsb: halt",
+            expected_error: "label cannot be named after instruction",
+            expected_error_count: 1,
+            message: "labels cannot be named after hinted divines",
+        });
+    }
+
+    #[test]
+    fn parse_program_constant_test() {
+        parse_program_prop(TestCase {
+            input: "const N = 42 push N const M = -1 push M",
+            expected: Program::new(&[
+                Instruction(Push(BFieldElement::new(42)), ""),
+                Instruction(Push(-BFieldElement::one()), ""),
+            ]),
+            message: "constants can be pushed",
+        });
+
+        parse_program_prop(TestCase {
+            input: "push N // N is defined later\nconst N = 7",
+            expected: Program::new(&[Instruction(Push(BFieldElement::new(7)), "")]),
+            message: "constants can be used before their definition",
+        });
+
+        parse_program_neg_prop(NegativeTestCase {
+            input: "push N",
+            expected_error: "unknown constant",
+            expected_error_count: 1,
+            message: "constants must be defined",
+        });
+
+        parse_program_neg_prop(NegativeTestCase {
+            input: "const N = 1 const N = 2 push N",
+            expected_error: "duplicate constant",
+            expected_error_count: 1,
+            message: "constants cannot be defined twice",
+        });
+
+        parse_program_neg_prop(NegativeTestCase {
+            input: "const pop = 1",
+            expected_error: "constant cannot be named after instruction",
+            expected_error_count: 1,
+            message: "constant names may not overlap with instruction names",
+        });
+
+        parse_program_neg_prop(NegativeTestCase {
+            input: "const N 1",
+            expected_error: "expecting '='",
+            expected_error_count: 1,
+            message: "constant definitions require '='",
+        });
+    }
+
+    #[test]
+    fn parse_program_macro_test() {
+        parse_program_prop(TestCase {
+            input: "
+                const N = 3
+                macro push_twice(x) { push x push x }
+                macro push_sum(x, y) {
+                    push_twice(x) // macros can invoke macros
+                    pop
+                    push y add
+                }
+                push_twice(N) push_sum(1, -2) push_sum(N, N)
+            ",
+            expected: Program::new(&[
+                Instruction(Push(BFieldElement::new(3)), ""),
+                Instruction(Push(BFieldElement::new(3)), ""),
+                Instruction(Push(BFieldElement::new(1)), ""),
+                Instruction(Push(BFieldElement::new(1)), ""),
+                Instruction(Pop, ""),
+                Instruction(Push(-BFieldElement::new(2)), ""),
+                Instruction(Add, ""),
+                Instruction(Push(BFieldElement::new(3)), ""),
+                Instruction(Push(BFieldElement::new(3)), ""),
+                Instruction(Pop, ""),
+                Instruction(Push(BFieldElement::new(3)), ""),
+                Instruction(Add, ""),
+            ]),
+            message: "macros are expanded with their arguments",
+        });
+
+        parse_program_prop(TestCase {
+            input: "const x = 1 macro push_it(x) { push x } push_it(2) push x",
+            expected: Program::new(&[
+                Instruction(Push(BFieldElement::new(2)), ""),
+                Instruction(Push(BFieldElement::new(1)), ""),
+            ]),
+            message: "macro parameters shadow constants",
+        });
+
+        parse_program_prop(TestCase {
+            input: "macro loop() { foo: call foo } loop()",
+            expected: Program::new(&[
+                Label("foo".to_string(), ""),
+                Instruction(Call("foo".to_string()), ""),
+            ]),
+            message: "macros without parameters can contain labels",
+        });
+
+        parse_program_neg_prop(NegativeTestCase {
+            input: "macro loop() { foo: call foo } loop() loop()",
+            expected_error: "duplicate label",
+            expected_error_count: 1,
+            message: "labels in macros are defined for every invocation",
+        });
+
+        parse_program_neg_prop(NegativeTestCase {
+            input: "push_twice(1)",
+            expected_error: "unknown macro",
+            expected_error_count: 1,
+            message: "macros must be defined",
+        });
+
+        parse_program_neg_prop(NegativeTestCase {
+            input: "macro push_twice(x) { push x push x } push_twice(1, 2)",
+            expected_error: "wrong number of macro arguments",
+            expected_error_count: 1,
+            message: "macros must be invoked with the right number of arguments",
+        });
+
+        parse_program_neg_prop(NegativeTestCase {
+            input: "macro push_it(x) { push x push y } push_it(1)",
+            expected_error: "unknown constant",
+            expected_error_count: 1,
+            message: "macros can only use their own parameters and constants",
+        });
+
+        parse_program_neg_prop(NegativeTestCase {
+            input: "macro a() { b() } macro b() { a() } a()",
+            expected_error: "recursive macro invocation",
+            expected_error_count: 1,
+            message: "macros cannot be recursive",
+        });
+
+        parse_program_neg_prop(NegativeTestCase {
+            input: "macro neg() { push -1 mul }",
+            expected_error: "macro cannot be named after instruction",
+            expected_error_count: 1,
+            message: "macro names may not overlap with pseudo-instruction names",
+        });
+
+        parse_program_neg_prop(NegativeTestCase {
+            input: "macro push_it(x) { push x",
+            expected_error: "expecting '}' or instruction",
+            expected_error_count: 1,
+            message: "macro bodies must be closed",
+        });
+
+        parse_program_neg_prop(NegativeTestCase {
+            input: "include \"library.tasm\"",
+            expected_error: "include is only possible when reading from a file",
+            expected_error_count: 1,
+            message: "includes cannot be resolved when parsing a string",
+        });
+    }
+
+    #[test]
+    fn resolve_includes_test() {
+        let directory = std::env::temp_dir().join(format!("tasm-includes-{}", random::<u64>()));
+        fs::create_dir_all(directory.join("lib")).unwrap();
+        let write = |name: &str, code: &str| fs::write(directory.join(name), code).unwrap();
+
+        write(
+            "main.tasm",
+            "include \"lib/a.tasm\"\ninclude \"lib/b.tasm\"\nhalt\n",
+        );
+        write("lib/a.tasm", "include \"common.tasm\" // shared\npush 1\n");
+        write("lib/b.tasm", "  include \"common.tasm\"\npush 2\n");
+        write("lib/common.tasm", "const ONE = 1\n");
+
+        let (code, line_origins) =
+            resolve_includes_with_origins(&directory.join("main.tasm")).unwrap();
+        let (program, source_map) = Program::from_code_with_source_map(&code).unwrap();
+        assert_eq!(Program::from_code("push 1 push 2 halt").unwrap(), program);
+        assert_eq!(code.lines().count(), line_origins.len());
+
+        let source_map = source_map.with_line_origins(&line_origins);
+        let file_and_line = |address| {
+            let location = source_map.location(address).unwrap();
+            (location.file.clone().unwrap(), location.line)
+        };
+        assert_eq!((directory.join("lib/a.tasm"), 2), file_and_line(0));
+        assert_eq!((directory.join("lib/b.tasm"), 2), file_and_line(2));
+        assert_eq!((directory.join("main.tasm"), 3), file_and_line(4));
+        assert_eq!(
+            code,
+            resolve_includes(&directory.join("main.tasm")).unwrap()
+        );
+
+        write("lib/common.tasm", "include \"a.tasm\"\n");
+        let error = resolve_includes(&directory.join("main.tasm")).unwrap_err();
+        let error_message = error.to_string();
+        assert!(
+            error_message.starts_with("include cycle"),
+            "{error_message}"
+        );
+        assert_eq!(2, error_message.matches(" -> ").count(), "{error_message}");
+
+        let error = resolve_includes(&directory.join("missing.tasm")).unwrap_err();
+        assert!(error.to_string().contains("missing.tasm"));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
     #[test]
     fn parse_program_test() {
         for size in 0..100 {
@@ -4,8 +4,11 @@ use std::io::Cursor;
 
 use twenty_first::shared_math::b_field_element::BFieldElement;
 
-use crate::instruction::{convert_labels, Instruction, LabelledInstruction};
+use crate::disassembler::decode;
+use crate::instruction::{convert_labels_with_origins, Instruction, LabelledInstruction};
 use crate::parser::parse;
+use crate::parser::parse_with_offsets;
+use crate::source_map::SourceMap;
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Program {
@@ -63,12 +66,18 @@ impl IntoIterator for Program {
 impl Program {
     /// Create a `Program` from a slice of `Instruction`.
     pub fn new(input: &[LabelledInstruction]) -> Self {
-        let instructions = convert_labels(input)
-            .iter()
-            .flat_map(|instr| vec![*instr; instr.size()])
-            .collect::<Vec<_>>();
+        Self::new_with_origins(input).0
+    }
+
+    /// Like [`Program::new`], but also returns the origin of every address: the index in `input`
+    /// of the `LabelledInstruction` that the instruction at that address stems from.
+    pub fn new_with_origins(input: &[LabelledInstruction]) -> (Self, Vec<usize>) {
+        let (instructions, origins) = convert_labels_with_origins(input)
+            .into_iter()
+            .flat_map(|(instr, origin)| vec![(instr, origin); instr.size()])
+            .unzip();
 
-        Program { instructions }
+        (Program { instructions }, origins)
     }
 
     /// Create a `Program` by parsing source code.
@@ -78,6 +87,21 @@ impl Program {
             .map_err(|err| anyhow::anyhow!("{}", err))
     }
 
+    /// Create a `Program` by parsing source code, together with a `SourceMap` that maps every
+    /// address of the `Program` back to the source code.
+    pub fn from_code_with_source_map(code: &str) -> Result<(Self, SourceMap)> {
+        let (labelled_instructions, offsets) =
+            parse_with_offsets(code).map_err(|err| anyhow::anyhow!("{}", err))?;
+        let (program, origins) = Program::new_with_origins(&labelled_instructions);
+        let source_map = SourceMap::new(code, &labelled_instructions, &offsets, &origins);
+        Ok((program, source_map))
+    }
+
+    /// Create a `Program` from its encoding as produced by [`Program::to_bwords`].
+    pub fn from_bwords(words: &[BFieldElement]) -> Result<Self> {
+        Ok(decode(words)?)
+    }
+
     /// Convert a `Program` to a `Vec<BFieldElement>`.
     ///
     /// Every single-word instruction is converted to a single word.
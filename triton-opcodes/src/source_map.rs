@@ -0,0 +1,218 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use crate::instruction::LabelledInstruction;
+
+/// The position of an instruction in the source code it was parsed from.
+///
+/// Lines and columns are counted from 1.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+    /// The file the instruction was read from, if known.
+    pub file: Option<PathBuf>,
+    pub line: usize,
+    pub column: usize,
+
+    /// The closest label at or before the instruction, if any.
+    pub label: Option<String>,
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}, ", file.display())?;
+        }
+        write!(f, "line {}, column {}", self.line, self.column)?;
+        if let Some(label) = &self.label {
+            write!(f, ", in {label}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Where a line of code with resolved includes comes from: the file it was read from, and its
+/// line number in that file, counted from 1.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LineOrigin {
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// A `SourceMap` maps every address of a `Program` back to the source code location of the
+/// corresponding instruction. Like the `Program`, it contains duplicate entries for
+/// instructions with a size of 2, so that the index corresponds to the VM's
+/// `instruction_pointer`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    locations: Vec<Option<SourceLocation>>,
+}
+
+impl SourceMap {
+    /// Create a `SourceMap` from the source code and the `LabelledInstruction`s parsed from it.
+    ///
+    /// The `offsets` are the byte offsets in `code` at which the `LabelledInstruction`s start, as
+    /// returned by [`parse_with_offsets`](crate::parser::parse_with_offsets). The `origins` map
+    /// every address of the `Program` to the index of the `LabelledInstruction` it stems from, as
+    /// returned by [`Program::new_with_origins`](crate::program::Program::new_with_origins).
+    pub fn new(
+        code: &str,
+        program: &[LabelledInstruction],
+        offsets: &[usize],
+        origins: &[usize],
+    ) -> Self {
+        let line_starts = line_starts(code);
+        let mut enclosing_label = None;
+        let mut enclosing_labels = Vec::with_capacity(program.len());
+        for labelled_instruction in program.iter() {
+            if let LabelledInstruction::Label(label_name, _) = labelled_instruction {
+                enclosing_label = Some(label_name.clone());
+            }
+            enclosing_labels.push(enclosing_label.clone());
+        }
+
+        let locations = origins
+            .iter()
+            .map(|&origin| {
+                let offset = *offsets.get(origin)?;
+                let line_index = line_starts.partition_point(|&start| start <= offset) - 1;
+                let line_start = line_starts[line_index];
+                Some(SourceLocation {
+                    file: None,
+                    line: line_index + 1,
+                    column: code.get(line_start..offset)?.chars().count() + 1,
+                    label: enclosing_labels[origin].clone(),
+                })
+            })
+            .collect();
+
+        SourceMap { locations }
+    }
+
+    /// Point the locations to the files the code was read from, given the origin of every line of
+    /// the code with resolved includes, as returned by
+    /// [`resolve_includes_with_origins`](crate::parser::resolve_includes_with_origins).
+    pub fn with_line_origins(mut self, line_origins: &[LineOrigin]) -> Self {
+        for location in self.locations.iter_mut().flatten() {
+            if let Some(origin) = line_origins.get(location.line - 1) {
+                location.file = Some(origin.file.clone());
+                location.line = origin.line;
+            }
+        }
+        self
+    }
+
+    /// The source code location of the instruction at `address`.
+    pub fn location(&self, address: usize) -> Option<&SourceLocation> {
+        self.locations.get(address)?.as_ref()
+    }
+
+    /// The closest label at or before the instruction at `address`.
+    pub fn enclosing_label(&self, address: usize) -> Option<&str> {
+        self.location(address)?.label.as_deref()
+    }
+
+    pub fn len(&self) -> usize {
+        self.locations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.locations.is_empty()
+    }
+}
+
+/// The byte offsets at which the lines of `code` start.
+fn line_starts(code: &str) -> Vec<usize> {
+    let newlines = code.match_indices('\n').map(|(index, _)| index + 1);
+    std::iter::once(0).chain(newlines).collect()
+}
+
+#[cfg(test)]
+mod source_map_tests {
+    use crate::program::Program;
+
+    use super::*;
+
+    #[test]
+    fn source_map_points_to_instructions_test() {
+        let code = "push 1\ncall foo\nhalt\n\nfoo:\n    // comment\n    pop return\n";
+        let (_, source_map) = Program::from_code_with_source_map(code).unwrap();
+
+        // push 1 and call foo have a size of 2
+        assert_eq!(7, source_map.len());
+
+        let push_location = source_map.location(0).unwrap();
+        assert_eq!((1, 1), (push_location.line, push_location.column));
+        assert_eq!(None, push_location.label);
+        assert_eq!(source_map.location(0), source_map.location(1));
+
+        let call_location = source_map.location(2).unwrap();
+        assert_eq!((2, 1), (call_location.line, call_location.column));
+        assert_eq!(None, source_map.enclosing_label(4));
+
+        let pop_location = source_map.location(5).unwrap();
+        assert_eq!((7, 5), (pop_location.line, pop_location.column));
+        assert_eq!(Some("foo"), source_map.enclosing_label(5));
+
+        let return_location = source_map.location(6).unwrap();
+        assert_eq!((7, 9), (return_location.line, return_location.column));
+        assert_eq!("line 7, column 9, in foo", return_location.to_string());
+
+        assert_eq!(None, source_map.location(7));
+    }
+
+    #[test]
+    fn pseudo_instructions_and_macros_map_to_their_source_test() {
+        let code = "neg\nmacro twice() { dup0 add }\ntwice() halt\n";
+        let (program, source_map) = Program::from_code_with_source_map(code).unwrap();
+        assert_eq!(program.len(), source_map.len());
+
+        let line_and_column = |address| {
+            let location = source_map.location(address).unwrap();
+            (location.line, location.column)
+        };
+
+        // neg expands to push -1 and mul, and dup0 has a size of 2
+        for address in 0..3 {
+            assert_eq!((1, 1), line_and_column(address));
+        }
+        assert_eq!((2, 17), line_and_column(3));
+        assert_eq!((2, 17), line_and_column(4));
+        assert_eq!((2, 22), line_and_column(5));
+        assert_eq!((3, 9), line_and_column(6));
+    }
+
+    #[test]
+    fn line_origins_point_locations_to_files_test() {
+        let code = "push 1\n\nfoo:\n    pop\n";
+        let (_, source_map) = Program::from_code_with_source_map(code).unwrap();
+        let line_origins = vec![
+            LineOrigin {
+                file: PathBuf::from("main.tasm"),
+                line: 1,
+            },
+            LineOrigin {
+                file: PathBuf::from("main.tasm"),
+                line: 2,
+            },
+            LineOrigin {
+                file: PathBuf::from("lib.tasm"),
+                line: 1,
+            },
+            LineOrigin {
+                file: PathBuf::from("lib.tasm"),
+                line: 2,
+            },
+        ];
+        let source_map = source_map.with_line_origins(&line_origins);
+
+        let push_location = source_map.location(0).unwrap();
+        assert_eq!("main.tasm, line 1, column 1", push_location.to_string());
+
+        let pop_location = source_map.location(2).unwrap();
+        assert_eq!(Some(PathBuf::from("lib.tasm")), pop_location.file);
+        assert_eq!(
+            "lib.tasm, line 2, column 5, in foo",
+            pop_location.to_string()
+        );
+    }
+}
@@ -9,6 +9,7 @@ use triton_profiler::prof_stop;
 use triton_profiler::triton_profiler::Report;
 use triton_profiler::triton_profiler::TritonProfiler;
 use triton_vm::proof::Claim;
+use triton_vm::proof::ClaimedProgram;
 use triton_vm::shared_tests::FIBONACCI_VIT;
 use triton_vm::stark::Stark;
 use triton_vm::table::master_table::MasterBaseTable;
@@ -39,11 +40,11 @@ fn prove_fib_100(criterion: &mut Criterion) {
     let padded_height = MasterBaseTable::padded_height(&aet, &instructions);
     let claim = Claim {
         input,
-        program: instructions,
+        program: ClaimedProgram::Code(instructions),
         output,
         padded_height,
     };
-    let stark = Stark::new(claim, Default::default());
+    let stark = Stark::new(claim, Default::default()).unwrap();
     //start the profiler
     prof_start!(maybe_profiler, "prove");
     let _proof = stark.prove(aet.clone(), &mut maybe_profiler);
@@ -6,6 +6,7 @@ use triton_opcodes::program::Program;
 use triton_profiler::triton_profiler::Report;
 use triton_profiler::triton_profiler::TritonProfiler;
 use triton_vm::proof::Claim;
+use triton_vm::proof::ClaimedProgram;
 use triton_vm::shared_tests::save_proof;
 use triton_vm::stark::Stark;
 use triton_vm::stark::StarkParameters;
@@ -34,12 +35,12 @@ fn prove_halt(_criterion: &mut Criterion) {
     let padded_height = MasterBaseTable::padded_height(&aet, &code);
     let claim = Claim {
         input: vec![],
-        program: code,
+        program: ClaimedProgram::Code(code),
         output,
         padded_height,
     };
     let parameters = StarkParameters::default();
-    let stark = Stark::new(claim, parameters);
+    let stark = Stark::new(claim, parameters).unwrap();
     let proof = stark.prove(aet, &mut maybe_profiler);
 
     if let Some(profiler) = &mut maybe_profiler {
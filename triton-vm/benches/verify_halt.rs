@@ -9,6 +9,7 @@ use triton_profiler::triton_profiler::TritonProfiler;
 
 use triton_opcodes::program::Program;
 use triton_vm::proof::Claim;
+use triton_vm::proof::ClaimedProgram;
 use triton_vm::shared_tests::load_proof;
 use triton_vm::shared_tests::proof_file_exists;
 use triton_vm::shared_tests::save_proof;
@@ -42,11 +43,11 @@ fn verify_halt(criterion: &mut Criterion) {
         let padded_height = proof.padded_height();
         let claim = Claim {
             input: vec![],
-            program: instructions,
+            program: ClaimedProgram::Code(instructions),
             output: vec![],
             padded_height,
         };
-        let stark = Stark::new(claim, stark_parameters);
+        let stark = Stark::new(claim, stark_parameters).unwrap();
         (proof, stark)
     } else {
         let (aet, output, err) = simulate_no_input(&program);
@@ -57,11 +58,11 @@ fn verify_halt(criterion: &mut Criterion) {
         let padded_height = MasterBaseTable::padded_height(&aet, &instructions);
         let claim = Claim {
             input: vec![],
-            program: instructions,
+            program: ClaimedProgram::Code(instructions),
             output,
             padded_height,
         };
-        let stark = Stark::new(claim, stark_parameters);
+        let stark = Stark::new(claim, stark_parameters).unwrap();
         let proof = stark.prove(aet, &mut None);
         if let Err(e) = save_proof(filename, proof.clone()) {
             panic!("Problem! could not save proof to disk: {e:?}");
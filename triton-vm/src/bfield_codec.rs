@@ -37,6 +37,41 @@ impl BFieldCodec for BFieldElement {
     }
 }
 
+impl BFieldCodec for u32 {
+    fn decode(sequence: &[BFieldElement]) -> Result<Box<Self>> {
+        if sequence.len() != 1 {
+            bail!("trying to decode more or less than one BFieldElements as one u32");
+        }
+        let Ok(value) = u32::try_from(sequence[0].value()) else {
+            bail!("trying to decode BFieldElement that is not a u32 as u32");
+        };
+        Ok(Box::new(value))
+    }
+
+    fn encode(&self) -> Vec<BFieldElement> {
+        vec![BFieldElement::new(*self as u64)]
+    }
+}
+
+/// A `u64` is encoded as two `u32` limbs, the lower limb first, like instruction `split` puts them
+/// on the stack.
+impl BFieldCodec for u64 {
+    fn decode(sequence: &[BFieldElement]) -> Result<Box<Self>> {
+        if sequence.len() != 2 {
+            bail!("trying to decode more or less than two BFieldElements as one u64");
+        }
+        let lo = *u32::decode(&sequence[0..1])?;
+        let hi = *u32::decode(&sequence[1..2])?;
+        Ok(Box::new(((hi as u64) << 32) | lo as u64))
+    }
+
+    fn encode(&self) -> Vec<BFieldElement> {
+        let lo = *self & 0xffff_ffff;
+        let hi = *self >> 32;
+        vec![BFieldElement::new(lo), BFieldElement::new(hi)]
+    }
+}
+
 impl BFieldCodec for XFieldElement {
     // FIXME: Use `XFieldElement::try_into()`.
     fn decode(sequence: &[BFieldElement]) -> Result<Box<Self>> {
@@ -445,6 +480,22 @@ mod bfield_codec_tests {
         }
     }
 
+    #[test]
+    fn test_encode_decode_random_u32_and_u64() {
+        let mut rng = thread_rng();
+        for _ in 1..=10 {
+            let value = rng.next_u32();
+            assert_eq!(value, *u32::decode(&value.encode()).unwrap());
+            let value = rng.next_u64();
+            assert_eq!(value, *u64::decode(&value.encode()).unwrap());
+        }
+
+        let not_a_u32 = BFieldElement::new(1 << 32);
+        assert!(u32::decode(&[not_a_u32]).is_err());
+        assert!(u64::decode(&[BFieldElement::new(0), not_a_u32]).is_err());
+        assert!(u64::decode(&[BFieldElement::new(0)]).is_err());
+    }
+
     #[test]
     fn test_encode_decode_random_xfieldelement() {
         for _ in 1..=10 {
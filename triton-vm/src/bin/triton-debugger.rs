@@ -0,0 +1,220 @@
+//! An interactive debugger for Triton assembly, built on top of `triton_vm::debugger`.
+//!
+//! Run `triton-debugger <program.tasm>` and type `help` at the prompt for a list of commands.
+
+use std::fs;
+use std::io::BufRead;
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use anyhow::Result;
+use itertools::Itertools;
+use structopt::StructOpt;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use triton_opcodes::ord_n::Ord16;
+use triton_opcodes::parser::parse_with_offsets;
+use triton_opcodes::parser::resolve_includes_with_origins;
+use triton_opcodes::program::Program;
+use triton_opcodes::source_map::SourceMap;
+use triton_vm::debugger::Debugger;
+use triton_vm::debugger::StopReason;
+use triton_vm::debugger::Watchpoint;
+use triton_vm::error::backtrace;
+use triton_vm::input::parse_bfield_element;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "triton-debugger",
+    about = "Step through the execution of a TASM program"
+)]
+struct Args {
+    /// The program to debug
+    #[structopt(parse(from_os_str))]
+    program: PathBuf,
+
+    /// File with public input, whitespace-separated field elements
+    #[structopt(long, parse(from_os_str))]
+    input: Option<PathBuf>,
+
+    /// File with secret input, whitespace-separated field elements
+    #[structopt(long, parse(from_os_str))]
+    secret_input: Option<PathBuf>,
+}
+
+const HELP: &str = "\
+commands:
+  s, step                  execute one instruction
+  n, next                  execute one instruction, stepping over calls
+  o, out                   run until the current function returns
+  c, continue              run until a breakpoint or watchpoint is hit
+  b, break <addr|label>    set a breakpoint
+  d, delete <addr|label>   remove a breakpoint
+  w, watch <ram ADDR|stN>  stop when the watched value changes
+  u, unwatch <ram ADDR|stN>
+  p, print                 print the current state
+  stack                    print the op stack
+  jumps                    print the jump stack
+  bt, backtrace            print the call stack with source locations
+  ram                      print all initialized RAM cells
+  sponge                   print the sponge state
+  output                   print the output written so far
+  h, help                  print this message
+  q, quit                  exit the debugger";
+
+#[paw::main]
+fn main(args: Args) -> Result<()> {
+    let (code, line_origins) = resolve_includes_with_origins(&args.program)?;
+    let (instructions, offsets) = parse_with_offsets(&code).map_err(|err| anyhow!("{err}"))?;
+    let (program, origins) = Program::new_with_origins(&instructions);
+    let source_map =
+        SourceMap::new(&code, &instructions, &offsets, &origins).with_line_origins(&line_origins);
+    let stdin = read_input_file(args.input)?;
+    let secret_in = read_input_file(args.secret_input)?;
+    let mut debugger = Debugger::new(&program, stdin, secret_in)
+        .with_labels(&instructions)
+        .with_source_map(source_map);
+
+    print_location(&debugger);
+    let stdin = std::io::stdin();
+    loop {
+        print!("(tdb) ");
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let tokens = line.split_whitespace().collect_vec();
+        let Some((&command, arguments)) = tokens.split_first() else {
+            continue;
+        };
+        match command {
+            "q" | "quit" => return Ok(()),
+            "h" | "help" => println!("{HELP}"),
+            "s" | "step" => report(&debugger.step(), &debugger),
+            "n" | "next" => report(&debugger.step_over(), &debugger),
+            "o" | "out" => report(&debugger.step_out(), &debugger),
+            "c" | "continue" => report(&debugger.run(), &debugger),
+            "b" | "break" => match breakpoint_address(&debugger, arguments) {
+                Ok(address) => {
+                    debugger.add_breakpoint(address);
+                    println!("breakpoint set at address {address}");
+                }
+                Err(err) => println!("{err}"),
+            },
+            "d" | "delete" => match breakpoint_address(&debugger, arguments) {
+                Ok(address) if debugger.remove_breakpoint(address) => {
+                    println!("breakpoint at address {address} removed")
+                }
+                Ok(address) => println!("no breakpoint at address {address}"),
+                Err(err) => println!("{err}"),
+            },
+            "w" | "watch" => match parse_watchpoint(arguments) {
+                Ok(watchpoint) => {
+                    debugger.add_watchpoint(watchpoint);
+                    println!("watching {watchpoint}");
+                }
+                Err(err) => println!("{err}"),
+            },
+            "u" | "unwatch" => match parse_watchpoint(arguments) {
+                Ok(watchpoint) if debugger.remove_watchpoint(watchpoint) => {
+                    println!("stopped watching {watchpoint}")
+                }
+                Ok(watchpoint) => println!("{watchpoint} is not being watched"),
+                Err(err) => println!("{err}"),
+            },
+            "p" | "print" => println!("{}", debugger.state()),
+            "stack" => {
+                let stack = &debugger.state().op_stack.stack;
+                println!("[{}]", stack.iter().rev().join(", "));
+            }
+            "jumps" => {
+                for (origin, destination) in debugger.state().jump_stack.iter().rev() {
+                    let destination_label = debugger
+                        .label_at(destination.value() as usize)
+                        .unwrap_or("?");
+                    println!("{destination_label} ({destination}), called from {origin}");
+                }
+            }
+            "bt" | "backtrace" => {
+                let state = debugger.state();
+                let source_map = debugger.source_map().expect("source map is always set");
+                let frames = backtrace(state.instruction_pointer, &state.jump_stack, source_map);
+                for (depth, frame) in frames.iter().enumerate() {
+                    println!("#{depth} {frame}");
+                }
+            }
+            "ram" => {
+                let ram = &debugger.state().ram;
+                for address in ram.keys().sorted_by_key(|address| address.value()) {
+                    println!("{address}: {}", ram[address]);
+                }
+                println!("ramp: {}", debugger.state().ramp);
+            }
+            "sponge" => println!("[{}]", debugger.state().sponge_state.iter().join(", ")),
+            "output" => println!("[{}]", debugger.stdout().iter().join(", ")),
+            _ => println!("unknown command “{command}”, type “help” for a list of commands"),
+        }
+    }
+}
+
+fn read_input_file(path: Option<PathBuf>) -> Result<Vec<BFieldElement>> {
+    let Some(path) = path else {
+        return Ok(vec![]);
+    };
+    fs::read_to_string(path)?
+        .split_whitespace()
+        .map(parse_bfield_element)
+        .collect()
+}
+
+fn report(stop_reason: &StopReason, debugger: &Debugger) {
+    match (stop_reason, debugger.fault()) {
+        (StopReason::Faulted, Some(fault)) => println!("faulted: {fault}"),
+        (StopReason::Stepped, _) => (),
+        _ => println!("{stop_reason}"),
+    }
+    print_location(debugger);
+}
+
+fn print_location(debugger: &Debugger) {
+    let state = debugger.state();
+    let instruction_pointer = state.instruction_pointer;
+    if let Some(label) = debugger.label_at(instruction_pointer) {
+        println!("{label}:");
+    }
+    match state.current_instruction() {
+        Ok(instruction) => println!("{instruction_pointer:>6}  {instruction}"),
+        Err(_) => println!("{instruction_pointer:>6}  <end of program>"),
+    }
+}
+
+fn breakpoint_address(debugger: &Debugger, arguments: &[&str]) -> Result<usize> {
+    let Some(&argument) = arguments.first() else {
+        return Err(anyhow!("expected an address or a label"));
+    };
+    match argument.parse() {
+        Ok(address) => Ok(address),
+        Err(_) => debugger
+            .labels()
+            .get(argument)
+            .copied()
+            .ok_or_else(|| anyhow!("unknown label “{argument}”")),
+    }
+}
+
+fn parse_watchpoint(arguments: &[&str]) -> Result<Watchpoint> {
+    match arguments {
+        ["ram", address] => Ok(Watchpoint::Ram(BFieldElement::new(address.parse()?))),
+        [register] => {
+            let index: usize = register
+                .strip_prefix("st")
+                .ok_or_else(|| anyhow!("expected “ram <address>” or “st<n>”"))?
+                .parse()?;
+            let position = Ord16::try_from(index).map_err(|err| anyhow!("{err}"))?;
+            Ok(Watchpoint::OpStack(position))
+        }
+        _ => Err(anyhow!("expected “ram <address>” or “st<n>”")),
+    }
+}
@@ -0,0 +1,477 @@
+//! The Triton VM command-line tool.
+//!
+//! - `triton-vm run <program.tasm>` executes a program and prints its output.
+//! - `triton-vm trace <program.tasm>` prints the state of the VM after every instruction.
+//! - `triton-vm prove <program.tasm> --proof <file> --claim <file>` proves correct execution.
+//! - `triton-vm verify --proof <file> --claim <file>` verifies a proof against a claim,
+//!   with the parameters and hash function stated in the proof's header. Parameters giving less
+//!   than `--min-security-level` bits of conjectured soundness, 160 by default, are rejected.
+//! - `triton-vm disassemble <claim>` prints the program a claim is about as Triton assembly.
+//! - `triton-vm profile <program.tasm> [--folded <file>]` reports where cycles and table rows go.
+//!
+//! Input files contain whitespace-separated field elements, each in decimal notation, in
+//! hexadecimal notation with prefix `0x`, or as a negative decimal like `-1`, which denotes
+//! the additive inverse.
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::exit;
+
+use anyhow::anyhow;
+use anyhow::bail;
+use anyhow::Context;
+use anyhow::Result;
+use itertools::Itertools;
+use structopt::StructOpt;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::rescue_prime_regular::RescuePrimeRegular;
+
+use triton_opcodes::disassembler::disassemble;
+use triton_opcodes::parser::resolve_includes_with_origins;
+use triton_opcodes::program::Program;
+use triton_opcodes::source_map::SourceMap;
+use triton_vm::input::parse_bfield_element;
+use triton_vm::proof::Claim;
+use triton_vm::proof::ClaimedProgram;
+use triton_vm::proof::HashFunction;
+use triton_vm::proof::Proof;
+use triton_vm::soundness::SoundnessAnalysis;
+use triton_vm::stark::Stark;
+use triton_vm::stark::StarkParameters;
+use triton_vm::state::VMState;
+use triton_vm::table::master_table::MasterBaseTable;
+use triton_vm::vm::run_and_observe;
+use triton_vm::vm::simulate_with_profile;
+use triton_vm::vm::simulate_with_source_map;
+use triton_vm::vm::AlgebraicExecutionTrace;
+
+/// Reading a file, parsing a program or an input, or decoding a proof or claim failed.
+const EXIT_INVALID_INPUT: i32 = 1;
+/// The command-line arguments are invalid.
+const EXIT_USAGE: i32 = 2;
+/// The program could not be executed to completion.
+const EXIT_EXECUTION_FAILED: i32 = 3;
+/// The proof does not verify against the claim.
+const EXIT_PROOF_REJECTED: i32 = 4;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "triton-vm",
+    about = "Run, prove, and verify Triton assembly programs"
+)]
+enum Command {
+    /// Execute a program and print its output
+    Run {
+        #[structopt(flatten)]
+        execution: ExecutionArgs,
+    },
+
+    /// Execute a program and print the state of the VM after every instruction
+    Trace {
+        #[structopt(flatten)]
+        execution: ExecutionArgs,
+
+        /// Print every state in full instead of one line per instruction
+        #[structopt(long)]
+        full: bool,
+    },
+
+    /// Execute a program and prove that it was executed correctly
+    Prove {
+        #[structopt(flatten)]
+        execution: ExecutionArgs,
+
+        #[structopt(flatten)]
+        stark: StarkArgs,
+
+        /// Where to write the proof
+        #[structopt(long, parse(from_os_str))]
+        proof: PathBuf,
+
+        /// Where to write the claim, which the proof is about
+        #[structopt(long, parse(from_os_str))]
+        claim: PathBuf,
+
+        /// Identify the program in the claim by its digest instead of including it in full
+        #[structopt(long)]
+        program_digest: bool,
+    },
+
+    /// Verify a proof against a claim, using the parameters and hash function the proof's header
+    /// states
+    Verify {
+        /// The proof to verify
+        #[structopt(long, parse(from_os_str))]
+        proof: PathBuf,
+
+        /// The claim the proof is about
+        #[structopt(long, parse(from_os_str))]
+        claim: PathBuf,
+
+        /// Reject proofs whose parameters give fewer bits of conjectured soundness
+        #[structopt(long, default_value = "160")]
+        min_security_level: usize,
+    },
+
+    /// Execute a program and report the cycles and table rows spent in every function and label
+    Profile {
+        #[structopt(flatten)]
+        execution: ExecutionArgs,
+
+        /// Where to write the processor cycles per call stack in the folded-stacks format of
+        /// flame graph tools
+        #[structopt(long, parse(from_os_str))]
+        folded: Option<PathBuf>,
+    },
+
+    /// Print the program a claim is about as Triton assembly
+    Disassemble {
+        /// The claim containing the program
+        #[structopt(parse(from_os_str))]
+        claim: PathBuf,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+struct ExecutionArgs {
+    /// The program to execute
+    #[structopt(parse(from_os_str))]
+    program: PathBuf,
+
+    /// File with public input, whitespace-separated field elements
+    #[structopt(long, parse(from_os_str))]
+    input: Option<PathBuf>,
+
+    /// File with secret input, whitespace-separated field elements
+    #[structopt(long, parse(from_os_str))]
+    secret_input: Option<PathBuf>,
+}
+
+#[derive(Debug, StructOpt)]
+struct StarkArgs {
+    /// The targeted security level in bits
+    #[structopt(long, default_value = "160")]
+    security_level: usize,
+
+    /// The FRI expansion factor, a power of two greater than one
+    #[structopt(long, default_value = "4")]
+    fri_expansion_factor: usize,
+
+    /// The number of codeword elements FRI folds into one per round, a power of two greater
+    /// than one
+    #[structopt(long, default_value = "2")]
+    fri_folding_factor: usize,
+
+    /// Commit to all codeword elements FRI folds together as one Merkle leaf
+    #[structopt(long)]
+    fri_coset_leaves: bool,
+
+    /// The hash function for Merkle trees and Fiat-Shamir challenges
+    #[structopt(long, default_value = "rescue-prime", possible_values = &["rescue-prime", "blake3"])]
+    hash_function: String,
+}
+
+impl StarkArgs {
+    fn parameters(&self) -> Result<StarkParameters> {
+        let expansion_factor = self.fri_expansion_factor;
+        if expansion_factor < 2 || !expansion_factor.is_power_of_two() {
+            return Err(anyhow!(
+                "FRI expansion factor must be a power of two greater than one, \
+                but got {expansion_factor}."
+            ));
+        }
+        let folding_factor = self.fri_folding_factor;
+        if folding_factor < 2 || !folding_factor.is_power_of_two() {
+            return Err(anyhow!(
+                "FRI folding factor must be a power of two greater than one, \
+                but got {folding_factor}."
+            ));
+        }
+        let parameters = StarkParameters::new(self.security_level, expansion_factor)
+            .with_fri_folding_factor(folding_factor);
+        match self.fri_coset_leaves {
+            true => Ok(parameters.with_fri_coset_leaves()),
+            false => Ok(parameters),
+        }
+    }
+
+    fn hash_function(&self) -> HashFunction {
+        match self.hash_function.as_str() {
+            "blake3" => HashFunction::Blake3,
+            _ => HashFunction::RescuePrimeRegular,
+        }
+    }
+
+    fn prove(&self, claim: Claim, aet: AlgebraicExecutionTrace) -> Result<Proof> {
+        let parameters = self.parameters()?;
+        let proof = match self.hash_function() {
+            HashFunction::RescuePrimeRegular => {
+                Stark::<RescuePrimeRegular>::with_hasher(claim, parameters)?.prove(aet, &mut None)
+            }
+            HashFunction::Blake3 => {
+                Stark::<blake3::Hasher>::with_hasher(claim, parameters)?.prove(aet, &mut None)
+            }
+        };
+        Ok(proof)
+    }
+}
+
+/// Verify the proof with the parameters and the hash function stated in its header. Since the
+/// header is as untrusted as the rest of the proof, parameters that give fewer than
+/// `min_security_level` bits of conjectured soundness are rejected.
+fn verify(claim: Claim, proof: Proof, min_security_level: usize) -> Result<bool> {
+    let parameters = StarkParameters::from_header(&proof.header)?;
+    if claim.padded_height != proof.header.padded_height {
+        bail!("the claim's padded height differs from the one in the proof's header");
+    }
+    let security_level =
+        SoundnessAnalysis::new(&parameters, claim.padded_height).conjectured_bits();
+    if security_level < min_security_level as f64 {
+        bail!(
+            "its parameters give {security_level:.1} bits of conjectured soundness, \
+            less than the required {min_security_level}"
+        );
+    }
+    match proof.header.hash_function {
+        HashFunction::RescuePrimeRegular => {
+            Stark::<RescuePrimeRegular>::with_hasher(claim, parameters)?.verify(proof, &mut None)
+        }
+        HashFunction::Blake3 => {
+            Stark::<blake3::Hasher>::with_hasher(claim, parameters)?.verify(proof, &mut None)
+        }
+    }
+}
+
+/// A loaded program together with its inputs.
+struct Execution {
+    program: Program,
+    source_map: SourceMap,
+    input: Vec<BFieldElement>,
+    secret_input: Vec<BFieldElement>,
+}
+
+fn main() {
+    let command = match Command::from_iter_safe(std::env::args_os()) {
+        Ok(command) => command,
+        Err(err) if err.use_stderr() => {
+            eprintln!("{err}");
+            exit(EXIT_USAGE);
+        }
+        // help or version was requested
+        Err(err) => err.exit(),
+    };
+    let exit_code = match execute(command) {
+        Ok(exit_code) => exit_code,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            EXIT_INVALID_INPUT
+        }
+    };
+    exit(exit_code);
+}
+
+/// Execute the command, returning the exit code. Errors are for invalid input.
+fn execute(command: Command) -> Result<i32> {
+    match command {
+        Command::Run { execution } => {
+            let execution = Execution::load(&execution)?;
+            let Some((aet, output)) = execution.simulate() else {
+                return Ok(EXIT_EXECUTION_FAILED);
+            };
+            for element in output {
+                println!("{element}");
+            }
+            print_statistics(&aet, &execution.program.to_bwords());
+        }
+        Command::Trace { execution, full } => {
+            let execution = Execution::load(&execution)?;
+            let print_state = |state: &VMState| {
+                if full {
+                    println!("{state}");
+                    return;
+                }
+                let instruction = match state.current_instruction() {
+                    Ok(instruction) => instruction.to_string(),
+                    Err(_) => "<end of program>".to_string(),
+                };
+                let stack = state.op_stack.stack.iter().rev().take(8).join(", ");
+                println!(
+                    "{:>8} {:>6}  {instruction:<20} [{stack}]",
+                    state.cycle_count, state.instruction_pointer
+                );
+            };
+            let (_, _, err) = run_and_observe(
+                &execution.program,
+                execution.input,
+                execution.secret_input,
+                print_state,
+            );
+            if let Some(err) = err {
+                eprintln!("execution failed: {err}");
+                return Ok(EXIT_EXECUTION_FAILED);
+            }
+        }
+        Command::Prove {
+            execution,
+            stark,
+            proof,
+            claim,
+            program_digest,
+        } => {
+            // fail early on invalid parameters, before executing the program
+            stark.parameters()?;
+            let execution = Execution::load(&execution)?;
+            let Some((aet, output)) = execution.simulate() else {
+                return Ok(EXIT_EXECUTION_FAILED);
+            };
+            let program = execution.program.to_bwords();
+            print_statistics(&aet, &program);
+
+            let padded_height = MasterBaseTable::padded_height(&aet, &program);
+            let stark_claim = Claim {
+                input: execution.input,
+                program: ClaimedProgram::Code(program),
+                output,
+                padded_height,
+            };
+            let stark_proof = stark.prove(stark_claim.clone(), aet)?;
+            let stark_claim = match program_digest {
+                true => stark_claim.with_program_digest(),
+                false => stark_claim,
+            };
+
+            fs::write(&proof, stark_proof.to_bytes())
+                .with_context(|| format!("cannot write proof to {}", proof.display()))?;
+            fs::write(&claim, serde_json::to_string_pretty(&stark_claim)?)
+                .with_context(|| format!("cannot write claim to {}", claim.display()))?;
+            eprintln!("wrote proof to {}", proof.display());
+            eprintln!("wrote claim to {}", claim.display());
+        }
+        Command::Verify {
+            proof,
+            claim,
+            min_security_level,
+        } => {
+            let proof_bytes = fs::read(&proof)
+                .with_context(|| format!("cannot read proof from {}", proof.display()))?;
+            let proof = Proof::from_bytes(&proof_bytes)?;
+            let claim = read_claim_file(&claim)?;
+
+            match verify(claim, proof, min_security_level) {
+                Ok(true) => println!("proof is valid"),
+                Ok(false) => {
+                    println!("proof is invalid");
+                    return Ok(EXIT_PROOF_REJECTED);
+                }
+                Err(err) => {
+                    println!("proof is invalid: {err}");
+                    return Ok(EXIT_PROOF_REJECTED);
+                }
+            }
+        }
+        Command::Profile { execution, folded } => {
+            let execution = Execution::load(&execution)?;
+            let (_, _, err, profile) = simulate_with_profile(
+                &execution.program,
+                &execution.source_map,
+                execution.input,
+                execution.secret_input,
+            );
+            print!("{profile}");
+            if let Some(folded) = folded {
+                let folded_stacks = profile.folded_stacks(|cost| cost.processor_cycles);
+                fs::write(&folded, folded_stacks).with_context(|| {
+                    format!("cannot write folded stacks to {}", folded.display())
+                })?;
+                eprintln!("wrote folded stacks to {}", folded.display());
+            }
+            if let Some(err) = err {
+                eprintln!("execution failed: {err}");
+                return Ok(EXIT_EXECUTION_FAILED);
+            }
+        }
+        Command::Disassemble { claim } => {
+            let claim = read_claim_file(&claim)?;
+            let ClaimedProgram::Code(program) = claim.program else {
+                bail!("the claim identifies its program only by its digest");
+            };
+            let program = Program::from_bwords(&program)?;
+            print!("{}", disassemble(&program)?);
+        }
+    }
+    Ok(0)
+}
+
+fn read_claim_file(path: &Path) -> Result<Claim> {
+    let claim = fs::read_to_string(path)
+        .with_context(|| format!("cannot read claim from {}", path.display()))?;
+    Ok(serde_json::from_str(&claim)?)
+}
+
+impl Execution {
+    fn load(args: &ExecutionArgs) -> Result<Self> {
+        let (code, line_origins) = resolve_includes_with_origins(&args.program)?;
+        let (program, source_map) = Program::from_code_with_source_map(&code)?;
+        let source_map = source_map.with_line_origins(&line_origins);
+        Ok(Self {
+            program,
+            source_map,
+            input: read_input_file(args.input.as_deref())?,
+            secret_input: read_input_file(args.secret_input.as_deref())?,
+        })
+    }
+
+    /// Run the program, returning the Algebraic Execution Trace and the program's output.
+    /// Reports the error and returns `None` if execution fails.
+    fn simulate(&self) -> Option<(AlgebraicExecutionTrace, Vec<BFieldElement>)> {
+        let (aet, output, err) = simulate_with_source_map(
+            &self.program,
+            &self.source_map,
+            self.input.clone(),
+            self.secret_input.clone(),
+        );
+        match err {
+            Some(err) => {
+                eprintln!("execution failed: {err}");
+                None
+            }
+            None => Some((aet, output)),
+        }
+    }
+}
+
+/// Print the cycle count and table heights to stderr, keeping stdout for the program's output.
+fn print_statistics(aet: &AlgebraicExecutionTrace, program: &[BFieldElement]) {
+    let cycle_count = aet.processor_trace.nrows() - 1;
+    eprintln!("cycle count:             {cycle_count}");
+    eprintln!(
+        "padded height:           {}",
+        MasterBaseTable::padded_height(aet, program)
+    );
+    eprintln!("program table height:    {}", program.len());
+    eprintln!("processor table height:  {}", aet.processor_trace.nrows());
+    eprintln!(
+        "instruction table height: {}",
+        MasterBaseTable::instruction_table_length(aet, program)
+    );
+    eprintln!(
+        "hash table height:       {}",
+        MasterBaseTable::hash_table_length(aet)
+    );
+    eprintln!(
+        "u32 table height:        {}",
+        MasterBaseTable::u32_table_length(aet)
+    );
+}
+
+fn read_input_file(path: Option<&Path>) -> Result<Vec<BFieldElement>> {
+    let Some(path) = path else {
+        return Ok(vec![]);
+    };
+    fs::read_to_string(path)
+        .with_context(|| format!("cannot read input from {}", path.display()))?
+        .split_whitespace()
+        .map(parse_bfield_element)
+        .collect()
+}
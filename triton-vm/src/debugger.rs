@@ -0,0 +1,505 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Display;
+
+use anyhow::bail;
+use anyhow::Result;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use triton_opcodes::instruction::label_addresses;
+use triton_opcodes::instruction::AnInstruction::*;
+use triton_opcodes::instruction::LabelledInstruction;
+use triton_opcodes::ord_n::Ord16;
+use triton_opcodes::program::Program;
+use triton_opcodes::source_map::SourceMap;
+
+use crate::error::SourceMappedError;
+use crate::input::DefaultHintHandler;
+use crate::input::InputCursor;
+use crate::state::VMOutput;
+use crate::state::VMState;
+
+/// A location in memory the [`Debugger`] keeps an eye on. Execution stops whenever the value at
+/// the watched location changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Watchpoint {
+    /// The RAM cell at the given address.
+    Ram(BFieldElement),
+
+    /// The given position of the operational stack, counted from the top.
+    OpStack(Ord16),
+}
+
+impl Display for Watchpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Watchpoint::Ram(address) => write!(f, "ram[{address}]"),
+            Watchpoint::OpStack(position) => write!(f, "st{position}"),
+        }
+    }
+}
+
+/// The reason the [`Debugger`] handed control back to the caller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StopReason {
+    /// The requested number of instructions was executed.
+    Stepped,
+
+    /// The instruction pointer reached an address with a breakpoint. The instruction at that
+    /// address has not been executed yet.
+    Breakpoint(usize),
+
+    /// The value at the watched location changed from the first to the second value.
+    Watchpoint(Watchpoint, BFieldElement, BFieldElement),
+
+    /// The program terminated gracefully.
+    Halted,
+
+    /// The last executed instruction failed. The error is available through
+    /// [`Debugger::fault`].
+    Faulted,
+}
+
+impl Display for StopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StopReason::Stepped => write!(f, "stepped"),
+            StopReason::Breakpoint(address) => write!(f, "breakpoint at address {address}"),
+            StopReason::Watchpoint(watchpoint, old, new) => {
+                write!(f, "watchpoint {watchpoint} changed: {old} -> {new}")
+            }
+            StopReason::Halted => write!(f, "halted"),
+            StopReason::Faulted => write!(f, "faulted"),
+        }
+    }
+}
+
+/// Step through the execution of a `Program`, one `VMState` at a time.
+///
+/// Like `vm::run`, the debugger only keeps the current state around, and unlike `vm::simulate`,
+/// it does not record an Algebraic Execution Trace. Execution can be stopped at
+/// breakpoints, which are set by address or label, and at watchpoints, which fire whenever the
+/// value of a RAM cell or an op-stack position changes.
+///
+/// If an instruction fails, e.g., because an `assert` is violated, the debugger stops with
+/// [`StopReason::Faulted`]. The jump stack, the RAM pointer, and the Sponge state can then be
+/// inspected through [`Debugger::state`]. The op stack reflects the partial execution of the
+/// failing instruction. For most errors, the instruction pointer still points to the failing
+/// instruction. If a [`SourceMap`] was supplied, the fault is a [`SourceMappedError`] pointing to
+/// the failing instruction in the source code.
+pub struct Debugger<'pgm> {
+    state: VMState<'pgm>,
+    stdin: InputCursor,
+    secret_in: InputCursor,
+    stdout: Vec<BFieldElement>,
+    labels: HashMap<String, usize>,
+    breakpoints: HashSet<usize>,
+    watchpoints: Vec<Watchpoint>,
+    source_map: Option<SourceMap>,
+    fault: Option<anyhow::Error>,
+}
+
+impl<'pgm> Debugger<'pgm> {
+    pub fn new(
+        program: &'pgm Program,
+        stdin: Vec<BFieldElement>,
+        secret_in: Vec<BFieldElement>,
+    ) -> Self {
+        Self {
+            state: VMState::new(program),
+            stdin: InputCursor::new(stdin),
+            secret_in: InputCursor::new(secret_in),
+            stdout: vec![],
+            labels: HashMap::new(),
+            breakpoints: HashSet::new(),
+            watchpoints: vec![],
+            source_map: None,
+            fault: None,
+        }
+    }
+
+    /// Make the labels of the parsed source code available for setting breakpoints. The
+    /// `labelled_instructions` must be the ones the debugged `Program` was built from.
+    pub fn with_labels(mut self, labelled_instructions: &[LabelledInstruction]) -> Self {
+        self.labels = label_addresses(labelled_instructions);
+        self
+    }
+
+    /// Explain faults in terms of the source code the debugged `Program` was parsed from.
+    pub fn with_source_map(mut self, source_map: SourceMap) -> Self {
+        self.source_map = Some(source_map);
+        self
+    }
+
+    pub fn source_map(&self) -> Option<&SourceMap> {
+        self.source_map.as_ref()
+    }
+
+    pub fn state(&self) -> &VMState<'pgm> {
+        &self.state
+    }
+
+    pub fn stdout(&self) -> &[BFieldElement] {
+        &self.stdout
+    }
+
+    /// The error that made the VM stop, if any.
+    pub fn fault(&self) -> Option<&anyhow::Error> {
+        self.fault.as_ref()
+    }
+
+    pub fn is_terminated(&self) -> bool {
+        self.fault.is_some() || self.state.is_complete()
+    }
+
+    pub fn labels(&self) -> &HashMap<String, usize> {
+        &self.labels
+    }
+
+    /// The label at the given address, if there is one.
+    pub fn label_at(&self, address: usize) -> Option<&str> {
+        self.labels
+            .iter()
+            .find(|(_, &label_address)| label_address == address)
+            .map(|(label, _)| label.as_str())
+    }
+
+    pub fn add_breakpoint(&mut self, address: usize) {
+        self.breakpoints.insert(address);
+    }
+
+    pub fn add_breakpoint_at_label(&mut self, label: &str) -> Result<usize> {
+        let address = self.address_of_label(label)?;
+        self.add_breakpoint(address);
+        Ok(address)
+    }
+
+    /// Remove the breakpoint at the given address. Returns whether there was a breakpoint.
+    pub fn remove_breakpoint(&mut self, address: usize) -> bool {
+        self.breakpoints.remove(&address)
+    }
+
+    pub fn remove_breakpoint_at_label(&mut self, label: &str) -> Result<bool> {
+        let address = self.address_of_label(label)?;
+        Ok(self.remove_breakpoint(address))
+    }
+
+    pub fn breakpoints(&self) -> Vec<usize> {
+        let mut breakpoints = self.breakpoints.iter().copied().collect::<Vec<_>>();
+        breakpoints.sort_unstable();
+        breakpoints
+    }
+
+    pub fn add_watchpoint(&mut self, watchpoint: Watchpoint) {
+        if !self.watchpoints.contains(&watchpoint) {
+            self.watchpoints.push(watchpoint);
+        }
+    }
+
+    /// Remove the given watchpoint. Returns whether the watchpoint was set.
+    pub fn remove_watchpoint(&mut self, watchpoint: Watchpoint) -> bool {
+        let num_watchpoints = self.watchpoints.len();
+        self.watchpoints.retain(|&w| w != watchpoint);
+        num_watchpoints != self.watchpoints.len()
+    }
+
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+
+    /// Execute exactly one instruction, ignoring breakpoints.
+    pub fn step(&mut self) -> StopReason {
+        if let Some(stop_reason) = self.termination_reason() {
+            return stop_reason;
+        }
+
+        let watched_values = self.watched_values();
+        if let Err(err) = self.step_once() {
+            self.fault = Some(err);
+            return StopReason::Faulted;
+        }
+        if let Some(stop_reason) = self.triggered_watchpoint(&watched_values) {
+            return stop_reason;
+        }
+        self.termination_reason().unwrap_or(StopReason::Stepped)
+    }
+
+    /// Execute one instruction. If that instruction is a `call`, keep executing until the called
+    /// function has returned, or until execution stops for some other reason.
+    pub fn step_over(&mut self) -> StopReason {
+        let is_call = matches!(self.state.current_instruction(), Ok(Call(_)));
+        let jump_stack_depth = self.state.jump_stack.len();
+        let stop_reason = self.step();
+        if !is_call || stop_reason != StopReason::Stepped {
+            return stop_reason;
+        }
+        self.continue_while(|state| state.jump_stack.len() > jump_stack_depth)
+    }
+
+    /// Keep executing until the currently executed function has returned, or until execution
+    /// stops for some other reason.
+    pub fn step_out(&mut self) -> StopReason {
+        let jump_stack_depth = self.state.jump_stack.len();
+        if jump_stack_depth == 0 {
+            return self.run();
+        }
+        let stop_reason = self.step();
+        if stop_reason != StopReason::Stepped {
+            return stop_reason;
+        }
+        self.continue_while(|state| state.jump_stack.len() >= jump_stack_depth)
+    }
+
+    /// Keep executing until a breakpoint or a watchpoint is hit, or the program terminates.
+    pub fn run(&mut self) -> StopReason {
+        // Always execute at least one instruction to allow continuing from a breakpoint.
+        let stop_reason = self.step();
+        if stop_reason != StopReason::Stepped {
+            return stop_reason;
+        }
+        self.continue_while(|_| true)
+    }
+
+    fn continue_while<P>(&mut self, predicate: P) -> StopReason
+    where
+        P: Fn(&VMState) -> bool,
+    {
+        while predicate(&self.state) {
+            let instruction_pointer = self.state.instruction_pointer;
+            if self.breakpoints.contains(&instruction_pointer) {
+                return StopReason::Breakpoint(instruction_pointer);
+            }
+            let stop_reason = self.step();
+            if stop_reason != StopReason::Stepped {
+                return stop_reason;
+            }
+        }
+        StopReason::Stepped
+    }
+
+    fn step_once(&mut self) -> Result<()> {
+        let instruction_pointer = self.state.instruction_pointer;
+        let vm_output = self
+            .state
+            .step_mut(
+                &mut self.stdin,
+                &mut self.secret_in,
+                &mut DefaultHintHandler,
+            )
+            .map_err(|err| match &self.source_map {
+                Some(source_map) => SourceMappedError::new(
+                    err,
+                    instruction_pointer,
+                    &self.state.jump_stack,
+                    source_map,
+                )
+                .into(),
+                None => err,
+            })?;
+        if let Some(VMOutput::WriteOutputSymbol(written_word)) = vm_output {
+            self.stdout.push(written_word);
+        }
+        Ok(())
+    }
+
+    fn termination_reason(&self) -> Option<StopReason> {
+        if self.fault.is_some() {
+            Some(StopReason::Faulted)
+        } else if self.state.is_complete() {
+            Some(StopReason::Halted)
+        } else {
+            None
+        }
+    }
+
+    fn watched_values(&self) -> Vec<BFieldElement> {
+        self.watchpoints
+            .iter()
+            .map(|&watchpoint| self.watched_value(watchpoint))
+            .collect()
+    }
+
+    fn watched_value(&self, watchpoint: Watchpoint) -> BFieldElement {
+        match watchpoint {
+            Watchpoint::Ram(address) => self.state.ram.get(&address).copied().unwrap_or_default(),
+            Watchpoint::OpStack(position) => self.state.op_stack.safe_peek(position),
+        }
+    }
+
+    fn triggered_watchpoint(&self, previous_values: &[BFieldElement]) -> Option<StopReason> {
+        self.watchpoints
+            .iter()
+            .zip(previous_values.iter())
+            .map(|(&watchpoint, &old)| (watchpoint, old, self.watched_value(watchpoint)))
+            .find(|(_, old, new)| old != new)
+            .map(|(watchpoint, old, new)| StopReason::Watchpoint(watchpoint, old, new))
+    }
+
+    fn address_of_label(&self, label: &str) -> Result<usize> {
+        match self.labels.get(label) {
+            Some(&address) => Ok(address),
+            None => bail!("Unknown label: {label}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod debugger_tests {
+    use num_traits::One;
+    use triton_opcodes::ord_n::Ord16::*;
+    use triton_opcodes::parser::parse;
+
+    use crate::error::InstructionError;
+    use crate::vm::triton_vm_tests::GCD_X_Y;
+
+    use super::*;
+
+    const CALL_TWICE: &str = "
+        push 3
+        call double
+        call double
+        write_io
+        halt
+        double:
+            dup0 add return
+    ";
+
+    #[test]
+    fn step_executes_exactly_one_instruction_test() {
+        let program = Program::from_code("push 1 push 2 add halt").unwrap();
+        let mut debugger = Debugger::new(&program, vec![], vec![]);
+
+        assert_eq!(StopReason::Stepped, debugger.step());
+        assert_eq!(1, debugger.state().cycle_count);
+        assert_eq!(2, debugger.state().instruction_pointer);
+        assert_eq!(StopReason::Stepped, debugger.step());
+        assert_eq!(StopReason::Halted, debugger.step());
+        assert_eq!(BFieldElement::new(3), debugger.state().op_stack.st(ST0));
+        assert_eq!(StopReason::Halted, debugger.step());
+    }
+
+    #[test]
+    fn breakpoint_by_label_test() {
+        let instructions = parse(CALL_TWICE).unwrap();
+        let program = Program::new(&instructions);
+        let mut debugger = Debugger::new(&program, vec![], vec![]).with_labels(&instructions);
+
+        let double_address = debugger.add_breakpoint_at_label("double").unwrap();
+        assert_eq!(Some("double"), debugger.label_at(double_address));
+
+        assert_eq!(StopReason::Breakpoint(double_address), debugger.run());
+        assert_eq!(1, debugger.state().jump_stack.len());
+        assert_eq!(StopReason::Breakpoint(double_address), debugger.run());
+        assert!(debugger.remove_breakpoint_at_label("double").unwrap());
+        assert_eq!(StopReason::Halted, debugger.run());
+        assert_eq!(vec![BFieldElement::new(12)], debugger.stdout());
+
+        assert!(debugger.add_breakpoint_at_label("no_such_label").is_err());
+    }
+
+    #[test]
+    fn step_over_and_step_out_test() {
+        let instructions = parse(CALL_TWICE).unwrap();
+        let program = Program::new(&instructions);
+        let mut debugger = Debugger::new(&program, vec![], vec![]).with_labels(&instructions);
+
+        // push 3
+        debugger.step();
+        // call double – stepping over the call executes the entire function
+        assert_eq!(StopReason::Stepped, debugger.step_over());
+        assert_eq!(0, debugger.state().jump_stack.len());
+        assert_eq!(BFieldElement::new(6), debugger.state().op_stack.st(ST0));
+
+        // step into the second call, then step out of it again
+        debugger.step();
+        assert_eq!(1, debugger.state().jump_stack.len());
+        assert_eq!(StopReason::Stepped, debugger.step_out());
+        assert_eq!(0, debugger.state().jump_stack.len());
+        assert_eq!(BFieldElement::new(12), debugger.state().op_stack.st(ST0));
+
+        // a breakpoint inside the called function interrupts stepping over the call
+        let mut debugger = Debugger::new(&program, vec![], vec![]).with_labels(&instructions);
+        let double_address = debugger.add_breakpoint_at_label("double").unwrap();
+        debugger.step();
+        assert_eq!(StopReason::Breakpoint(double_address), debugger.step_over());
+    }
+
+    #[test]
+    fn watchpoints_test() {
+        let code = "push 5 push 7 write_mem pop push 42 push 8 write_mem halt";
+        let program = Program::from_code(code).unwrap();
+        let mut debugger = Debugger::new(&program, vec![], vec![]);
+        let five = BFieldElement::new(5);
+        let watchpoint = Watchpoint::Ram(five);
+        debugger.add_watchpoint(watchpoint);
+
+        let expected = StopReason::Watchpoint(watchpoint, BFieldElement::new(0), 7_u64.into());
+        assert_eq!(expected, debugger.run());
+        assert_eq!(five.value(), debugger.state().ramp);
+        assert!(debugger.remove_watchpoint(watchpoint));
+
+        let watchpoint = Watchpoint::OpStack(ST0);
+        debugger.add_watchpoint(watchpoint);
+        let expected = StopReason::Watchpoint(watchpoint, 7_u64.into(), five);
+        assert_eq!(expected, debugger.step());
+        let expected = StopReason::Watchpoint(watchpoint, five, 42_u64.into());
+        assert_eq!(expected, debugger.run());
+        debugger.remove_watchpoint(watchpoint);
+        assert_eq!(StopReason::Halted, debugger.run());
+    }
+
+    #[test]
+    fn stop_at_failing_assertion_test() {
+        let program = Program::from_code(GCD_X_Y).unwrap();
+        let stdin = vec![42_u64.into(), 56_u64.into()];
+        let mut debugger = Debugger::new(&program, stdin, vec![]);
+        assert_eq!(StopReason::Halted, debugger.run());
+        assert!(debugger.fault().is_none());
+
+        let code = "push 2 call check halt check: push 0 assert return";
+        let program = Program::from_code(code).unwrap();
+        let mut debugger = Debugger::new(&program, vec![], vec![]);
+        assert_eq!(StopReason::Faulted, debugger.run());
+        assert!(debugger.is_terminated());
+
+        let state = debugger.state();
+        assert_eq!(
+            Ok(Assert),
+            state.current_instruction().map_err(|e| e.to_string())
+        );
+        assert_eq!(1, state.jump_stack.len());
+        assert_eq!(BFieldElement::new(2), state.op_stack.st(ST0));
+        let fault = debugger.fault().unwrap();
+        assert!(matches!(
+            fault.downcast_ref::<InstructionError>(),
+            Some(InstructionError::AssertionFailed(_, _, _))
+        ));
+
+        // A faulted debugger does not execute any further instructions.
+        let cycle_count = debugger.state().cycle_count;
+        assert_eq!(StopReason::Faulted, debugger.step());
+        assert_eq!(cycle_count, debugger.state().cycle_count);
+    }
+
+    #[test]
+    fn source_mapped_fault_test() {
+        let code = "push 2 call check halt\ncheck:\n    push 0 assert return";
+        let (program, source_map) = Program::from_code_with_source_map(code).unwrap();
+        let mut debugger = Debugger::new(&program, vec![], vec![]).with_source_map(source_map);
+        assert_eq!(StopReason::Faulted, debugger.run());
+
+        let fault = debugger.fault().unwrap();
+        let fault = fault.downcast_ref::<SourceMappedError>().unwrap();
+        let location = fault.location().unwrap();
+        assert_eq!((3, 12), (location.line, location.column));
+        assert_eq!(Some("check".to_string()), location.label);
+        assert_eq!(2, fault.backtrace.len());
+    }
+
+    #[test]
+    fn reading_secret_input_test() {
+        let program = Program::from_code("divine assert halt").unwrap();
+        let mut debugger = Debugger::new(&program, vec![], vec![BFieldElement::one()]);
+        assert_eq!(StopReason::Halted, debugger.run());
+    }
+}
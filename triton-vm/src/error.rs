@@ -3,6 +3,8 @@ use std::fmt::Display;
 use std::fmt::Formatter;
 
 use anyhow::Result;
+use triton_opcodes::source_map::SourceLocation;
+use triton_opcodes::source_map::SourceMap;
 use twenty_first::shared_math::b_field_element::BFieldElement;
 
 use InstructionError::*;
@@ -20,6 +22,9 @@ pub enum InstructionError {
     RunawayInstructionArg,
     UngracefulTermination,
     FailedU32Conversion(BFieldElement),
+    PublicInputExhausted,
+    SecretInputExhausted,
+    NonU32NodeIndex(BFieldElement),
 }
 
 impl Display for InstructionError {
@@ -81,12 +86,116 @@ impl Display for InstructionError {
                     word.value()
                 )
             }
+
+            PublicInputExhausted => {
+                write!(f, "Cannot read from public input: input is exhausted")
+            }
+
+            SecretInputExhausted => {
+                write!(f, "Cannot read from secret input: input is exhausted")
+            }
+
+            NonU32NodeIndex(node_index) => {
+                write!(
+                    f,
+                    "Node index {} of divine_sibling is not a u32",
+                    node_index.value()
+                )
+            }
         }
     }
 }
 
 impl Error for InstructionError {}
 
+/// A runtime error of the VM, explained in terms of the source code the failing `Program` was
+/// parsed from.
+#[derive(Debug)]
+pub struct SourceMappedError {
+    pub error: anyhow::Error,
+
+    /// The symbolic call stack at the time of the error. The first frame is the failing
+    /// instruction, every following frame is the `call` instruction leading to the previous one.
+    pub backtrace: Vec<StackFrame>,
+}
+
+/// One entry of a symbolic call stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackFrame {
+    pub instruction_pointer: usize,
+    pub location: Option<SourceLocation>,
+}
+
+impl SourceMappedError {
+    /// Explain `error`, which occurred while executing the instruction at `instruction_pointer`
+    /// with the given `jump_stack`.
+    pub fn new(
+        error: anyhow::Error,
+        instruction_pointer: usize,
+        jump_stack: &[(BFieldElement, BFieldElement)],
+        source_map: &SourceMap,
+    ) -> Self {
+        let backtrace = backtrace(instruction_pointer, jump_stack, source_map);
+        SourceMappedError { error, backtrace }
+    }
+
+    /// The source code location of the failing instruction, if known.
+    pub fn location(&self) -> Option<&SourceLocation> {
+        self.backtrace.first()?.location.as_ref()
+    }
+}
+
+impl Display for SourceMappedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.error)?;
+        if let Some(location) = self.location() {
+            writeln!(f, "  --> {location}")?;
+        }
+        write!(f, "backtrace:")?;
+        for (depth, frame) in self.backtrace.iter().enumerate() {
+            write!(f, "\n  #{depth} {frame}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for SourceMappedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.error.as_ref())
+    }
+}
+
+impl Display for StackFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "ip {}: {location}", self.instruction_pointer),
+            None => write!(f, "ip {}: <unknown>", self.instruction_pointer),
+        }
+    }
+}
+
+/// The symbolic call stack of a VM at `instruction_pointer` with the given `jump_stack`,
+/// innermost frame first.
+pub fn backtrace(
+    instruction_pointer: usize,
+    jump_stack: &[(BFieldElement, BFieldElement)],
+    source_map: &SourceMap,
+) -> Vec<StackFrame> {
+    // The jump stack holds return addresses, which point right behind the `call` instruction.
+    let call_sites = jump_stack
+        .iter()
+        .rev()
+        .map(|(origin, _destination)| (origin.value() as usize).saturating_sub(2));
+
+    std::iter::once(instruction_pointer)
+        .chain(call_sites)
+        .map(|instruction_pointer| StackFrame {
+            instruction_pointer,
+            location: source_map.location(instruction_pointer).cloned(),
+        })
+        .collect()
+}
+
 pub fn vm_err<T>(runtime_error: InstructionError) -> Result<T> {
     Err(vm_fail(runtime_error))
 }
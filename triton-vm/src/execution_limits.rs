@@ -0,0 +1,183 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use anyhow::Result;
+use twenty_first::shared_math::other::roundup_npo2;
+
+use crate::execution_profile::ExecutionCost;
+use crate::state::VMOutput;
+use crate::table::master_table::TableId;
+
+use ExecutionLimitError::*;
+
+/// Bounds on the resources an execution of a program may consume. Executing the program stops
+/// with an [`ExecutionLimitError`] as soon as any bound is exceeded, which guarantees termination
+/// even for programs that never `halt`. By default, there are no bounds.
+///
+/// All bounds are checked after every instruction, before the instruction's rows are recorded.
+/// Since the heights of the tables only ever grow, so does the padded height. Exceeding
+/// `max_padded_height` is therefore detected as soon as the program has executed the first
+/// instruction that makes the trace too tall to prove, not only once it terminates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionLimits {
+    /// The maximum number of instructions executed.
+    pub max_cycles: Option<usize>,
+
+    /// The maximum height of the Processor Table, which is one more than the number of cycles.
+    pub max_processor_table_height: Option<usize>,
+
+    /// The maximum height of the Hash Table, stemming from instruction `hash` and the Sponge
+    /// instructions.
+    pub max_hash_table_height: Option<usize>,
+
+    /// The maximum height of the U32 Table.
+    pub max_u32_table_height: Option<usize>,
+
+    /// The maximum height the tables are padded to, see
+    /// [`MasterBaseTable::padded_height`](crate::table::master_table::MasterBaseTable::padded_height).
+    pub max_padded_height: Option<usize>,
+}
+
+impl ExecutionLimits {
+    pub fn with_max_cycles(self, max_cycles: usize) -> Self {
+        Self {
+            max_cycles: Some(max_cycles),
+            ..self
+        }
+    }
+
+    pub fn with_max_processor_table_height(self, max_height: usize) -> Self {
+        Self {
+            max_processor_table_height: Some(max_height),
+            ..self
+        }
+    }
+
+    pub fn with_max_hash_table_height(self, max_height: usize) -> Self {
+        Self {
+            max_hash_table_height: Some(max_height),
+            ..self
+        }
+    }
+
+    pub fn with_max_u32_table_height(self, max_height: usize) -> Self {
+        Self {
+            max_u32_table_height: Some(max_height),
+            ..self
+        }
+    }
+
+    pub fn with_max_padded_height(self, max_padded_height: usize) -> Self {
+        Self {
+            max_padded_height: Some(max_padded_height),
+            ..self
+        }
+    }
+}
+
+/// An execution exceeded one of its [`ExecutionLimits`]. Every variant holds the exceeded limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionLimitError {
+    CycleLimitExceeded(usize),
+    TableHeightLimitExceeded(TableId, usize),
+    PaddedHeightLimitExceeded(usize),
+}
+
+impl Display for ExecutionLimitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CycleLimitExceeded(limit) => {
+                write!(f, "Execution exceeds the limit of {limit} cycles")
+            }
+
+            TableHeightLimitExceeded(table, limit) => {
+                write!(f, "{table} exceeds the height limit of {limit} rows")
+            }
+
+            PaddedHeightLimitExceeded(limit) => {
+                write!(f, "Padded height exceeds the limit of {limit}")
+            }
+        }
+    }
+}
+
+impl Error for ExecutionLimitError {}
+
+/// Keeps track of the heights of the tables while a program is executed, and checks them against
+/// the [`ExecutionLimits`].
+#[derive(Debug, Clone)]
+pub(crate) struct ExecutionMeter {
+    limits: ExecutionLimits,
+    program_length: usize,
+    cost: ExecutionCost,
+}
+
+impl ExecutionMeter {
+    pub fn new(limits: ExecutionLimits, program_length: usize) -> Self {
+        Self {
+            limits,
+            program_length,
+            cost: ExecutionCost::default(),
+        }
+    }
+
+    /// Account for one executed instruction and its output. Fails if any limit is exceeded.
+    pub fn record_step(&mut self, vm_output: &Option<VMOutput>) -> Result<()> {
+        self.cost += ExecutionCost {
+            processor_cycles: 1,
+            ..ExecutionCost::of_output(vm_output)
+        };
+        self.check_limits()
+    }
+
+    fn check_limits(&self) -> Result<()> {
+        let exceeds = |value: usize, limit: Option<usize>| limit.is_some_and(|limit| value > limit);
+        let limits = &self.limits;
+        if exceeds(self.cost.processor_cycles, limits.max_cycles) {
+            return Err(CycleLimitExceeded(limits.max_cycles.unwrap()).into());
+        }
+        let table_heights = [
+            (
+                TableId::ProcessorTable,
+                self.processor_table_height(),
+                limits.max_processor_table_height,
+            ),
+            (
+                TableId::HashTable,
+                self.cost.hash_table_rows,
+                limits.max_hash_table_height,
+            ),
+            (
+                TableId::U32Table,
+                self.cost.u32_table_rows,
+                limits.max_u32_table_height,
+            ),
+        ];
+        for (table, height, limit) in table_heights {
+            if exceeds(height, limit) {
+                return Err(TableHeightLimitExceeded(table, limit.unwrap()).into());
+            }
+        }
+        if exceeds(self.padded_height(), limits.max_padded_height) {
+            return Err(PaddedHeightLimitExceeded(limits.max_padded_height.unwrap()).into());
+        }
+        Ok(())
+    }
+
+    /// The Processor Table records the initial state and the state after every cycle.
+    fn processor_table_height(&self) -> usize {
+        self.cost.processor_cycles + 1
+    }
+
+    /// The padded height of the trace recorded so far. Coincides with
+    /// [`MasterBaseTable::padded_height`](crate::table::master_table::MasterBaseTable::padded_height)
+    /// of that trace.
+    pub fn padded_height(&self) -> usize {
+        let instruction_table_height = self.program_length + self.processor_table_height();
+        let max_height = instruction_table_height
+            .max(self.cost.hash_table_rows)
+            .max(self.cost.u32_table_rows);
+        roundup_npo2(max_height as u64) as usize
+    }
+}
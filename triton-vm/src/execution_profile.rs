@@ -0,0 +1,415 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::AddAssign;
+
+use itertools::Itertools;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::rescue_prime_regular::NUM_ROUNDS;
+
+use triton_opcodes::source_map::SourceMap;
+
+use crate::execution_trace::ExecutionTrace;
+use crate::state::VMOutput;
+use crate::state::VMState;
+use crate::table::master_table::MasterBaseTable;
+use crate::table::master_table::TableId;
+
+/// The name of the outermost function, _i.e._, the code executed before the first `call`.
+pub const ENTRY_FUNCTION_NAME: &str = "<entry>";
+
+/// The number of rows or entries an executed program (or a part of it) contributes to the tables
+/// of Triton VM.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionCost {
+    pub processor_cycles: usize,
+    pub hash_table_rows: usize,
+    pub u32_table_entries: usize,
+    pub u32_table_rows: usize,
+}
+
+impl ExecutionCost {
+    /// The rows the output of one instruction adds to the Hash Table and the U32 Table. Does not
+    /// include the instruction's processor cycle.
+    pub fn of_output(vm_output: &Option<VMOutput>) -> Self {
+        match vm_output {
+            Some(VMOutput::XlixTrace(_, _)) => ExecutionCost {
+                hash_table_rows: NUM_ROUNDS + 1,
+                ..Default::default()
+            },
+            Some(VMOutput::U32TableEntries(entries)) => ExecutionCost {
+                u32_table_entries: entries.len(),
+                u32_table_rows: entries
+                    .iter()
+                    .map(|&(_, lhs, rhs)| MasterBaseTable::u32_table_entry_length(lhs, rhs))
+                    .sum(),
+                ..Default::default()
+            },
+            _ => ExecutionCost::default(),
+        }
+    }
+}
+
+impl AddAssign for ExecutionCost {
+    fn add_assign(&mut self, rhs: Self) {
+        self.processor_cycles += rhs.processor_cycles;
+        self.hash_table_rows += rhs.hash_table_rows;
+        self.u32_table_entries += rhs.u32_table_entries;
+        self.u32_table_rows += rhs.u32_table_rows;
+    }
+}
+
+/// The heights of the tables whose lengths depend on the execution, before padding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TableHeights {
+    pub program: usize,
+    pub processor: usize,
+    pub instruction: usize,
+    pub hash: usize,
+    pub u32: usize,
+    pub padded: usize,
+}
+
+impl TableHeights {
+    pub fn new(aet: &impl ExecutionTrace, program: &[BFieldElement]) -> Self {
+        Self {
+            program: program.len(),
+            processor: aet.processor_trace().nrows(),
+            instruction: MasterBaseTable::instruction_table_length(aet, program),
+            hash: MasterBaseTable::hash_table_length(aet),
+            u32: MasterBaseTable::u32_table_length(aet),
+            padded: MasterBaseTable::padded_height(aet, program),
+        }
+    }
+
+    /// The tallest table, which determines the padded height of all tables.
+    pub fn height_determining_table(&self) -> TableId {
+        [
+            (TableId::InstructionTable, self.instruction),
+            (TableId::HashTable, self.hash),
+            (TableId::U32Table, self.u32),
+        ]
+        .into_iter()
+        .rev()
+        .max_by_key(|&(_, height)| height)
+        .map(|(table, _)| table)
+        .unwrap()
+    }
+}
+
+/// Where the cycles and table rows of an execution of a program go.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionProfile {
+    /// The cost of every call stack, not including the cost of the functions called from it.
+    /// A call stack is the list of names of the called functions, outermost first, starting with
+    /// [`ENTRY_FUNCTION_NAME`].
+    pub call_stacks: BTreeMap<Vec<String>, ExecutionCost>,
+
+    /// The cost of the instructions following every label, up to the next label.
+    pub labels: BTreeMap<String, ExecutionCost>,
+
+    pub table_heights: TableHeights,
+}
+
+impl ExecutionProfile {
+    pub fn total_cost(&self) -> ExecutionCost {
+        let mut total_cost = ExecutionCost::default();
+        for &cost in self.call_stacks.values() {
+            total_cost += cost;
+        }
+        total_cost
+    }
+
+    /// The cost of the call stack, including the cost of all functions called from it.
+    pub fn inclusive_cost(&self, call_stack: &[String]) -> ExecutionCost {
+        let mut inclusive_cost = ExecutionCost::default();
+        for (_, &cost) in self
+            .call_stacks
+            .iter()
+            .filter(|(other_call_stack, _)| other_call_stack.starts_with(call_stack))
+        {
+            inclusive_cost += cost;
+        }
+        inclusive_cost
+    }
+
+    /// The call stacks in the “folded stacks” format understood by flame graph tools like
+    /// [inferno](https://github.com/jonhoo/inferno): one line `outer;inner weight` per call
+    /// stack, where `weight` is derived from the call stack's cost, _e.g._,
+    /// `|cost| cost.processor_cycles`. Call stacks of weight 0 are omitted.
+    pub fn folded_stacks<F: Fn(&ExecutionCost) -> usize>(&self, weight: F) -> String {
+        self.call_stacks
+            .iter()
+            .map(|(call_stack, cost)| (call_stack, weight(cost)))
+            .filter(|&(_, weight)| weight > 0)
+            .map(|(call_stack, weight)| format!("{} {weight}\n", call_stack.join(";")))
+            .collect()
+    }
+}
+
+impl Display for ExecutionProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let write_cost_row = |f: &mut std::fmt::Formatter<'_>, name: &str, cost: ExecutionCost| {
+            writeln!(
+                f,
+                "{name:<40} {:>12} {:>12} {:>12} {:>12}",
+                cost.processor_cycles,
+                cost.hash_table_rows,
+                cost.u32_table_entries,
+                cost.u32_table_rows,
+            )
+        };
+        let header = ["cycles", "hash rows", "u32 entries", "u32 rows"];
+        let [cycles, hash_rows, u32_entries, u32_rows] = header;
+
+        writeln!(
+            f,
+            "{:<40} {cycles:>12} {hash_rows:>12} {u32_entries:>12} {u32_rows:>12}",
+            "call tree (inclusive)"
+        )?;
+        for call_stack in self.call_stacks.keys() {
+            let indentation = "  ".repeat(call_stack.len() - 1);
+            let name = format!("{indentation}{}", call_stack.last().unwrap());
+            write_cost_row(f, &name, self.inclusive_cost(call_stack))?;
+        }
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<40} {cycles:>12} {hash_rows:>12} {u32_entries:>12} {u32_rows:>12}",
+            "labels"
+        )?;
+        let labels_by_cycles = self
+            .labels
+            .iter()
+            .sorted_by_key(|(_, cost)| std::cmp::Reverse(cost.processor_cycles));
+        for (label, &cost) in labels_by_cycles {
+            write_cost_row(f, label, cost)?;
+        }
+
+        let heights = self.table_heights;
+        writeln!(f)?;
+        writeln!(f, "program table height:     {}", heights.program)?;
+        writeln!(f, "processor table height:   {}", heights.processor)?;
+        writeln!(f, "instruction table height: {}", heights.instruction)?;
+        writeln!(f, "hash table height:        {}", heights.hash)?;
+        writeln!(f, "u32 table height:         {}", heights.u32)?;
+        writeln!(
+            f,
+            "padded height:            {} (determined by {})",
+            heights.padded,
+            heights.height_determining_table()
+        )
+    }
+}
+
+/// Collects an [`ExecutionProfile`] while a program is being executed.
+pub(crate) struct ExecutionProfiler<'a> {
+    source_map: &'a SourceMap,
+    call_stack: Vec<usize>,
+    label: Option<&'a str>,
+    call_stack_costs: HashMap<Vec<usize>, ExecutionCost>,
+    label_costs: HashMap<Option<&'a str>, ExecutionCost>,
+}
+
+impl<'a> ExecutionProfiler<'a> {
+    pub fn new(source_map: &'a SourceMap) -> Self {
+        Self {
+            source_map,
+            call_stack: vec![],
+            label: None,
+            call_stack_costs: HashMap::new(),
+            label_costs: HashMap::new(),
+        }
+    }
+
+    /// Attribute the instruction about to be executed in `state` to the current function and
+    /// label.
+    pub fn record_instruction(&mut self, state: &VMState) {
+        self.call_stack.clear();
+        let call_destinations = state.jump_stack.iter().map(|&(_, destination)| destination);
+        self.call_stack
+            .extend(call_destinations.map(|destination| destination.value() as usize));
+        self.label = self.source_map.enclosing_label(state.instruction_pointer);
+
+        self.add_cost(ExecutionCost {
+            processor_cycles: 1,
+            ..Default::default()
+        });
+    }
+
+    /// Attribute the co-processor rows resulting from the last recorded instruction.
+    pub fn record_output(&mut self, vm_output: &Option<VMOutput>) {
+        let cost = ExecutionCost::of_output(vm_output);
+        if cost != ExecutionCost::default() {
+            self.add_cost(cost);
+        }
+    }
+
+    fn add_cost(&mut self, cost: ExecutionCost) {
+        *self
+            .call_stack_costs
+            .entry(self.call_stack.clone())
+            .or_default() += cost;
+        *self.label_costs.entry(self.label).or_default() += cost;
+    }
+
+    pub fn finish(self, table_heights: TableHeights) -> ExecutionProfile {
+        let function_name = |address: usize| match self.source_map.enclosing_label(address) {
+            Some(label) => label.to_string(),
+            None => format!("_addr_{address}"),
+        };
+
+        let mut call_stacks = BTreeMap::new();
+        for (call_stack, cost) in self.call_stack_costs.iter() {
+            let function_names = call_stack.iter().map(|&address| function_name(address));
+            let call_stack = std::iter::once(ENTRY_FUNCTION_NAME.to_string())
+                .chain(function_names)
+                .collect_vec();
+            *call_stacks.entry(call_stack).or_default() += *cost;
+        }
+
+        // Make sure every call stack's callers show up in the call tree, even if they did not
+        // execute any instruction of their own, e.g., because of a tail call.
+        let callers = call_stacks
+            .keys()
+            .flat_map(|call_stack: &Vec<String>| {
+                (1..call_stack.len()).map(|length| call_stack[..length].to_vec())
+            })
+            .collect_vec();
+        for caller in callers {
+            call_stacks.entry(caller).or_default();
+        }
+
+        let labels = self
+            .label_costs
+            .into_iter()
+            .map(|(label, cost)| (label.unwrap_or(ENTRY_FUNCTION_NAME).to_string(), cost))
+            .collect();
+
+        ExecutionProfile {
+            call_stacks,
+            labels,
+            table_heights,
+        }
+    }
+}
+
+#[cfg(test)]
+mod execution_profile_tests {
+    use triton_opcodes::program::Program;
+
+    use crate::vm::simulate_with_profile;
+
+    use super::*;
+
+    fn profile_of(code: &str) -> ExecutionProfile {
+        let (program, source_map) = Program::from_code_with_source_map(code).unwrap();
+        let (aet, _, err, profile) = simulate_with_profile(&program, &source_map, vec![], vec![]);
+        assert!(err.is_none(), "{}", err.unwrap());
+        assert_eq!(
+            TableHeights::new(&aet, &program.to_bwords()),
+            profile.table_heights
+        );
+        profile
+    }
+
+    fn call_stack(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn costs_are_attributed_to_call_stacks_and_labels_test() {
+        let profile = profile_of(
+            "
+            push 7 call double call double halt
+            double:
+                dup0 add call square_and_forget
+                return
+            square_and_forget:
+                dup0 dup0 mul pop
+                push 4 push 2 lt pop
+                push 0 push 0 push 0 push 0 push 0
+                hash pop pop pop pop pop
+                return
+            ",
+        );
+
+        let total_cost = profile.total_cost();
+        assert_eq!(
+            profile.table_heights.processor - 1,
+            total_cost.processor_cycles
+        );
+        assert_eq!(profile.table_heights.hash, total_cost.hash_table_rows);
+        assert_eq!(profile.table_heights.u32, total_cost.u32_table_rows);
+        assert_eq!(2, total_cost.u32_table_entries);
+        assert_eq!(
+            total_cost,
+            profile.inclusive_cost(&call_stack(&["<entry>"]))
+        );
+
+        let entry = call_stack(&["<entry>"]);
+        let double = call_stack(&["<entry>", "double"]);
+        let square = call_stack(&["<entry>", "double", "square_and_forget"]);
+        assert_eq!(
+            vec![&entry, &double, &square],
+            profile.call_stacks.keys().collect_vec()
+        );
+
+        let cost_of = |call_stack: &Vec<String>| profile.call_stacks[call_stack];
+        assert_eq!(3, cost_of(&entry).processor_cycles);
+        assert_eq!(2 * 4, cost_of(&double).processor_cycles);
+        assert_eq!(2 * 20, cost_of(&square).processor_cycles);
+        assert_eq!(0, cost_of(&double).hash_table_rows);
+        assert_eq!(2 * (NUM_ROUNDS + 1), cost_of(&square).hash_table_rows);
+
+        let double_inclusive = profile.inclusive_cost(&double);
+        assert_eq!(2 * (4 + 20), double_inclusive.processor_cycles);
+        assert_eq!(total_cost.hash_table_rows, double_inclusive.hash_table_rows);
+
+        assert_eq!(cost_of(&entry), profile.labels["<entry>"]);
+        assert_eq!(cost_of(&double), profile.labels["double"]);
+        assert_eq!(cost_of(&square), profile.labels["square_and_forget"]);
+
+        let folded_stacks = profile.folded_stacks(|cost| cost.processor_cycles);
+        let expected_folded_stacks =
+            "<entry> 3\n<entry>;double 8\n<entry>;double;square_and_forget 40\n";
+        assert_eq!(expected_folded_stacks, folded_stacks);
+        let folded_stacks = profile.folded_stacks(|cost| cost.hash_table_rows);
+        assert_eq!("<entry>;double;square_and_forget 18\n", folded_stacks);
+
+        let report = profile.to_string();
+        assert!(report.contains("  double"));
+        assert!(report.contains("determined by InstructionTable"));
+    }
+
+    #[test]
+    fn failing_execution_is_profiled_up_to_the_failure_test() {
+        let code = "push 1 call fail halt fail: push 0 assert return";
+        let (program, source_map) = Program::from_code_with_source_map(code).unwrap();
+        let (_, _, err, profile) = simulate_with_profile(&program, &source_map, vec![], vec![]);
+        assert!(err.is_some());
+        assert_eq!(4, profile.total_cost().processor_cycles);
+        assert_eq!(2, profile.labels["fail"].processor_cycles);
+    }
+
+    #[test]
+    fn height_determining_table_test() {
+        let hashes = ["hash"; 10].join(" ");
+        let profile = profile_of(&format!("{hashes} halt"));
+        assert_eq!(
+            TableId::HashTable,
+            profile.table_heights.height_determining_table()
+        );
+
+        let profile = profile_of("push 4294967295 push 4294967294 lt pop halt");
+        assert_eq!(
+            TableId::U32Table,
+            profile.table_heights.height_determining_table()
+        );
+        assert_eq!(
+            TableId::InstructionTable,
+            TableHeights::default().height_determining_table()
+        );
+    }
+}
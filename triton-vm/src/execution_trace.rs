@@ -0,0 +1,359 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::mem::align_of;
+use std::mem::size_of;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::bail;
+use anyhow::Result;
+use memmap2::Mmap;
+use ndarray::ArrayView1;
+use ndarray::ArrayView2;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use triton_opcodes::instruction::Instruction;
+
+use crate::table::hash_table;
+use crate::table::processor_table;
+
+/// Receives the Algebraic Execution Trace while `vm::simulate_to_writer` produces it, row by row.
+///
+/// The `AlgebraicExecutionTrace` itself is the in-memory sink. For long executions, an
+/// `MmapTraceWriter` streams the trace to disk instead, keeping memory consumption bounded.
+pub trait TraceWriter {
+    /// Append one row of the Processor Table.
+    fn write_processor_row(&mut self, row: ArrayView1<BFieldElement>) -> Result<()>;
+
+    /// Append rows to the part of the Hash Table that stems from instruction `hash`.
+    fn write_hash_rows(&mut self, rows: ArrayView2<BFieldElement>) -> Result<()>;
+
+    /// Append rows to the part of the Hash Table that stems from the Sponge instructions.
+    fn write_sponge_rows(&mut self, rows: ArrayView2<BFieldElement>) -> Result<()>;
+
+    /// Append entries destined for the U32 Table.
+    fn write_u32_entries(
+        &mut self,
+        entries: &[(Instruction, BFieldElement, BFieldElement)],
+    ) -> Result<()>;
+}
+
+/// Read access to a recorded Algebraic Execution Trace, regardless of where it is stored. This
+/// is what the `MasterBaseTable` is filled from.
+pub trait ExecutionTrace {
+    /// Every state of the processor, one row per executed instruction and the final state.
+    fn processor_trace(&self) -> ArrayView2<'_, BFieldElement>;
+
+    /// The internal states of the XLIX permutation for every executed `hash` instruction.
+    fn hash_trace(&self) -> ArrayView2<'_, BFieldElement>;
+
+    /// The internal states of the XLIX permutation for every executed Sponge instruction.
+    fn sponge_trace(&self) -> ArrayView2<'_, BFieldElement>;
+
+    /// All pairs of BFieldElements that were written to the U32 Table, alongside the u32
+    /// instruction that was executed at the time.
+    fn u32_entries(&self) -> &[(Instruction, BFieldElement, BFieldElement)];
+}
+
+// Memory-mapped traces are read by reinterpreting the mapped bytes as `BFieldElement`s, without
+// copying them. This is sound for the following reasons.
+// - `BFieldElement` is a struct with a single `u64` field. Since both have the same size, the
+//   field is at offset 0 and there is no padding, so every `u64` is a valid bit pattern.
+// - The field holds exactly the raw value `BFieldElement::from_raw_u64` takes, which is the value
+//   `TableFileWriter` writes in native byte order. This is checked by transmuting back.
+// - `TableFileWriter::finish` checks that the mapping is aligned, has the expected length, and
+//   only holds canonical raw values, i.e., values smaller than the field's modulus.
+// All of the layout assumptions are checked at compile time.
+const _: () = assert!(size_of::<BFieldElement>() == size_of::<u64>());
+const _: () = assert!(align_of::<BFieldElement>() == align_of::<u64>());
+const _: () = {
+    let raw_value: u64 = 0x0123_4567_89ab_cdef;
+    let element = BFieldElement::from_raw_u64(raw_value);
+    // Safety: both types have the same size, see the assertion above.
+    let element_bits = unsafe { std::mem::transmute::<BFieldElement, u64>(element) };
+    assert!(element_bits == raw_value);
+};
+
+const PROCESSOR_TRACE_FILE_NAME: &str = "processor_trace.bin";
+const HASH_TRACE_FILE_NAME: &str = "hash_trace.bin";
+const SPONGE_TRACE_FILE_NAME: &str = "sponge_trace.bin";
+
+/// A `TraceWriter` that streams the Processor Table and the Hash Table to files in a directory.
+/// Once execution has finished, `finish()` maps the files into memory, giving an `MmapTrace`.
+///
+/// The U32 Table entries are comparatively few and are kept in memory.
+pub struct MmapTraceWriter {
+    processor_trace: TableFileWriter,
+    hash_trace: TableFileWriter,
+    sponge_trace: TableFileWriter,
+    u32_entries: Vec<(Instruction, BFieldElement, BFieldElement)>,
+}
+
+impl MmapTraceWriter {
+    /// Create a writer storing its files in `directory`, which is created if necessary.
+    /// Existing trace files in that directory are overwritten.
+    pub fn create<P: AsRef<Path>>(directory: P) -> Result<Self> {
+        let directory = directory.as_ref();
+        std::fs::create_dir_all(directory)?;
+        Ok(Self {
+            processor_trace: TableFileWriter::create(
+                directory.join(PROCESSOR_TRACE_FILE_NAME),
+                processor_table::BASE_WIDTH,
+            )?,
+            hash_trace: TableFileWriter::create(
+                directory.join(HASH_TRACE_FILE_NAME),
+                hash_table::BASE_WIDTH,
+            )?,
+            sponge_trace: TableFileWriter::create(
+                directory.join(SPONGE_TRACE_FILE_NAME),
+                hash_table::BASE_WIDTH,
+            )?,
+            u32_entries: vec![],
+        })
+    }
+
+    /// Flush all files and map them into memory.
+    pub fn finish(self) -> Result<MmapTrace> {
+        Ok(MmapTrace {
+            processor_trace: self.processor_trace.finish()?,
+            hash_trace: self.hash_trace.finish()?,
+            sponge_trace: self.sponge_trace.finish()?,
+            u32_entries: self.u32_entries,
+        })
+    }
+}
+
+impl TraceWriter for MmapTraceWriter {
+    fn write_processor_row(&mut self, row: ArrayView1<BFieldElement>) -> Result<()> {
+        self.processor_trace.write_row(row)
+    }
+
+    fn write_hash_rows(&mut self, rows: ArrayView2<BFieldElement>) -> Result<()> {
+        rows.rows()
+            .into_iter()
+            .try_for_each(|row| self.hash_trace.write_row(row))
+    }
+
+    fn write_sponge_rows(&mut self, rows: ArrayView2<BFieldElement>) -> Result<()> {
+        rows.rows()
+            .into_iter()
+            .try_for_each(|row| self.sponge_trace.write_row(row))
+    }
+
+    fn write_u32_entries(
+        &mut self,
+        entries: &[(Instruction, BFieldElement, BFieldElement)],
+    ) -> Result<()> {
+        self.u32_entries.extend_from_slice(entries);
+        Ok(())
+    }
+}
+
+/// An Algebraic Execution Trace backed by memory-mapped files, as produced by an
+/// `MmapTraceWriter`. The operating system pages the trace in and out as needed.
+pub struct MmapTrace {
+    processor_trace: MappedTable,
+    hash_trace: MappedTable,
+    sponge_trace: MappedTable,
+    u32_entries: Vec<(Instruction, BFieldElement, BFieldElement)>,
+}
+
+impl ExecutionTrace for MmapTrace {
+    fn processor_trace(&self) -> ArrayView2<'_, BFieldElement> {
+        self.processor_trace.view()
+    }
+
+    fn hash_trace(&self) -> ArrayView2<'_, BFieldElement> {
+        self.hash_trace.view()
+    }
+
+    fn sponge_trace(&self) -> ArrayView2<'_, BFieldElement> {
+        self.sponge_trace.view()
+    }
+
+    fn u32_entries(&self) -> &[(Instruction, BFieldElement, BFieldElement)] {
+        &self.u32_entries
+    }
+}
+
+/// Appends rows of fixed width to a file, every element in the native byte order of its
+/// Montgomery representation.
+struct TableFileWriter {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    width: usize,
+    num_rows: usize,
+}
+
+impl TableFileWriter {
+    fn create(path: PathBuf, width: usize) -> Result<Self> {
+        let writer = BufWriter::new(File::create(&path)?);
+        Ok(Self {
+            path,
+            writer,
+            width,
+            num_rows: 0,
+        })
+    }
+
+    fn write_row(&mut self, row: ArrayView1<BFieldElement>) -> Result<()> {
+        if row.len() != self.width {
+            bail!(
+                "Row of length {} does not fit table of width {}.",
+                row.len(),
+                self.width
+            );
+        }
+        for element in row.iter() {
+            let raw_value = u64::from_le_bytes(element.raw_bytes());
+            self.writer.write_all(&raw_value.to_ne_bytes())?;
+        }
+        self.num_rows += 1;
+        Ok(())
+    }
+
+    /// Flush the file and map it into memory. Fails unless the mapping can be read as
+    /// `BFieldElement`s: it must have the expected length and alignment, and every stored value
+    /// must be canonical, i.e., smaller than the field's modulus.
+    fn finish(mut self) -> Result<MappedTable> {
+        self.writer.flush()?;
+        let file = File::open(&self.path)?;
+        let mmap = match self.num_rows {
+            0 => None,
+            // Safety: the file was written by this writer and is not modified while mapped,
+            // as long as nobody else tampers with it.
+            _ => Some(unsafe { Mmap::map(&file)? }),
+        };
+        if let Some(mmap) = &mmap {
+            Self::check_mapping(mmap, self.num_rows * self.width)?;
+        }
+        Ok(MappedTable {
+            mmap,
+            width: self.width,
+            num_rows: self.num_rows,
+        })
+    }
+
+    fn check_mapping(mmap: &Mmap, num_elements: usize) -> Result<()> {
+        if mmap.len() != num_elements * size_of::<BFieldElement>() {
+            bail!(
+                "Trace file holds {} bytes but should hold {num_elements} elements.",
+                mmap.len()
+            );
+        }
+        if mmap.as_ptr().align_offset(align_of::<BFieldElement>()) != 0 {
+            bail!("Trace file is not mapped to a suitably aligned address.");
+        }
+        let non_canonical_value = mmap
+            .chunks_exact(size_of::<u64>())
+            .map(|bytes| u64::from_ne_bytes(bytes.try_into().unwrap()))
+            .find(|&raw_value| raw_value >= BFieldElement::P);
+        if let Some(raw_value) = non_canonical_value {
+            bail!("Trace file contains non-canonical value {raw_value}.");
+        }
+        Ok(())
+    }
+}
+
+struct MappedTable {
+    mmap: Option<Mmap>,
+    width: usize,
+    num_rows: usize,
+}
+
+impl MappedTable {
+    fn view(&self) -> ArrayView2<'_, BFieldElement> {
+        let elements: &[BFieldElement] = match &self.mmap {
+            None => &[],
+            Some(mmap) => {
+                let num_elements = self.num_rows * self.width;
+                // Safety: `TableFileWriter::finish` checked that the mapping is aligned and holds
+                // exactly `num_elements` canonical values. The in-memory representation of
+                // `BFieldElement` is that of `u64`, see the compile-time assertions in this module.
+                unsafe {
+                    std::slice::from_raw_parts(mmap.as_ptr() as *const BFieldElement, num_elements)
+                }
+            }
+        };
+        ArrayView2::from_shape([self.num_rows, self.width], elements)
+            .expect("trace file must contain complete rows")
+    }
+}
+
+#[cfg(test)]
+mod execution_trace_tests {
+    use rand::random;
+    use triton_opcodes::program::Program;
+
+    use crate::arithmetic_domain::ArithmeticDomain;
+    use crate::table::master_table::MasterBaseTable;
+    use crate::table::master_table::MasterTable;
+    use crate::vm::simulate;
+    use crate::vm::simulate_to_writer;
+
+    use super::*;
+
+    #[test]
+    fn mmap_trace_equals_in_memory_trace_test() {
+        let code = "push 5 push 3 lt push 7 split absorb_init squeeze hash pop pop pop \
+            read_io call fib halt fib: dup0 push 0 eq skiz return push -1 add recurse";
+        let program = Program::from_code(code).unwrap();
+        let stdin = vec![BFieldElement::new(11)];
+
+        let (aet, stdout, err) = simulate(&program, stdin.clone(), vec![]);
+        if let Some(e) = err {
+            panic!("Execution failed: {e}");
+        }
+
+        let directory = std::env::temp_dir().join(format!("triton-vm-trace-{}", random::<u64>()));
+        let mut trace_writer = MmapTraceWriter::create(&directory).unwrap();
+        let (mmap_stdout, err) = simulate_to_writer(&program, stdin, vec![], &mut trace_writer);
+        if let Some(e) = err {
+            panic!("Execution failed: {e}");
+        }
+        let mmap_trace = trace_writer.finish().unwrap();
+
+        assert_eq!(stdout, mmap_stdout);
+        assert_eq!(aet.processor_trace(), mmap_trace.processor_trace());
+        assert_eq!(aet.hash_trace(), mmap_trace.hash_trace());
+        assert_eq!(aet.sponge_trace(), mmap_trace.sponge_trace());
+        assert_eq!(aet.u32_entries(), mmap_trace.u32_entries());
+        assert!(mmap_trace.hash_trace().nrows() > 0);
+
+        let program = program.to_bwords();
+        let fri_domain = ArithmeticDomain::new_no_offset(1 << 10);
+        let in_memory_table = MasterBaseTable::new(aet, &program, 0, fri_domain);
+        let mmap_table = MasterBaseTable::new(mmap_trace, &program, 0, fri_domain);
+        assert_eq!(in_memory_table.trace_table(), mmap_table.trace_table());
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn empty_mmap_trace_test() {
+        let directory = std::env::temp_dir().join(format!("triton-vm-trace-{}", random::<u64>()));
+        let mmap_trace = MmapTraceWriter::create(&directory)
+            .unwrap()
+            .finish()
+            .unwrap();
+        assert_eq!(0, mmap_trace.processor_trace().nrows());
+        assert_eq!(hash_table::BASE_WIDTH, mmap_trace.hash_trace().ncols(),);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn reject_non_canonical_trace_file_test() {
+        let path = std::env::temp_dir().join(format!("triton-vm-table-{}.bin", random::<u64>()));
+        let mut table_writer = TableFileWriter::create(path.clone(), 2).unwrap();
+        let row = [BFieldElement::new(1), BFieldElement::new(2)];
+        table_writer.write_row(ArrayView1::from(&row)).unwrap();
+        table_writer.writer.flush().unwrap();
+
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[..8].copy_from_slice(&u64::MAX.to_ne_bytes());
+        std::fs::write(&path, bytes).unwrap();
+        assert!(table_writer.finish().is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
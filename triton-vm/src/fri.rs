@@ -1,10 +1,12 @@
 use std::error::Error;
 use std::fmt;
+use std::iter::once;
 use std::marker::PhantomData;
 
 use anyhow::Result;
 use itertools::Itertools;
 use num_traits::One;
+use num_traits::Zero;
 use rayon::iter::*;
 use triton_profiler::prof_start;
 use triton_profiler::prof_stop;
@@ -17,17 +19,20 @@ use twenty_first::shared_math::polynomial::Polynomial;
 use twenty_first::shared_math::rescue_prime_digest::Digest;
 use twenty_first::shared_math::traits::CyclicGroupGenerator;
 use twenty_first::shared_math::traits::FiniteField;
+use twenty_first::shared_math::traits::Inverse;
 use twenty_first::shared_math::traits::ModPowU32;
 use twenty_first::shared_math::x_field_element::XFieldElement;
-use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
 use twenty_first::util_types::algebraic_hasher::Hashable;
 use twenty_first::util_types::merkle_tree::MerkleTree;
 use twenty_first::util_types::merkle_tree::PartialAuthenticationPath;
 use twenty_first::util_types::merkle_tree_maker::MerkleTreeMaker;
 
 use crate::arithmetic_domain::ArithmeticDomain;
+use crate::proof::ProofHasher;
 use crate::proof_item::FriResponse;
 use crate::proof_item::ProofItem;
+use crate::proof_of_work::dequeue_and_check_proof_of_work;
+use crate::proof_of_work::enqueue_proof_of_work;
 use crate::proof_stream::ProofStream;
 use crate::stark::Maker;
 
@@ -48,6 +53,8 @@ pub enum FriValidationError {
     LastIterationTooHighDegree,
     BadMerkleRootForFirstCodeword,
     BadMerkleRootForLastCodeword,
+    InsufficientProofOfWork,
+    MismatchingCosetValue,
 }
 
 #[derive(Debug, Clone)]
@@ -56,24 +63,44 @@ pub struct Fri<H> {
     // `max_degree` is the max degree of any interpolation rounded up to the
     // nearest power of 2.
     pub expansion_factor: usize,
+
+    /// The number of codeword elements folded into one element of the next round's codeword.
+    /// Every round divides the length of the codeword by this power of two.
+    pub folding_factor: usize,
+
+    /// Whether the Merkle trees of all but the last codeword commit to entire cosets, i.e., to
+    /// all `folding_factor` many elements that are folded together, as one leaf. Then, every
+    /// colinearity check opens one leaf with one authentication path per round. Otherwise, every
+    /// codeword element is a leaf of its own.
+    pub coset_leaves: bool,
     pub colinearity_checks_count: usize,
+    pub num_grinding_bits: usize,
     pub domain: ArithmeticDomain,
     _hasher: PhantomData<H>,
 }
 
-impl<H: AlgebraicHasher> Fri<H> {
+impl<H: ProofHasher> Fri<H> {
     pub fn new(
         offset: BFieldElement,
         domain_length: usize,
         expansion_factor: usize,
+        folding_factor: usize,
         colinearity_checks_count: usize,
+        num_grinding_bits: usize,
     ) -> Self {
+        assert!(
+            folding_factor > 1 && folding_factor.is_power_of_two(),
+            "FRI folding factor must be a power of two greater than one, but got {folding_factor}."
+        );
         let domain = ArithmeticDomain::new(offset, domain_length);
         let _hasher = PhantomData;
         Self {
             domain,
             expansion_factor,
+            folding_factor,
+            coset_leaves: false,
             colinearity_checks_count,
+            num_grinding_bits,
             _hasher,
         }
     }
@@ -125,6 +152,62 @@ impl<H: AlgebraicHasher> Fri<H> {
         }
     }
 
+    /// Build the (deduplicated) Merkle authentication paths for the leaves at the given indices
+    /// and enqueue the corresponding cosets and (partial) authentication paths on the proof stream.
+    fn enqueue_cosets(
+        &self,
+        leaf_indices: &[usize],
+        codeword: &[XFieldElement],
+        merkle_tree: &MerkleTree<H, Maker>,
+        proof_stream: &mut ProofStream<ProofItem, H>,
+    ) {
+        let num_leaves = codeword.len() / self.folding_factor;
+        let cosets = leaf_indices
+            .iter()
+            .map(|&leaf_index| Self::leaf(codeword, num_leaves, leaf_index))
+            .collect_vec();
+        let auth_paths = merkle_tree.get_authentication_structure(leaf_indices);
+        proof_stream.enqueue(&ProofItem::FriCosets(cosets));
+        proof_stream.enqueue(&ProofItem::CompressedAuthenticationPaths(auth_paths));
+    }
+
+    /// Given a set of `leaf_indices`, a merkle `root`, and the (correctly set) `proof_stream`,
+    /// verify whether the cosets at the `leaf_indices` are members of the set committed to by the
+    /// merkle `root` and return these cosets if they are. Fails otherwise.
+    fn dequeue_and_authenticate_cosets(
+        &self,
+        leaf_indices: &[usize],
+        root: Digest,
+        proof_stream: &mut ProofStream<ProofItem, H>,
+    ) -> Result<Vec<Vec<XFieldElement>>> {
+        let cosets = proof_stream.dequeue()?.as_fri_cosets()?;
+        let auth_paths = proof_stream
+            .dequeue()?
+            .as_compressed_authentication_paths()?;
+        if cosets
+            .iter()
+            .any(|coset| coset.len() != self.folding_factor)
+        {
+            return Err(anyhow::Error::new(FriValidationError::BadSizedProof));
+        }
+        let digests: Vec<Digest> = cosets
+            .par_iter()
+            .map(|coset| Self::leaf_digest(coset))
+            .collect();
+        if MerkleTree::<H, Maker>::verify_authentication_structure_from_leaves(
+            root,
+            leaf_indices,
+            &digests,
+            &auth_paths,
+        ) {
+            Ok(cosets)
+        } else {
+            Err(anyhow::Error::new(
+                FriValidationError::BadMerkleAuthenticationPath,
+            ))
+        }
+    }
+
     /// Create a FRI proof and return chosen indices of round 0 and Merkle root of round 0 codeword
     pub fn prove(
         &self,
@@ -142,30 +225,45 @@ impl<H: AlgebraicHasher> Fri<H> {
             self.commit(codeword, proof_stream)?.into_iter().unzip();
 
         // Fiat-Shamir to get indices
+        enqueue_proof_of_work(proof_stream, self.num_grinding_bits);
         let top_level_indices: Vec<usize> = self.sample_indices(&proof_stream.prover_fiat_shamir());
 
         // query phase
-        // query step 0: enqueue authentication paths for all points `A` into proof stream
-        let initial_a_indices: Vec<usize> = top_level_indices.clone();
-        Self::enqueue_auth_pairs(&initial_a_indices, codeword, &merkle_trees[0], proof_stream);
-        // query step 1: loop over FRI rounds, enqueue authentication paths for all points `B`
-        let mut current_domain_len = self.domain.length;
-        let mut b_indices: Vec<usize> = initial_a_indices;
-        // the last codeword is transmitted to the verifier in the clear. Thus, no co-linearity
-        // check is needed for the last codeword and we only have to look at the interval given here
-        for r in 0..merkle_trees.len() - 1 {
-            debug_assert_eq!(
-                codewords[r].len(),
-                current_domain_len,
-                "The current domain length needs to be the same as the length of the \
-                current codeword"
-            );
-            b_indices = b_indices
-                .iter()
-                .map(|x| (x + current_domain_len / 2) % current_domain_len)
-                .collect();
-            Self::enqueue_auth_pairs(&b_indices, &codewords[r], &merkle_trees[r], proof_stream);
-            current_domain_len /= 2;
+        let mut a_indices: Vec<usize> = top_level_indices.clone();
+        if self.coset_leaves {
+            // loop over FRI rounds, enqueue the leaves holding the cosets of all points `A`, and
+            // their authentication paths. The last codeword is transmitted in the clear.
+            for r in 0..merkle_trees.len() - 1 {
+                let num_leaves = codewords[r].len() / self.folding_factor;
+                a_indices = a_indices.iter().map(|x| x % num_leaves).collect();
+                self.enqueue_cosets(&a_indices, &codewords[r], &merkle_trees[r], proof_stream);
+            }
+        } else {
+            // query step 0: enqueue authentication paths for all points `A` into proof stream
+            Self::enqueue_auth_pairs(&a_indices, codeword, &merkle_trees[0], proof_stream);
+            // query step 1: loop over FRI rounds, enqueue authentication paths for all points that
+            // are folded together with the points `A`
+            let mut current_domain_len = self.domain.length;
+            // the last codeword is transmitted to the verifier in the clear. Thus, no co-linearity
+            // check is needed for the last codeword and we only have to look at the interval given
+            // here
+            for r in 0..merkle_trees.len() - 1 {
+                debug_assert_eq!(
+                    codewords[r].len(),
+                    current_domain_len,
+                    "The current domain length needs to be the same as the length of the \
+                    current codeword"
+                );
+                let sibling_indices = self.sibling_indices(&a_indices, current_domain_len);
+                Self::enqueue_auth_pairs(
+                    &sibling_indices,
+                    &codewords[r],
+                    &merkle_trees[r],
+                    proof_stream,
+                );
+                current_domain_len /= self.folding_factor;
+                a_indices = a_indices.iter().map(|x| x % current_domain_len).collect();
+            }
         }
 
         let merkle_root_of_1st_round: Digest = merkle_trees[0].get_root();
@@ -182,25 +280,15 @@ impl<H: AlgebraicHasher> Fri<H> {
         let mut offset = self.domain.offset;
         let mut codeword_local = codeword.to_vec();
 
-        let one: XFieldElement = XFieldElement::one();
-        let two: XFieldElement = one + one;
-        let two_inv = one / two;
-
         // Compute and send Merkle root
-        let mut digests: Vec<Digest> = Vec::with_capacity(codeword_local.len());
-        codeword_local
-            .clone()
-            .into_par_iter()
-            .map(|xfe| H::hash(&xfe))
-            .collect_into_vec(&mut digests);
-        let mut mt: MerkleTree<H, Maker> = Maker::from_digests(&digests);
+        let mut mt: MerkleTree<H, Maker> = self.merkle_tree(&codeword_local, 0);
         let mut mt_root: Digest = mt.get_root();
 
         proof_stream.enqueue(&ProofItem::MerkleRoot(mt_root));
         let mut values_and_merkle_trees = vec![(codeword_local.clone(), mt)];
 
-        let (num_rounds, _) = self.num_rounds();
-        for _round in 0..num_rounds {
+        let num_rounds = self.num_rounds().0 as usize;
+        for round in 0..num_rounds {
             let n = codeword_local.len();
 
             // Get challenge
@@ -214,31 +302,27 @@ impl<H: AlgebraicHasher> Fri<H> {
                 .collect();
 
             let x_offset_inverses = XFieldElement::batch_inversion(x_offset);
-            codeword_local = (0..n / 2)
+            let folded_len = n / self.folding_factor;
+            codeword_local = (0..folded_len)
                 .into_par_iter()
                 .map(|i| {
-                    two_inv
-                        * ((one + alpha * x_offset_inverses[i]) * codeword_local[i]
-                            + (one - alpha * x_offset_inverses[i]) * codeword_local[n / 2 + i])
+                    let coset = (0..self.folding_factor).map(|j| i + j * folded_len);
+                    let values = coset.clone().map(|k| codeword_local[k]).collect_vec();
+                    let x_inverses = coset.map(|k| x_offset_inverses[k]).collect_vec();
+                    Self::fold_coset(&values, &x_inverses, alpha)
                 })
                 .collect();
 
             // Compute and send Merkle root. We have to do that within this loops, since
             // the next round's alpha must be calculated from the previous round's Merkle root.
-            codeword_local
-                .clone()
-                .into_par_iter()
-                .map(|xfe| H::hash(&xfe))
-                .collect_into_vec(&mut digests);
-
-            mt = Maker::from_digests(&digests);
+            mt = self.merkle_tree(&codeword_local, round + 1);
             mt_root = mt.get_root();
             proof_stream.enqueue(&ProofItem::MerkleRoot(mt_root));
             values_and_merkle_trees.push((codeword_local.clone(), mt));
 
             // Update subgroup generator and offset
-            subgroup_generator = subgroup_generator * subgroup_generator;
-            offset = offset * offset;
+            subgroup_generator = subgroup_generator.mod_pow_u32(self.folding_factor as u32);
+            offset = offset.mod_pow_u32(self.folding_factor as u32);
         }
 
         // Send the last codeword
@@ -248,15 +332,16 @@ impl<H: AlgebraicHasher> Fri<H> {
         Ok(values_and_merkle_trees)
     }
 
-    // Return the c-indices for the 1st round of FRI
-    fn sample_indices(&self, seed: &Digest) -> Vec<usize> {
+    /// Sample the indices of the points `A` in the first round's codeword, i.e., indices into the
+    /// full FRI domain.
+    pub(crate) fn sample_indices(&self, seed: &Digest) -> Vec<usize> {
         // This algorithm starts with the inner-most indices to pick up
-        // to `last_codeword_length` indices from the codeword in the last round.
-        // It then calculates the indices in the subsequent rounds by choosing
-        // between the two possible next indices in the next round until we get
-        // the c-indices for the first round.
-        let num_rounds = self.num_rounds().0;
-        let last_codeword_length = self.domain.length >> num_rounds;
+        // to `last_codeword_length` distinct indices from the codeword in the last round.
+        // It then lifts every index into the previous round's domain by choosing one of the
+        // `folding_factor` many indices that fold into it, using fresh randomness for every
+        // index and every round, until it arrives at indices into the first round's domain.
+        let num_rounds = self.num_rounds().0 as usize;
+        let last_codeword_length = self.domain_length(num_rounds);
         assert!(
             self.colinearity_checks_count <= last_codeword_length,
             "Requested number of indices must not exceed length of last codeword"
@@ -275,37 +360,36 @@ impl<H: AlgebraicHasher> Fri<H> {
             counter += 1;
         }
 
-        // Use last indices to derive first c-indices
+        // Lift the last round's indices through all rounds into the first round's domain
         let mut indices = last_indices;
-        for i in 1..num_rounds {
-            let codeword_length = last_codeword_length << i;
+        for round in (0..num_rounds).rev() {
+            let folded_codeword_length = self.domain_length(round + 1);
 
             indices = indices
                 .into_par_iter()
                 .zip((counter..counter + self.colinearity_checks_count).into_par_iter())
-                .map(|(index, _count)| {
+                .map(|(index, count)| {
                     let mut seed_local = seed.to_sequence();
-                    seed_local.append(&mut counter.to_sequence());
+                    seed_local.append(&mut count.to_sequence());
                     let digest: Digest = H::hash_slice(&seed_local);
-                    let reduce_modulo: bool = H::sample_index(&digest, 2) == 0;
-                    if reduce_modulo {
-                        index + codeword_length / 2
-                    } else {
-                        index
-                    }
+                    let coset_position = H::sample_index(&digest, self.folding_factor);
+                    index + coset_position * folded_codeword_length
                 })
                 .collect();
+            counter += self.colinearity_checks_count;
         }
 
         indices
     }
 
+    /// Verify the FRI proof and return the indices of the first round's points `A` together with
+    /// the authenticated values of the first codeword at those indices.
     pub fn verify(
         &self,
         proof_stream: &mut ProofStream<ProofItem, H>,
         first_codeword_mt_root: &Digest,
         maybe_profiler: &mut Option<TritonProfiler>,
-    ) -> Result<()> {
+    ) -> Result<Vec<(usize, XFieldElement)>> {
         prof_start!(maybe_profiler, "init");
         let (num_rounds, degree_of_last_round) = self.num_rounds();
         let num_rounds = num_rounds as usize;
@@ -364,7 +448,7 @@ impl<H: AlgebraicHasher> Fri<H> {
         let last_fri_domain_generator = self
             .domain
             .generator
-            .mod_pow_u32(2u32.pow(num_rounds as u32));
+            .mod_pow_u32(self.folding_factor.pow(num_rounds as u32) as u32);
         intt::<XFieldElement>(&mut last_polynomial, last_fri_domain_generator, log_2_of_n);
 
         let last_poly_degree: isize = (Polynomial::<XFieldElement> {
@@ -387,58 +471,86 @@ impl<H: AlgebraicHasher> Fri<H> {
         prof_start!(maybe_profiler, "query phase");
         // query step 0: get "A" indices and verify set membership of corresponding values.
         prof_start!(maybe_profiler, "sample indices");
+        if !dequeue_and_check_proof_of_work(proof_stream, self.num_grinding_bits)? {
+            return Err(anyhow::Error::new(
+                FriValidationError::InsufficientProofOfWork,
+            ));
+        }
         let mut a_indices: Vec<usize> = self.sample_indices(&proof_stream.verifier_fiat_shamir());
+        let first_round_indices = a_indices.clone();
         prof_stop!(maybe_profiler, "sample indices");
         prof_start!(maybe_profiler, "dequeue and authenticate");
-        let mut a_values = Self::dequeue_and_authenticate(&a_indices, roots[0], proof_stream)?;
+        // With coset leaves, the values of the points `A` are only revealed as part of their
+        // cosets in the first round.
+        let mut a_values = match self.coset_leaves {
+            true => vec![],
+            false => Self::dequeue_and_authenticate(&a_indices, roots[0], proof_stream)?,
+        };
+        let mut first_round_values = a_values.clone();
         prof_stop!(maybe_profiler, "dequeue and authenticate");
 
-        // set up "B" for offsetting inside loop.  Note that "B" and "A" indices
-        // can be calcuated from each other.
-        let mut b_indices = a_indices.clone();
         let mut current_domain_len = self.domain.length;
 
-        // query step 1:  loop over FRI rounds, verify "B"s, compute values for "C"s
+        // query step 1:  loop over FRI rounds, verify the points that are folded together with
+        // the "A"s, compute values for "C"s
         prof_start!(maybe_profiler, "loop");
         for r in 0..num_rounds {
-            // get "B" indices and verify set membership of corresponding values
-            b_indices = b_indices
-                .iter()
-                .map(|x| (x + current_domain_len / 2) % current_domain_len)
-                .collect();
-            let b_values = Self::dequeue_and_authenticate(&b_indices, roots[r], proof_stream)?;
+            if self.coset_leaves {
+                let (opened_a_values, c_values) = self.dequeue_and_fold_cosets(
+                    &a_indices,
+                    &a_values,
+                    r,
+                    roots[r],
+                    alphas[r],
+                    proof_stream,
+                )?;
+                if r == 0 {
+                    first_round_values = opened_a_values;
+                }
+                a_values = c_values;
+                current_domain_len /= self.folding_factor;
+                a_indices = a_indices.iter().map(|x| x % current_domain_len).collect();
+                continue;
+            }
+
+            // get indices of the rest of the "A"s' cosets and verify set membership of the
+            // corresponding values
+            let sibling_indices = self.sibling_indices(&a_indices, current_domain_len);
+            let sibling_values =
+                Self::dequeue_and_authenticate(&sibling_indices, roots[r], proof_stream)?;
+            let num_siblings = self.folding_factor - 1;
             debug_assert_eq!(
                 self.colinearity_checks_count,
                 a_indices.len(),
                 "There must be equally many 'a indices' as there are colinearity checks."
             );
-            debug_assert_eq!(
-                self.colinearity_checks_count,
-                b_indices.len(),
-                "There must be equally many 'b indices' as there are colinearity checks."
-            );
             debug_assert_eq!(
                 self.colinearity_checks_count,
                 a_values.len(),
                 "There must be equally many 'a values' as there are colinearity checks."
             );
             debug_assert_eq!(
-                self.colinearity_checks_count,
-                b_values.len(),
-                "There must be equally many 'b values' as there are colinearity checks."
+                self.colinearity_checks_count * num_siblings,
+                sibling_values.len(),
+                "There must be 'folding factor - 1' many sibling values per colinearity check."
             );
 
-            // compute "C" indices and values for next round from "A" and "B`"" of current round
-            current_domain_len /= 2;
+            // compute "C" indices and values for next round from the cosets of current round
+            current_domain_len /= self.folding_factor;
             let c_indices = a_indices.iter().map(|x| x % current_domain_len).collect();
             let c_values = (0..self.colinearity_checks_count)
                 .into_par_iter()
                 .map(|i| {
-                    Polynomial::<XFieldElement>::get_colinear_y(
-                        (self.get_evaluation_argument(a_indices[i], r), a_values[i]),
-                        (self.get_evaluation_argument(b_indices[i], r), b_values[i]),
-                        alphas[r],
-                    )
+                    let siblings = i * num_siblings..(i + 1) * num_siblings;
+                    let coset_indices =
+                        once(&a_indices[i]).chain(sibling_indices[siblings.clone()].iter());
+                    let x_inverses = coset_indices
+                        .map(|&idx| self.get_evaluation_argument(idx, r).inverse())
+                        .collect_vec();
+                    let values = once(a_values[i])
+                        .chain(sibling_values[siblings].iter().copied())
+                        .collect_vec();
+                    Self::fold_coset(&values, &x_inverses, alphas[r])
                 })
                 .collect();
 
@@ -453,13 +565,23 @@ impl<H: AlgebraicHasher> Fri<H> {
         // enclosing scope) with last codeword from the proofstream.
         prof_start!(maybe_profiler, "compare last codeword");
         a_indices = a_indices.iter().map(|x| x % current_domain_len).collect();
-        if (0..self.colinearity_checks_count).any(|i| last_codeword[a_indices[i]] != a_values[i]) {
+        // With coset leaves and without any folding rounds, no values of the points `A` were
+        // opened. The first codeword is then the last one, which was transmitted in the clear.
+        let values_were_opened = !self.coset_leaves || num_rounds > 0;
+        if !values_were_opened {
+            first_round_values = a_indices.iter().map(|&i| last_codeword[i]).collect();
+        } else if (0..self.colinearity_checks_count)
+            .any(|i| last_codeword[a_indices[i]] != a_values[i])
+        {
             return Err(anyhow::Error::new(
                 FriValidationError::MismatchingLastCodeword,
             ));
         }
         prof_stop!(maybe_profiler, "compare last codeword");
-        Ok(())
+        Ok(first_round_indices
+            .into_iter()
+            .zip_eq(first_round_values)
+            .collect())
     }
 
     /// Given index `i` of the FRI codeword in round `round`, compute the corresponding value in the
@@ -467,26 +589,165 @@ impl<H: AlgebraicHasher> Fri<H> {
     /// [STARK-Anatomy](https://neptune.cash/learn/stark-anatomy/fri/#split-and-fold).
     fn get_evaluation_argument(&self, idx: usize, round: usize) -> XFieldElement {
         let domain_value = self.domain.offset * self.domain.generator.mod_pow_u32(idx as u32);
-        let round_exponent = 2u32.pow(round as u32);
+        let round_exponent = self.folding_factor.pow(round as u32) as u32;
         let evaluation_argument = domain_value.mod_pow_u32(round_exponent);
 
         evaluation_argument.lift()
     }
 
-    fn num_rounds(&self) -> (u8, u32) {
+    /// The length of the codeword in round `round`.
+    pub(crate) fn domain_length(&self, round: usize) -> usize {
+        self.domain.length / self.folding_factor.pow(round as u32)
+    }
+
+    /// For every index into a codeword of length `domain_length`, the indices of the other
+    /// `folding_factor - 1` elements of its coset, i.e., the elements it is folded together with.
+    /// Indices `i` and `i + domain_length / folding_factor` are folded together.
+    pub(crate) fn sibling_indices(&self, indices: &[usize], domain_length: usize) -> Vec<usize> {
+        let coset_distance = domain_length / self.folding_factor;
+        indices
+            .iter()
+            .flat_map(|&i| {
+                (1..self.folding_factor).map(move |j| (i + j * coset_distance) % domain_length)
+            })
+            .collect()
+    }
+
+    /// Dequeue and authenticate the cosets of the points `A` of round `round`, check that they
+    /// contain the `a_values` computed in the previous round, and fold them into the values of the
+    /// next round's codeword. Returns the values of the points `A` as opened in their cosets, and
+    /// the folded values.
+    fn dequeue_and_fold_cosets(
+        &self,
+        a_indices: &[usize],
+        a_values: &[XFieldElement],
+        round: usize,
+        root: Digest,
+        alpha: XFieldElement,
+        proof_stream: &mut ProofStream<ProofItem, H>,
+    ) -> Result<(Vec<XFieldElement>, Vec<XFieldElement>)> {
+        let num_leaves = self.domain_length(round) / self.folding_factor;
+        let leaf_indices = a_indices.iter().map(|x| x % num_leaves).collect_vec();
+        let cosets = self.dequeue_and_authenticate_cosets(&leaf_indices, root, proof_stream)?;
+        let opened_a_values = a_indices
+            .iter()
+            .zip_eq(cosets.iter())
+            .map(|(a_index, coset)| coset[a_index / num_leaves])
+            .collect_vec();
+        if round > 0 && opened_a_values != a_values {
+            return Err(anyhow::Error::new(
+                FriValidationError::MismatchingCosetValue,
+            ));
+        }
+
+        let c_values = leaf_indices
+            .par_iter()
+            .zip_eq(cosets.par_iter())
+            .map(|(&leaf_index, coset)| {
+                let x_inverses = (0..self.folding_factor)
+                    .map(|j| leaf_index + j * num_leaves)
+                    .map(|idx| self.get_evaluation_argument(idx, round).inverse())
+                    .collect_vec();
+                Self::fold_coset(coset, &x_inverses, alpha)
+            })
+            .collect();
+        Ok((opened_a_values, c_values))
+    }
+
+    /// The number of codeword elements per Merkle leaf in round `round`: the folding factor if
+    /// [`coset_leaves`](Self::coset_leaves) is set and the round's codeword gets folded, else 1.
+    pub(crate) fn leaf_width(&self, round: usize) -> usize {
+        let num_rounds = self.num_rounds().0 as usize;
+        match self.coset_leaves && round < num_rounds {
+            true => self.folding_factor,
+            false => 1,
+        }
+    }
+
+    /// The Merkle tree committing to the codeword of round `round`. Leaf `i` holds the elements at
+    /// indices `i`, `i + num_leaves`, `i + 2·num_leaves`, …, which is a single element unless
+    /// leaves are cosets.
+    pub(crate) fn merkle_tree(
+        &self,
+        codeword: &[XFieldElement],
+        round: usize,
+    ) -> MerkleTree<H, Maker> {
+        let num_leaves = codeword.len() / self.leaf_width(round);
+        let digests: Vec<Digest> = (0..num_leaves)
+            .into_par_iter()
+            .map(|leaf_index| Self::leaf_digest(&Self::leaf(codeword, num_leaves, leaf_index)))
+            .collect();
+        Maker::from_digests(&digests)
+    }
+
+    /// The codeword elements in leaf `leaf_index` of a Merkle tree with `num_leaves` many leaves.
+    pub(crate) fn leaf(
+        codeword: &[XFieldElement],
+        num_leaves: usize,
+        leaf_index: usize,
+    ) -> Vec<XFieldElement> {
+        codeword
+            .iter()
+            .skip(leaf_index)
+            .step_by(num_leaves)
+            .copied()
+            .collect()
+    }
+
+    /// The digest of a Merkle leaf. For a leaf of one element, this is the element's digest.
+    pub(crate) fn leaf_digest(leaf: &[XFieldElement]) -> Digest {
+        let coefficients = leaf.iter().flat_map(|xfe| xfe.coefficients).collect_vec();
+        H::hash_slice(&coefficients)
+    }
+
+    /// Fold the values of a codeword on one coset `{x·ζ^j}` of the `folding_factor`-th roots of
+    /// unity `ζ^j` into the value of the next round's codeword at `x^folding_factor`. Writing the
+    /// codeword's polynomial as `f(X) = Σ_t X^t·f_t(X^k)`, the folded polynomial is
+    /// `Σ_t α^t·f_t(Y)`. Its value is `(1/k)·Σ_j f(x_j)·Σ_t (α/x_j)^t`, which coincides with the
+    /// value at `α` of the polynomial interpolating the coset's values. For a folding factor of 2,
+    /// this is the line through both values.
+    ///
+    /// The values and the inverses of their points may be given in any order.
+    fn fold_coset(
+        values: &[XFieldElement],
+        x_inverses: &[XFieldElement],
+        alpha: XFieldElement,
+    ) -> XFieldElement {
+        let folding_factor = values.len();
+        let folding_factor_inverse = BFieldElement::new(folding_factor as u64).inverse();
+        let sum = values
+            .iter()
+            .zip_eq(x_inverses.iter())
+            .map(|(&value, &x_inverse)| {
+                let alpha_over_x = alpha * x_inverse;
+                let mut power = XFieldElement::one();
+                let mut geometric_sum = XFieldElement::zero();
+                for _ in 0..folding_factor {
+                    geometric_sum += power;
+                    power *= alpha_over_x;
+                }
+                value * geometric_sum
+            })
+            .fold(XFieldElement::zero(), |acc, summand| acc + summand);
+        sum * folding_factor_inverse
+    }
+
+    /// The number of folding rounds, and the maximal degree of the polynomial interpolating the
+    /// last codeword. The last codeword is at least as long as the number of colinearity checks,
+    /// such that distinct indices can be sampled from it.
+    pub(crate) fn num_rounds(&self) -> (u8, u32) {
         let max_degree = (self.domain.length / self.expansion_factor) - 1;
-        let mut rounds_count = log_2_ceil(max_degree as u128 + 1) as u8;
-        let mut max_degree_of_last_round = 0u32;
-        if self.expansion_factor < self.colinearity_checks_count {
-            let num_missed_rounds = log_2_ceil(
-                (self.colinearity_checks_count as f64 / self.expansion_factor as f64).ceil()
-                    as u128,
-            ) as u8;
-            rounds_count -= num_missed_rounds;
-            max_degree_of_last_round = 2u32.pow(num_missed_rounds as u32) - 1;
+        let log_2_of_degree_bound = log_2_ceil(max_degree as u128 + 1) as usize;
+        let log_2_of_folding_factor = log_2_floor(self.folding_factor as u128) as usize;
+        let mut rounds_count = log_2_of_degree_bound / log_2_of_folding_factor;
+        while rounds_count > 0 && self.domain_length(rounds_count) < self.colinearity_checks_count {
+            rounds_count -= 1;
         }
+        let log_2_of_last_degree_bound =
+            log_2_of_degree_bound - rounds_count * log_2_of_folding_factor;
+        let max_degree_of_last_round = 2u32.pow(log_2_of_last_degree_bound as u32) - 1;
 
-        (rounds_count, max_degree_of_last_round)
+        (rounds_count as u8, max_degree_of_last_round)
     }
 }
 
@@ -497,11 +758,13 @@ mod triton_xfri_tests {
     use rand::thread_rng;
     use rand::RngCore;
     use twenty_first::shared_math::b_field_element::BFieldElement;
+    use twenty_first::shared_math::other::random_elements;
     use twenty_first::shared_math::rescue_prime_regular::RescuePrimeRegular;
     use twenty_first::shared_math::traits::CyclicGroupGenerator;
     use twenty_first::shared_math::traits::ModPowU32;
     use twenty_first::shared_math::x_field_element::XFieldElement;
     use twenty_first::test_shared::corrupt_digest;
+    use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
     use twenty_first::utils::has_unique_elements;
 
     use super::*;
@@ -523,6 +786,41 @@ mod triton_xfri_tests {
         );
     }
 
+    #[test]
+    fn sampled_indices_range_over_first_round_domain_test() {
+        type H = RescuePrimeRegular;
+        let mut rng = thread_rng();
+
+        let subgroup_order = 1 << 14;
+        let expansion_factor = 4;
+        let colinearity_checks = 20;
+        for folding_factor in [2, 4, 8] {
+            let mut fri: Fri<H> = get_x_field_fri_test_object::<H>(
+                subgroup_order,
+                expansion_factor,
+                colinearity_checks,
+            );
+            fri.folding_factor = folding_factor;
+            let coset_block_length = fri.domain.length / folding_factor;
+
+            let mut hit_coset_blocks = vec![false; folding_factor];
+            for _ in 0..10 {
+                let seed = H::hash(&BFieldElement::new(rng.next_u64()));
+                let indices = fri.sample_indices(&seed);
+                assert_eq!(colinearity_checks, indices.len());
+                for index in indices {
+                    assert!(index < fri.domain.length);
+                    hit_coset_blocks[index / coset_block_length] = true;
+                }
+            }
+            assert!(
+                hit_coset_blocks.into_iter().all(|hit| hit),
+                "Sampled indices must range over the entire first round's domain, \
+                 folding factor {folding_factor}"
+            );
+        }
+    }
+
     #[test]
     fn get_rounds_count_test() {
         type Hasher = RescuePrimeRegular;
@@ -586,6 +884,126 @@ mod triton_xfri_tests {
         assert_eq!((3, 7), fri.num_rounds());
     }
 
+    #[test]
+    fn get_rounds_count_with_higher_folding_factor_test() {
+        type Hasher = RescuePrimeRegular;
+
+        let subgroup_order = 512;
+        let expansion_factor = 4;
+        let mut fri: Fri<Hasher> =
+            get_x_field_fri_test_object::<Hasher>(subgroup_order, expansion_factor, 2);
+
+        fri.folding_factor = 4;
+        assert_eq!((3, 1), fri.num_rounds());
+        fri.colinearity_checks_count = 16;
+        assert_eq!((2, 7), fri.num_rounds());
+
+        fri.folding_factor = 8;
+        fri.colinearity_checks_count = 2;
+        assert_eq!((2, 1), fri.num_rounds());
+        fri.colinearity_checks_count = 16;
+        assert_eq!((1, 15), fri.num_rounds());
+    }
+
+    #[test]
+    fn prove_and_verify_with_higher_folding_factor_test() {
+        type Hasher = RescuePrimeRegular;
+
+        let subgroup_order = 1024;
+        let expansion_factor = 4;
+        let colinearity_check_count = 6;
+        for folding_factor in [4, 8, 16] {
+            let mut fri: Fri<Hasher> = get_x_field_fri_test_object(
+                subgroup_order,
+                expansion_factor,
+                colinearity_check_count,
+            );
+            fri.folding_factor = folding_factor;
+            let max_degree = subgroup_order as usize / expansion_factor - 1;
+
+            let polynomial = Polynomial::new(random_elements::<XFieldElement>(max_degree + 1));
+            let codeword = fri.domain.evaluate(&polynomial);
+            let mut proof_stream: ProofStream<ProofItem, Hasher> = ProofStream::new();
+            let (indices, merkle_root_of_round_0) =
+                fri.prove(&codeword, &mut proof_stream).unwrap();
+            let verdict = fri.verify(&mut proof_stream, &merkle_root_of_round_0, &mut None);
+            let revealed = match verdict {
+                Ok(revealed) => revealed,
+                Err(e) => panic!("Found error for folding factor {folding_factor}: {e}"),
+            };
+            let expected = indices.iter().map(|&i| (i, codeword[i])).collect_vec();
+            assert_eq!(expected, revealed);
+
+            let too_high = fri.domain.generator.lift().get_cyclic_group_elements(None);
+            let too_high = too_high
+                .iter()
+                .map(|p| p.mod_pow_u32(max_degree as u32 + 1))
+                .collect_vec();
+            let mut proof_stream: ProofStream<ProofItem, Hasher> = ProofStream::new();
+            let (_, merkle_root_of_round_0) = fri.prove(&too_high, &mut proof_stream).unwrap();
+            let verdict = fri.verify(&mut proof_stream, &merkle_root_of_round_0, &mut None);
+            assert!(verdict.is_err());
+        }
+    }
+
+    #[test]
+    fn prove_and_verify_with_coset_leaves_test() {
+        type Hasher = RescuePrimeRegular;
+
+        let subgroup_order = 1024;
+        let expansion_factor = 4;
+        let colinearity_check_count = 6;
+        for folding_factor in [2, 4, 8] {
+            let mut fri: Fri<Hasher> = get_x_field_fri_test_object(
+                subgroup_order,
+                expansion_factor,
+                colinearity_check_count,
+            );
+            fri.folding_factor = folding_factor;
+            let max_degree = subgroup_order as usize / expansion_factor - 1;
+            let polynomial = Polynomial::new(random_elements::<XFieldElement>(max_degree + 1));
+            let codeword = fri.domain.evaluate(&polynomial);
+
+            let mut element_leaves_proof_stream: ProofStream<ProofItem, Hasher> =
+                ProofStream::new();
+            fri.prove(&codeword, &mut element_leaves_proof_stream)
+                .unwrap();
+
+            fri.coset_leaves = true;
+            let mut proof_stream: ProofStream<ProofItem, Hasher> = ProofStream::new();
+            let (indices, merkle_root_of_round_0) =
+                fri.prove(&codeword, &mut proof_stream).unwrap();
+            let verdict = fri.verify(&mut proof_stream, &merkle_root_of_round_0, &mut None);
+            let revealed = match verdict {
+                Ok(revealed) => revealed,
+                Err(e) => panic!("Found error for folding factor {folding_factor}: {e}"),
+            };
+            let expected = indices.iter().map(|&i| (i, codeword[i])).collect_vec();
+            assert_eq!(expected, revealed);
+            assert!(
+                num_authentication_paths(&proof_stream)
+                    < num_authentication_paths(&element_leaves_proof_stream)
+            );
+            // Folding by 2, the revealed elements about make up for the shorter paths.
+            if folding_factor > 2 {
+                assert!(
+                    proof_stream.transcript_length()
+                        < element_leaves_proof_stream.transcript_length()
+                );
+            }
+
+            let too_high = fri.domain.generator.lift().get_cyclic_group_elements(None);
+            let too_high = too_high
+                .iter()
+                .map(|p| p.mod_pow_u32(max_degree as u32 + 1))
+                .collect_vec();
+            let mut proof_stream: ProofStream<ProofItem, Hasher> = ProofStream::new();
+            let (_, merkle_root_of_round_0) = fri.prove(&too_high, &mut proof_stream).unwrap();
+            let verdict = fri.verify(&mut proof_stream, &merkle_root_of_round_0, &mut None);
+            assert!(verdict.is_err());
+        }
+    }
+
     #[test]
     fn fri_on_x_field_test() {
         type Hasher = RescuePrimeRegular;
@@ -679,7 +1097,19 @@ mod triton_xfri_tests {
         assert!(verify_result.is_err());
     }
 
-    fn get_x_field_fri_test_object<H: AlgebraicHasher>(
+    fn num_authentication_paths<H: ProofHasher>(proof_stream: &ProofStream<ProofItem, H>) -> usize {
+        proof_stream
+            .items
+            .iter()
+            .map(|item| match item {
+                ProofItem::FriResponse(response) => response.0.len(),
+                ProofItem::CompressedAuthenticationPaths(paths) => paths.len(),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    fn get_x_field_fri_test_object<H: ProofHasher>(
         subgroup_order: u64,
         expansion_factor: usize,
         colinearity_checks: usize,
@@ -689,7 +1119,9 @@ mod triton_xfri_tests {
             offset,
             subgroup_order as usize,
             expansion_factor,
+            2,
             colinearity_checks,
+            0,
         );
         fri
     }
@@ -713,10 +1145,10 @@ mod triton_xfri_tests {
 
         let (_, merkle_root_of_round_0) = fri.prove(&codeword, &mut prover_proof_stream).unwrap();
 
-        let proof = prover_proof_stream.to_proof();
+        let transcript = prover_proof_stream.to_transcript();
 
         let mut verifier_proof_stream: ProofStream<ProofItem, Hasher> =
-            ProofStream::from_proof(&proof).unwrap();
+            ProofStream::from_transcript(&transcript).unwrap();
 
         for (left, right) in prover_proof_stream
             .items
@@ -0,0 +1,495 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::anyhow;
+use anyhow::bail;
+use anyhow::Context;
+use anyhow::Result;
+use num_traits::Zero;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::rescue_prime_digest::Digest;
+use twenty_first::shared_math::traits::Inverse;
+
+use triton_opcodes::instruction::DivinationHint;
+use triton_opcodes::ord_n::Ord16::ST0;
+
+use crate::bfield_codec::BFieldCodec;
+use crate::error::vm_err;
+use crate::error::InstructionError::InverseOfZero;
+use crate::state::VMState;
+
+/// A source of public or secret input for Triton VM. Instruction `read_io` reads from the public
+/// input, instructions `divine` and `divine_sibling` read from the secret input. The VM asks for
+/// elements only when an instruction consumes them, allowing input to be computed on demand.
+pub trait InputProvider {
+    /// The next element of the input, or `None` if the input is exhausted.
+    fn next_element(&mut self) -> Result<Option<BFieldElement>>;
+}
+
+/// Input held in a vector, read front to back by advancing a cursor. Reading an element takes
+/// constant time, no matter how long the input is.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputCursor {
+    elements: Vec<BFieldElement>,
+    position: usize,
+}
+
+impl InputCursor {
+    pub fn new(elements: Vec<BFieldElement>) -> Self {
+        Self {
+            elements,
+            position: 0,
+        }
+    }
+
+    /// The number of elements read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The elements not read yet.
+    pub fn remaining(&self) -> &[BFieldElement] {
+        &self.elements[self.position..]
+    }
+}
+
+impl From<Vec<BFieldElement>> for InputCursor {
+    fn from(elements: Vec<BFieldElement>) -> Self {
+        Self::new(elements)
+    }
+}
+
+impl InputProvider for InputCursor {
+    fn next_element(&mut self) -> Result<Option<BFieldElement>> {
+        let element = self.elements.get(self.position).copied();
+        if element.is_some() {
+            self.position += 1;
+        }
+        Ok(element)
+    }
+}
+
+/// Input held in memory, built up from values in the order in which the program reads them.
+///
+/// Values are encoded using [`BFieldCodec`], and the encoding is put on the stream such that after
+/// reading all of a value's `n` elements, element `i` of the encoding is stack element `st{n-1-i}`
+/// – that is, the first element of the encoding ends up on top of the stack. For example, an
+/// `XFieldElement` read using `divine divine divine` has its constant term in `st0`, and a `u64`
+/// read using `read_io read_io` has its lower 32 bits in `st0` and its upper 32 bits in `st1`, as
+/// after instruction `split`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputStream {
+    elements: VecDeque<BFieldElement>,
+}
+
+impl InputStream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a value, which the program reads using as many instructions `read_io` or `divine` as
+    /// the encoding of the value is long.
+    pub fn push<T: BFieldCodec>(&mut self, value: &T) {
+        self.elements.extend(value.encode().into_iter().rev());
+    }
+
+    /// Append an authentication path, which the program reads using one instruction
+    /// `divine_sibling` per digest, starting with the sibling of the leaf.
+    pub fn push_authentication_path(&mut self, authentication_path: &[Digest]) {
+        for digest in authentication_path {
+            self.push(digest);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    pub fn to_vec(&self) -> Vec<BFieldElement> {
+        self.elements.iter().copied().collect()
+    }
+}
+
+impl From<Vec<BFieldElement>> for InputStream {
+    fn from(elements: Vec<BFieldElement>) -> Self {
+        Self {
+            elements: elements.into(),
+        }
+    }
+}
+
+impl InputProvider for InputStream {
+    fn next_element(&mut self) -> Result<Option<BFieldElement>> {
+        Ok(self.elements.pop_front())
+    }
+}
+
+/// Input produced lazily by an iterator, for example one that computes a witness only when the
+/// program asks for it.
+#[derive(Debug, Clone)]
+pub struct LazyInput<I: Iterator<Item = BFieldElement>> {
+    elements: I,
+}
+
+impl<I: Iterator<Item = BFieldElement>> LazyInput<I> {
+    pub fn new(elements: I) -> Self {
+        Self { elements }
+    }
+}
+
+impl<F: FnMut() -> Option<BFieldElement>> LazyInput<std::iter::FromFn<F>> {
+    /// Input produced by calling `callback` whenever the program reads an element. The callback
+    /// returns `None` once the input is exhausted.
+    pub fn from_fn(callback: F) -> Self {
+        Self::new(std::iter::from_fn(callback))
+    }
+}
+
+impl<I: Iterator<Item = BFieldElement>> InputProvider for LazyInput<I> {
+    fn next_element(&mut self) -> Result<Option<BFieldElement>> {
+        Ok(self.elements.next())
+    }
+}
+
+/// Input read from a file of whitespace-separated field elements, in the notation accepted by
+/// [`parse_bfield_element`]. The file is read line by line, as the program consumes the input.
+#[derive(Debug)]
+pub struct InputFile {
+    reader: BufReader<File>,
+    line_number: usize,
+    pending_elements: VecDeque<BFieldElement>,
+}
+
+impl InputFile {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("cannot read input from {}", path.display()))?;
+        Ok(Self {
+            reader: BufReader::new(file),
+            line_number: 0,
+            pending_elements: VecDeque::new(),
+        })
+    }
+}
+
+impl InputProvider for InputFile {
+    fn next_element(&mut self) -> Result<Option<BFieldElement>> {
+        while self.pending_elements.is_empty() {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            self.line_number += 1;
+            for word in line.split_whitespace() {
+                let element = parse_bfield_element(word)
+                    .with_context(|| format!("in line {} of input", self.line_number))?;
+                self.pending_elements.push_back(element);
+            }
+        }
+        Ok(self.pending_elements.pop_front())
+    }
+}
+
+/// Computes the element a hinted `divine`, like `divine_inverse`, pushes onto the stack. The
+/// element is computed from `state`, the state of the VM executing the hinted `divine`.
+///
+/// Closures `FnMut(DivinationHint, &VMState) -> Result<BFieldElement>` are hint handlers, too. A
+/// handler that computes only some hints itself can delegate the others to
+/// [`DefaultHintHandler`].
+pub trait HintHandler {
+    fn divine(&mut self, hint: DivinationHint, state: &VMState) -> Result<BFieldElement>;
+}
+
+impl<F: FnMut(DivinationHint, &VMState) -> Result<BFieldElement>> HintHandler for F {
+    fn divine(&mut self, hint: DivinationHint, state: &VMState) -> Result<BFieldElement> {
+        self(hint, state)
+    }
+}
+
+/// Computes every hint as documented on [`DivinationHint`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DefaultHintHandler;
+
+impl HintHandler for DefaultHintHandler {
+    fn divine(&mut self, hint: DivinationHint, state: &VMState) -> Result<BFieldElement> {
+        let st0 = state.op_stack.safe_peek(ST0);
+        match hint {
+            DivinationHint::Inverse if st0.is_zero() => vm_err(InverseOfZero),
+            DivinationHint::Inverse => Ok(st0.inverse()),
+            DivinationHint::Lsb => Ok(BFieldElement::new(st0.value() % 2)),
+        }
+    }
+}
+
+/// Parse a field element given in decimal, in hexadecimal with prefix `0x`, or as a negative
+/// decimal, which denotes the additive inverse.
+pub fn parse_bfield_element(word: &str) -> Result<BFieldElement> {
+    let (negate, magnitude) = match word.strip_prefix('-') {
+        Some(magnitude) => (true, magnitude),
+        None => (false, word),
+    };
+    let value = match magnitude.strip_prefix("0x") {
+        Some(hex_digits) => u64::from_str_radix(hex_digits, 16),
+        None => magnitude.parse(),
+    }
+    .map_err(|err| anyhow!("invalid field element “{word}”: {err}"))?;
+    if value >= BFieldElement::P {
+        bail!(
+            "invalid field element “{word}”: not smaller than {}",
+            BFieldElement::P
+        );
+    }
+    let element = BFieldElement::new(value);
+    match negate {
+        true => Ok(-element),
+        false => Ok(element),
+    }
+}
+
+#[cfg(test)]
+mod input_tests {
+    use twenty_first::shared_math::other::random_elements;
+    use twenty_first::shared_math::rescue_prime_digest::DIGEST_LENGTH;
+    use twenty_first::shared_math::x_field_element::XFieldElement;
+
+    use triton_opcodes::program::Program;
+
+    use crate::error::InstructionError;
+    use crate::error::InstructionError::*;
+    use crate::vm::run;
+    use crate::vm::run_and_observe;
+    use crate::vm::run_with_input;
+    use crate::vm::simulate;
+    use crate::vm::simulate_with_input;
+
+    use super::*;
+
+    #[test]
+    fn parse_bfield_element_test() {
+        assert_eq!(BFieldElement::new(42), parse_bfield_element("42").unwrap());
+        assert_eq!(
+            BFieldElement::new(42),
+            parse_bfield_element("0x2a").unwrap()
+        );
+        assert_eq!(
+            BFieldElement::new(42),
+            parse_bfield_element("0x2A").unwrap()
+        );
+        assert_eq!(-BFieldElement::new(1), parse_bfield_element("-1").unwrap());
+        assert_eq!(
+            BFieldElement::new(BFieldElement::P - 1),
+            parse_bfield_element("0xffffffff00000000").unwrap()
+        );
+        assert!(parse_bfield_element("0xffffffff00000001").is_err());
+        assert!(parse_bfield_element("18446744069414584321").is_err());
+        assert!(parse_bfield_element("forty-two").is_err());
+        assert!(parse_bfield_element("").is_err());
+    }
+
+    #[test]
+    fn values_end_up_on_the_stack_in_encoding_order_test() {
+        let u64_value = 0x1234_5678_9abc_def0_u64;
+        let xfe: XFieldElement = random_elements(1)[0];
+        let digest: Digest = random_elements(1)[0];
+
+        let mut input = InputStream::new();
+        input.push(&u64_value);
+        input.push(&7_u32);
+        let mut secret_input = InputStream::new();
+        secret_input.push(&xfe);
+        secret_input.push(&digest);
+
+        let code = "
+            read_io read_io write_io write_io
+            read_io write_io
+            divine divine divine write_io write_io write_io
+            divine divine divine divine divine write_io write_io write_io write_io write_io
+            halt
+        ";
+        let program = Program::from_code(code).unwrap();
+        let (_, output, err) = simulate_with_input(
+            &program,
+            &mut input,
+            &mut secret_input,
+            &mut DefaultHintHandler,
+        );
+        assert!(err.is_none(), "{}", err.unwrap());
+        assert!(input.is_empty());
+        assert!(secret_input.is_empty());
+
+        let expected_output = [
+            vec![
+                BFieldElement::new(0x9abc_def0),
+                BFieldElement::new(0x1234_5678),
+            ],
+            vec![BFieldElement::new(7)],
+            xfe.coefficients.to_vec(),
+            digest.values().to_vec(),
+        ]
+        .concat();
+        assert_eq!(expected_output, output);
+    }
+
+    #[test]
+    fn authentication_path_is_read_by_divine_sibling_test() {
+        let sibling: Digest = random_elements(1)[0];
+        let mut secret_input = InputStream::new();
+        secret_input.push_authentication_path(&[sibling]);
+
+        // with node index 3, the known digest is the right child, the sibling is the left child
+        let code = format!(
+            "push 3 {} divine_sibling write_io write_io write_io write_io write_io halt",
+            ["push 0"; 2 * DIGEST_LENGTH].join(" ")
+        );
+        let program = Program::from_code(&code).unwrap();
+        let (_, output, err) = run_with_input(
+            &program,
+            &mut InputCursor::default(),
+            &mut secret_input,
+            &mut DefaultHintHandler,
+        );
+        assert!(err.is_none(), "{}", err.unwrap());
+        assert_eq!(sibling.values().to_vec(), output);
+    }
+
+    #[test]
+    fn lazy_input_is_only_read_on_demand_test() {
+        let mut num_requested_elements = 0;
+        let mut secret_input = LazyInput::from_fn(|| {
+            num_requested_elements += 1;
+            Some(BFieldElement::new(num_requested_elements))
+        });
+
+        let program = Program::from_code("divine divine add write_io halt").unwrap();
+        let (_, output, err) = simulate_with_input(
+            &program,
+            &mut InputCursor::default(),
+            &mut secret_input,
+            &mut DefaultHintHandler,
+        );
+        assert!(err.is_none(), "{}", err.unwrap());
+        assert_eq!(vec![BFieldElement::new(3)], output);
+        assert_eq!(2, num_requested_elements);
+    }
+
+    #[test]
+    fn exhausted_input_is_an_error_test() {
+        let program = Program::from_code("read_io read_io halt").unwrap();
+        let mut input = LazyInput::new([BFieldElement::new(1)].into_iter());
+        let (_, _, err) = simulate_with_input(
+            &program,
+            &mut input,
+            &mut InputCursor::default(),
+            &mut DefaultHintHandler,
+        );
+        assert!(err.is_some());
+
+        let program = Program::from_code("divine_sibling halt").unwrap();
+        let mut secret_input = InputStream::from(vec![BFieldElement::new(1); 4]);
+        let (_, _, err) = run_with_input(
+            &program,
+            &mut InputCursor::default(),
+            &mut secret_input,
+            &mut DefaultHintHandler,
+        );
+        assert!(err.is_some());
+    }
+
+    #[test]
+    fn input_errors_are_reported_with_partial_trace_test() {
+        let expect_error = |err: Option<anyhow::Error>, expected: InstructionError| {
+            let err = err.expect("execution must fail");
+            let actual = err.downcast_ref::<InstructionError>().unwrap();
+            assert_eq!(expected.to_string(), actual.to_string());
+        };
+
+        let program = Program::from_code("read_io read_io halt").unwrap();
+        let (aet, _, err) = simulate(&program, vec![BFieldElement::new(1)], vec![]);
+        expect_error(err, PublicInputExhausted);
+        assert_eq!(2, aet.processor_trace.nrows());
+        let mut num_states = 0;
+        let (_, _, err) = run_and_observe(&program, vec![BFieldElement::new(1)], vec![], |_| {
+            num_states += 1
+        });
+        expect_error(err, PublicInputExhausted);
+        assert_eq!(2, num_states);
+
+        let program = Program::from_code("divine divine halt").unwrap();
+        let (aet, _, err) = simulate(&program, vec![], vec![BFieldElement::new(1)]);
+        expect_error(err, SecretInputExhausted);
+        assert_eq!(2, aet.processor_trace.nrows());
+
+        let code = format!(
+            "push 4294967296 {} divine_sibling halt",
+            ["push 0"; 2 * DIGEST_LENGTH].join(" ")
+        );
+        let program = Program::from_code(&code).unwrap();
+        let secret_input = vec![BFieldElement::new(1); 5];
+        let mut num_states = 0;
+        let (_, _, err) = run_and_observe(&program, vec![], secret_input, |_| num_states += 1);
+        expect_error(err, NonU32NodeIndex(BFieldElement::new(1 << 32)));
+        assert_eq!(2 * DIGEST_LENGTH + 2, num_states);
+    }
+
+    #[test]
+    fn default_hint_handler_test() {
+        let code = "push 7 divine_inverse mul write_io push 6 divine_lsb write_io \
+            push 7 divine_lsb write_io halt";
+        let program = Program::from_code(code).unwrap();
+        let (_, output, err) = run(&program, vec![], vec![]);
+        assert!(err.is_none(), "{}", err.unwrap());
+        let expected_output = [1, 0, 1].map(BFieldElement::new).to_vec();
+        assert_eq!(expected_output, output);
+
+        let program = Program::from_code("push 0 divine_inverse halt").unwrap();
+        let (_, _, err) = run(&program, vec![], vec![]);
+        assert!(err.is_some());
+    }
+
+    #[test]
+    fn custom_hint_handler_test() {
+        let mut num_inverses = 0;
+        let mut hint_handler = |hint: DivinationHint, state: &VMState| match hint {
+            DivinationHint::Lsb => Ok(BFieldElement::new(state.instruction_pointer as u64)),
+            _ => {
+                num_inverses += 1;
+                DefaultHintHandler.divine(hint, state)
+            }
+        };
+
+        let code = "push 5 divine_inverse mul write_io divine_lsb write_io divine write_io halt";
+        let program = Program::from_code(code).unwrap();
+        let mut secret_input = InputCursor::from(vec![BFieldElement::new(42)]);
+        let (_, output, err) = simulate_with_input(
+            &program,
+            &mut InputCursor::default(),
+            &mut secret_input,
+            &mut hint_handler,
+        );
+        assert!(err.is_none(), "{}", err.unwrap());
+        let expected_output = [1, 5, 42].map(BFieldElement::new).to_vec();
+        assert_eq!(expected_output, output);
+        assert_eq!(1, num_inverses);
+    }
+
+    #[test]
+    fn input_file_test() {
+        let path = std::env::temp_dir().join(format!("input_file_test_{}", std::process::id()));
+        std::fs::write(&path, "1 0x2\n\n  -1\n3 forty-two\n").unwrap();
+        let mut input = InputFile::open(&path).unwrap();
+
+        assert_eq!(Some(BFieldElement::new(1)), input.next_element().unwrap());
+        assert_eq!(Some(BFieldElement::new(2)), input.next_element().unwrap());
+        assert_eq!(Some(-BFieldElement::new(1)), input.next_element().unwrap());
+        let err = input.next_element().unwrap_err();
+        assert!(format!("{err:#}").contains("line 4"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,12 +1,21 @@
 pub mod arithmetic_domain;
 pub mod bfield_codec;
+pub mod debugger;
 pub mod error;
+pub mod execution_limits;
+pub mod execution_profile;
+pub mod execution_trace;
 pub mod fri;
+pub mod input;
 pub mod op_stack;
 pub mod proof;
+pub mod proof_estimate;
 pub mod proof_item;
+pub mod proof_of_work;
 pub mod proof_stream;
+pub mod recursion;
 pub mod shared_tests;
+pub mod soundness;
 pub mod stark;
 pub mod state;
 pub mod table;
@@ -1,21 +1,624 @@
+use std::error::Error;
+use std::fmt;
+
+use anyhow::bail;
+use num_traits::Zero;
 use serde::Deserialize;
 use serde::Serialize;
 use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::rescue_prime_digest::Digest;
+use twenty_first::shared_math::rescue_prime_regular::RescuePrimeRegular;
+use twenty_first::shared_math::rescue_prime_regular::RescuePrimeRegularState;
+use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
+use twenty_first::util_types::algebraic_hasher::Domain;
+use twenty_first::util_types::algebraic_hasher::SpongeHasher;
+use twenty_first::util_types::algebraic_hasher::RATE;
+
+use crate::bfield_codec::BFieldCodec;
+
+/// The first bytes of every serialized proof.
+pub const PROOF_MAGIC: [u8; 4] = *b"TVMP";
+
+/// The version of the serialized proof format. Proofs of any other version are rejected.
+pub const PROOF_FORMAT_VERSION: u32 = 1;
+
+const CHECKSUM_LENGTH: usize = blake3::OUT_LEN;
+
+/// Magic, version, hash function, 9 header fields, and the transcript length.
+const PREAMBLE_LENGTH: usize = PROOF_MAGIC.len() + 4 + 4 + 10 * 8;
+
+/// The hash function a proof's Merkle trees and Fiat-Shamir challenges are computed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HashFunction {
+    /// Algebraic, hence cheap to verify inside Triton VM. Use it for proofs to be verified
+    /// recursively.
+    RescuePrimeRegular,
+
+    /// Fast on regular hardware. Use it for proofs that are only ever verified natively.
+    Blake3,
+}
+
+impl HashFunction {
+    fn id(&self) -> u32 {
+        match self {
+            HashFunction::RescuePrimeRegular => 0,
+            HashFunction::Blake3 => 1,
+        }
+    }
+
+    fn from_id(id: u32) -> Option<Self> {
+        match id {
+            0 => Some(HashFunction::RescuePrimeRegular),
+            1 => Some(HashFunction::Blake3),
+            _ => None,
+        }
+    }
+}
+
+/// A hasher proofs can be made with, identified by the [`HashFunction`] recorded in the header
+/// of those proofs. Its sponge derives the Fiat-Shamir challenges from the proof stream, see
+/// [`ProofStream`](crate::proof_stream::ProofStream).
+pub trait ProofHasher: AlgebraicHasher {
+    const HASH_FUNCTION: HashFunction;
+
+    type SpongeState: Clone + fmt::Debug;
+
+    /// The state of the sponge before anything is absorbed.
+    fn sponge_init() -> Self::SpongeState;
+
+    fn sponge_absorb(sponge: &mut Self::SpongeState, input: &[BFieldElement; RATE]);
+
+    fn sponge_squeeze(sponge: &mut Self::SpongeState) -> [BFieldElement; RATE];
+}
+
+/// The sponge of Triton VM's instructions `absorb_init`, `absorb`, and `squeeze`. Absorbing into
+/// the initial state is the same as instruction `absorb_init`.
+impl ProofHasher for RescuePrimeRegular {
+    const HASH_FUNCTION: HashFunction = HashFunction::RescuePrimeRegular;
+
+    type SpongeState = RescuePrimeRegularState;
+
+    fn sponge_init() -> Self::SpongeState {
+        RescuePrimeRegularState::new(Domain::VariableLength)
+    }
+
+    fn sponge_absorb(sponge: &mut Self::SpongeState, input: &[BFieldElement; RATE]) {
+        <Self as SpongeHasher>::absorb(sponge, input)
+    }
+
+    fn sponge_squeeze(sponge: &mut Self::SpongeState) -> [BFieldElement; RATE] {
+        <Self as SpongeHasher>::squeeze(sponge)
+    }
+}
+
+/// Absorbing updates an incremental Blake3 hasher. Squeezing reads from its extendable output,
+/// then absorbs what was read, such that consecutive squeezes differ.
+impl ProofHasher for blake3::Hasher {
+    const HASH_FUNCTION: HashFunction = HashFunction::Blake3;
+
+    type SpongeState = blake3::Hasher;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Proof(pub Vec<BFieldElement>);
+    fn sponge_init() -> Self::SpongeState {
+        blake3::Hasher::new()
+    }
+
+    fn sponge_absorb(sponge: &mut Self::SpongeState, input: &[BFieldElement; RATE]) {
+        for element in input {
+            sponge.update(&element.value().to_be_bytes());
+        }
+    }
+
+    fn sponge_squeeze(sponge: &mut Self::SpongeState) -> [BFieldElement; RATE] {
+        let mut bytes = [0; RATE * 8];
+        sponge.finalize_xof().fill(&mut bytes);
+        sponge.update(&bytes);
+        let mut output = [BFieldElement::zero(); RATE];
+        for (element, chunk) in output.iter_mut().zip(bytes.chunks_exact(8)) {
+            *element = BFieldElement::new(u64::from_be_bytes(chunk.try_into().unwrap()));
+        }
+        output
+    }
+}
+
+/// Everything a verifier needs to know about a proof before looking at its transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofHeader {
+    pub version: u32,
+    pub hash_function: HashFunction,
+    pub security_level: usize,
+    pub fri_expansion_factor: usize,
+    pub fri_folding_factor: usize,
+    pub fri_coset_leaves: bool,
+    pub num_colinearity_checks: usize,
+    pub num_trace_randomizers: usize,
+    pub num_grinding_bits: usize,
+    pub zero_knowledge: bool,
+    pub padded_height: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Proof {
+    pub header: ProofHeader,
+    pub transcript: Vec<BFieldElement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofFormatError {
+    BadMagic,
+    UnsupportedVersion(u32),
+    UnknownHashFunction(u32),
+    Truncated,
+    LengthMismatch { expected: usize, actual: usize },
+    ChecksumMismatch,
+    NonCanonicalElement(u64),
+    InvalidZeroKnowledgeFlag(u64),
+    InvalidCosetLeavesFlag(u64),
+}
+
+impl Error for ProofFormatError {}
+
+impl fmt::Display for ProofFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Malformed proof: {self:?}")
+    }
+}
 
 impl Proof {
     pub fn padded_height(&self) -> usize {
-        // FIXME: This is very brittle.
-        self.0[1].value() as usize
+        self.header.padded_height
+    }
+
+    /// Serialize the proof. All integers are little-endian. The layout is
+    ///
+    /// | magic | version: u32 | hash function: u32 | security level: u64 |
+    /// | FRI expansion factor: u64 | FRI folding factor: u64 | FRI coset leaves: u64, 0 or 1 |
+    /// | number of colinearity checks: u64 | number of trace randomizers: u64 |
+    /// | number of grinding bits: u64 |
+    /// | zero-knowledge: u64, 0 or 1 | padded height: u64 |
+    /// | transcript length: u64 | transcript: canonical u64 each |
+    /// | blake3 checksum of everything before |
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let header = &self.header;
+        let num_bytes = PREAMBLE_LENGTH + 8 * self.transcript.len() + CHECKSUM_LENGTH;
+        let mut bytes = Vec::with_capacity(num_bytes);
+        bytes.extend_from_slice(&PROOF_MAGIC);
+        bytes.extend_from_slice(&header.version.to_le_bytes());
+        bytes.extend_from_slice(&header.hash_function.id().to_le_bytes());
+        for field in [
+            header.security_level,
+            header.fri_expansion_factor,
+            header.fri_folding_factor,
+            header.fri_coset_leaves as usize,
+            header.num_colinearity_checks,
+            header.num_trace_randomizers,
+            header.num_grinding_bits,
+            header.zero_knowledge as usize,
+            header.padded_height,
+            self.transcript.len(),
+        ] {
+            bytes.extend_from_slice(&(field as u64).to_le_bytes());
+        }
+        for element in self.transcript.iter() {
+            bytes.extend_from_slice(&element.value().to_le_bytes());
+        }
+        let checksum = blake3::hash(&bytes);
+        bytes.extend_from_slice(checksum.as_bytes());
+        bytes
     }
+
+    /// Deserialize a proof produced by [`Proof::to_bytes`]. Proofs of a different format version
+    /// are rejected before anything else but the magic is looked at.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofFormatError> {
+        let mut reader = ByteReader { bytes, index: 0 };
+        if reader.take(PROOF_MAGIC.len())? != PROOF_MAGIC {
+            return Err(ProofFormatError::BadMagic);
+        }
+        let version = u32::from_le_bytes(reader.take(4)?.try_into().unwrap());
+        if version != PROOF_FORMAT_VERSION {
+            return Err(ProofFormatError::UnsupportedVersion(version));
+        }
+
+        if bytes.len() < PREAMBLE_LENGTH + CHECKSUM_LENGTH {
+            return Err(ProofFormatError::Truncated);
+        }
+        let (content, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LENGTH);
+        if blake3::hash(content).as_bytes() != checksum {
+            return Err(ProofFormatError::ChecksumMismatch);
+        }
+
+        let hash_function_id = u32::from_le_bytes(reader.take(4)?.try_into().unwrap());
+        let hash_function = HashFunction::from_id(hash_function_id)
+            .ok_or(ProofFormatError::UnknownHashFunction(hash_function_id))?;
+        let security_level = reader.take_u64()? as usize;
+        let fri_expansion_factor = reader.take_u64()? as usize;
+        let fri_folding_factor = reader.take_u64()? as usize;
+        let fri_coset_leaves = reader.take_flag(ProofFormatError::InvalidCosetLeavesFlag)?;
+        let num_colinearity_checks = reader.take_u64()? as usize;
+        let num_trace_randomizers = reader.take_u64()? as usize;
+        let num_grinding_bits = reader.take_u64()? as usize;
+        let zero_knowledge = reader.take_flag(ProofFormatError::InvalidZeroKnowledgeFlag)?;
+        let padded_height = reader.take_u64()? as usize;
+        let transcript_length = reader.take_u64()? as usize;
+
+        let expected = transcript_length
+            .checked_mul(8)
+            .and_then(|len| len.checked_add(PREAMBLE_LENGTH + CHECKSUM_LENGTH))
+            .ok_or(ProofFormatError::Truncated)?;
+        if bytes.len() != expected {
+            return Err(ProofFormatError::LengthMismatch {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+
+        let mut transcript = Vec::with_capacity(transcript_length);
+        for _ in 0..transcript_length {
+            let value = reader.take_u64()?;
+            if value >= BFieldElement::P {
+                return Err(ProofFormatError::NonCanonicalElement(value));
+            }
+            transcript.push(BFieldElement::new(value));
+        }
+
+        let header = ProofHeader {
+            version,
+            hash_function,
+            security_level,
+            fri_expansion_factor,
+            fri_folding_factor,
+            fri_coset_leaves,
+            num_colinearity_checks,
+            num_trace_randomizers,
+            num_grinding_bits,
+            zero_knowledge,
+            padded_height,
+        };
+        Ok(Proof { header, transcript })
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    index: usize,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, num_bytes: usize) -> Result<&'a [u8], ProofFormatError> {
+        let end = self.index + num_bytes;
+        let slice = self
+            .bytes
+            .get(self.index..end)
+            .ok_or(ProofFormatError::Truncated)?;
+        self.index = end;
+        Ok(slice)
+    }
+
+    fn take_u64(&mut self) -> Result<u64, ProofFormatError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// A boolean encoded as a u64 that is either 0 or 1. Any other value is turned into an error
+    /// by `invalid_flag`.
+    fn take_flag(
+        &mut self,
+        invalid_flag: fn(u64) -> ProofFormatError,
+    ) -> Result<bool, ProofFormatError> {
+        match self.take_u64()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            flag => Err(invalid_flag(flag)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Claim {
     pub input: Vec<BFieldElement>,
-    pub program: Vec<BFieldElement>,
+    pub program: ClaimedProgram,
     pub output: Vec<BFieldElement>,
     pub padded_height: usize,
 }
+
+/// How a [`Claim`] identifies the program it is about. The prover needs the full program, the
+/// verifier only its digest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClaimedProgram {
+    /// The program as given by `Program::to_bwords`.
+    Code(Vec<BFieldElement>),
+
+    /// The Rescue-Prime digest of the program as given by `Program::to_bwords`.
+    Digest(Digest),
+}
+
+const CLAIMED_PROGRAM_CODE: u64 = 0;
+const CLAIMED_PROGRAM_DIGEST: u64 = 1;
+
+impl ClaimedProgram {
+    pub fn digest(&self) -> Digest {
+        match self {
+            ClaimedProgram::Code(code) => RescuePrimeRegular::hash_slice(code),
+            ClaimedProgram::Digest(digest) => *digest,
+        }
+    }
+
+    /// The program, unless only its digest is known.
+    pub fn code(&self) -> Option<&[BFieldElement]> {
+        match self {
+            ClaimedProgram::Code(code) => Some(code),
+            ClaimedProgram::Digest(_) => None,
+        }
+    }
+}
+
+impl Claim {
+    /// The same claim, identifying the program by its digest. This is all a verifier needs.
+    pub fn with_program_digest(&self) -> Self {
+        Self {
+            program: ClaimedProgram::Digest(self.program.digest()),
+            ..self.clone()
+        }
+    }
+}
+
+/// The encoding is
+///
+/// | input length | input | program kind | program length | program | output length | output |
+/// | padded height |
+///
+/// where the program kind is 0 if the program is given in full and 1 if given by its digest.
+impl BFieldCodec for Claim {
+    fn decode(sequence: &[BFieldElement]) -> anyhow::Result<Box<Self>> {
+        let (input, sequence) = split_length_prepended(sequence)?;
+        let Some((program_kind, sequence)) = sequence.split_first() else {
+            bail!("cannot decode claim: missing program kind");
+        };
+        let (program, sequence) = split_length_prepended(sequence)?;
+        let program = match program_kind.value() {
+            CLAIMED_PROGRAM_CODE => ClaimedProgram::Code(program.to_vec()),
+            CLAIMED_PROGRAM_DIGEST => ClaimedProgram::Digest(*Digest::decode(program)?),
+            kind => bail!("cannot decode claim: unknown program kind {kind}"),
+        };
+        let (output, sequence) = split_length_prepended(sequence)?;
+        let [padded_height] = sequence else {
+            bail!("cannot decode claim: expected exactly one element for the padded height");
+        };
+        Ok(Box::new(Claim {
+            input: input.to_vec(),
+            program,
+            output: output.to_vec(),
+            padded_height: padded_height.value() as usize,
+        }))
+    }
+
+    fn encode(&self) -> Vec<BFieldElement> {
+        let (program_kind, program) = match &self.program {
+            ClaimedProgram::Code(code) => (CLAIMED_PROGRAM_CODE, code.clone()),
+            ClaimedProgram::Digest(digest) => (CLAIMED_PROGRAM_DIGEST, digest.encode()),
+        };
+        let mut sequence = vec![];
+        sequence.append(&mut length_prepended(&self.input));
+        sequence.push(BFieldElement::new(program_kind));
+        sequence.append(&mut length_prepended(&program));
+        sequence.append(&mut length_prepended(&self.output));
+        sequence.push(BFieldElement::new(self.padded_height as u64));
+        sequence
+    }
+}
+
+fn length_prepended(elements: &[BFieldElement]) -> Vec<BFieldElement> {
+    let mut sequence = vec![BFieldElement::new(elements.len() as u64)];
+    sequence.extend_from_slice(elements);
+    sequence
+}
+
+/// Split a sequence into the length-prepended part at its beginning and the remainder.
+fn split_length_prepended(
+    sequence: &[BFieldElement],
+) -> anyhow::Result<(&[BFieldElement], &[BFieldElement])> {
+    let Some((length, sequence)) = sequence.split_first() else {
+        bail!("cannot decode claim: missing length");
+    };
+    let length = length.value() as usize;
+    if sequence.len() < length {
+        bail!(
+            "cannot decode claim: prepended length {length} exceeds remaining length {}",
+            sequence.len()
+        );
+    }
+    Ok(sequence.split_at(length))
+}
+
+#[cfg(test)]
+mod proof_tests {
+    use twenty_first::shared_math::other::random_elements;
+    use twenty_first::shared_math::other::random_elements_array;
+
+    use super::*;
+
+    fn random_proof() -> Proof {
+        let header = ProofHeader {
+            version: PROOF_FORMAT_VERSION,
+            hash_function: HashFunction::RescuePrimeRegular,
+            security_level: 160,
+            fri_expansion_factor: 4,
+            fri_folding_factor: 2,
+            fri_coset_leaves: false,
+            num_colinearity_checks: 80,
+            num_trace_randomizers: 160,
+            num_grinding_bits: 0,
+            zero_knowledge: true,
+            padded_height: 256,
+        };
+        Proof {
+            header,
+            transcript: random_elements(100),
+        }
+    }
+
+    #[test]
+    fn proof_bytes_round_trip_test() {
+        let proof = random_proof();
+        let bytes = proof.to_bytes();
+        assert_eq!(proof, Proof::from_bytes(&bytes).unwrap());
+        assert_eq!(256, Proof::from_bytes(&bytes).unwrap().padded_height());
+
+        let empty_proof = Proof {
+            transcript: vec![],
+            ..proof
+        };
+        let bytes = empty_proof.to_bytes();
+        assert_eq!(empty_proof, Proof::from_bytes(&bytes).unwrap());
+
+        let mut blake3_proof = empty_proof;
+        blake3_proof.header.hash_function = HashFunction::Blake3;
+        let bytes = blake3_proof.to_bytes();
+        assert_eq!(blake3_proof, Proof::from_bytes(&bytes).unwrap());
+
+        let mut non_zk_proof = blake3_proof;
+        non_zk_proof.header.zero_knowledge = false;
+        let bytes = non_zk_proof.to_bytes();
+        assert_eq!(non_zk_proof, Proof::from_bytes(&bytes).unwrap());
+
+        let mut coset_leaves_proof = non_zk_proof;
+        coset_leaves_proof.header.fri_coset_leaves = true;
+        let bytes = coset_leaves_proof.to_bytes();
+        assert_eq!(coset_leaves_proof, Proof::from_bytes(&bytes).unwrap());
+    }
+
+    #[test]
+    fn reject_malformed_proof_bytes_test() {
+        let bytes = random_proof().to_bytes();
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] ^= 1;
+        assert_eq!(
+            Err(ProofFormatError::BadMagic),
+            Proof::from_bytes(&bad_magic)
+        );
+
+        let mut other_version = bytes.clone();
+        other_version[4..8].copy_from_slice(&(PROOF_FORMAT_VERSION + 1).to_le_bytes());
+        assert_eq!(
+            Err(ProofFormatError::UnsupportedVersion(
+                PROOF_FORMAT_VERSION + 1
+            )),
+            Proof::from_bytes(&other_version)
+        );
+
+        let mut flipped_bit = bytes.clone();
+        flipped_bit[PREAMBLE_LENGTH + 3] ^= 1;
+        assert_eq!(
+            Err(ProofFormatError::ChecksumMismatch),
+            Proof::from_bytes(&flipped_bit)
+        );
+
+        assert_eq!(
+            Err(ProofFormatError::Truncated),
+            Proof::from_bytes(&bytes[..6])
+        );
+        assert!(Proof::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn reject_non_canonical_transcript_element_test() {
+        let mut bytes = random_proof().to_bytes();
+        bytes.truncate(bytes.len() - CHECKSUM_LENGTH);
+        bytes[PREAMBLE_LENGTH..PREAMBLE_LENGTH + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        let checksum = blake3::hash(&bytes);
+        bytes.extend_from_slice(checksum.as_bytes());
+        assert_eq!(
+            Err(ProofFormatError::NonCanonicalElement(u64::MAX)),
+            Proof::from_bytes(&bytes)
+        );
+    }
+
+    fn random_claim() -> Claim {
+        Claim {
+            input: random_elements(5),
+            program: ClaimedProgram::Code(random_elements(20)),
+            output: random_elements(3),
+            padded_height: 512,
+        }
+    }
+
+    #[test]
+    fn claim_encode_decode_test() {
+        let claim = random_claim();
+        assert_eq!(claim, *Claim::decode(&claim.encode()).unwrap());
+
+        let compact_claim = claim.with_program_digest();
+        assert_eq!(claim.program.digest(), compact_claim.program.digest());
+        assert!(compact_claim.program.code().is_none());
+        assert_eq!(
+            compact_claim,
+            *Claim::decode(&compact_claim.encode()).unwrap()
+        );
+        assert!(compact_claim.encode().len() < claim.encode().len());
+
+        let empty_claim = Claim {
+            input: vec![],
+            program: ClaimedProgram::Code(vec![]),
+            output: vec![],
+            padded_height: 0,
+        };
+        assert_eq!(empty_claim, *Claim::decode(&empty_claim.encode()).unwrap());
+    }
+
+    #[test]
+    fn claim_decode_random_negative_test() {
+        let encoding = random_claim().encode();
+        assert!(Claim::decode(&encoding[..encoding.len() - 1]).is_err());
+        assert!(Claim::decode(&[encoding.clone(), vec![BFieldElement::new(1)]].concat()).is_err());
+        assert!(Claim::decode(&[]).is_err());
+
+        let mut unknown_program_kind = encoding;
+        unknown_program_kind[6] = BFieldElement::new(2);
+        assert!(Claim::decode(&unknown_program_kind).is_err());
+
+        let garbage: [BFieldElement; 10] = random_elements_array();
+        assert!(Claim::decode(&garbage).is_err());
+    }
+
+    #[test]
+    fn reject_unknown_hash_function_test() {
+        let mut bytes = random_proof().to_bytes();
+        bytes.truncate(bytes.len() - CHECKSUM_LENGTH);
+        bytes[8..12].copy_from_slice(&7_u32.to_le_bytes());
+        let checksum = blake3::hash(&bytes);
+        bytes.extend_from_slice(checksum.as_bytes());
+        assert_eq!(
+            Err(ProofFormatError::UnknownHashFunction(7)),
+            Proof::from_bytes(&bytes)
+        );
+    }
+
+    #[test]
+    fn reject_invalid_zero_knowledge_flag_test() {
+        // magic, version, hash function, and 7 header fields precede the flag
+        let flag_position = PROOF_MAGIC.len() + 4 + 4 + 7 * 8;
+        let mut bytes = random_proof().to_bytes();
+        bytes.truncate(bytes.len() - CHECKSUM_LENGTH);
+        bytes[flag_position..flag_position + 8].copy_from_slice(&2_u64.to_le_bytes());
+        let checksum = blake3::hash(&bytes);
+        bytes.extend_from_slice(checksum.as_bytes());
+        assert_eq!(
+            Err(ProofFormatError::InvalidZeroKnowledgeFlag(2)),
+            Proof::from_bytes(&bytes)
+        );
+    }
+
+    #[test]
+    fn reject_invalid_coset_leaves_flag_test() {
+        // magic, version, hash function, and 3 header fields precede the flag
+        let flag_position = PROOF_MAGIC.len() + 4 + 4 + 3 * 8;
+        let mut bytes = random_proof().to_bytes();
+        bytes.truncate(bytes.len() - CHECKSUM_LENGTH);
+        bytes[flag_position..flag_position + 8].copy_from_slice(&3_u64.to_le_bytes());
+        let checksum = blake3::hash(&bytes);
+        bytes.extend_from_slice(checksum.as_bytes());
+        assert_eq!(
+            Err(ProofFormatError::InvalidCosetLeavesFlag(3)),
+            Proof::from_bytes(&bytes)
+        );
+    }
+}
@@ -0,0 +1,400 @@
+use std::fmt::Display;
+
+use twenty_first::shared_math::other::log_2_floor;
+use twenty_first::shared_math::rescue_prime_digest::DIGEST_LENGTH;
+use twenty_first::shared_math::x_field_element::EXTENSION_DEGREE;
+use twenty_first::util_types::algebraic_hasher::RATE;
+
+use crate::stark::Stark;
+use crate::stark::StarkHasher;
+use crate::stark::StarkParameters;
+use crate::table::challenges::AllChallenges;
+use crate::table::master_table::num_all_table_quotients;
+use crate::table::master_table::NUM_BASE_COLUMNS;
+use crate::table::master_table::NUM_EXT_COLUMNS;
+
+/// The encoding of every proof item is prefixed by its length.
+const ITEM_LENGTH_PREFIX: usize = 1;
+
+/// Every partial authentication path is prefixed by its encoding's length, its length as a path,
+/// and the mask of the digests it contains.
+const AUTHENTICATION_PATH_PREFIX: usize = 3;
+
+/// Every element of a FRI response is prefixed by its path's length and the mask of the digests
+/// it contains.
+const FRI_RESPONSE_ELEMENT_PREFIX: usize = 2;
+
+/// The predicted length of a proof's transcript, in number of `BFieldElement`s, broken down by the
+/// kind of [`ProofItem`](crate::proof_item::ProofItem). Every item's length prefix is included.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProofItemLengths {
+    pub padded_height: usize,
+    pub merkle_roots: usize,
+    pub master_base_table_rows: usize,
+    pub master_ext_table_rows: usize,
+    pub out_of_domain_rows: usize,
+    pub out_of_domain_quotient_value: usize,
+    pub revealed_quotient_elements: usize,
+    pub fri_codeword: usize,
+    pub fri_responses: usize,
+    pub fri_cosets: usize,
+    pub compressed_authentication_paths: usize,
+    pub proof_of_work: usize,
+}
+
+impl ProofItemLengths {
+    pub fn total(&self) -> usize {
+        self.padded_height
+            + self.merkle_roots
+            + self.master_base_table_rows
+            + self.master_ext_table_rows
+            + self.out_of_domain_rows
+            + self.out_of_domain_quotient_value
+            + self.revealed_quotient_elements
+            + self.fri_codeword
+            + self.fri_responses
+            + self.fri_cosets
+            + self.compressed_authentication_paths
+            + self.proof_of_work
+    }
+}
+
+/// The number of hash function invocations needed to verify a proof. Hashing the claim's program,
+/// if the claim contains the program and not only its digest, is not included.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VerifierHashCount {
+    /// Permutations of the Fiat-Shamir sponge: absorbing every proof item and squeezing the seeds
+    /// of all challenges. Absorbing the claim is not included.
+    pub fiat_shamir: usize,
+
+    /// Challenges, weights, and indices derived from the seeds, and proof-of-work checks.
+    pub sampling: usize,
+
+    /// Leafs of all Merkle trees: revealed rows, revealed quotient leafs, revealed FRI codeword
+    /// elements or cosets, and the last FRI codeword.
+    pub leafs: usize,
+
+    /// Internal nodes of Merkle trees when authenticating the revealed leafs, at most.
+    pub merkle_nodes: usize,
+}
+
+impl VerifierHashCount {
+    pub fn total(&self) -> usize {
+        self.fiat_shamir + self.sampling + self.leafs + self.merkle_nodes
+    }
+}
+
+/// What a proof for some [`StarkParameters`] and padded height looks like, and what verifying it
+/// costs, computed without proving.
+///
+/// Everything is exact except for the authentication paths. Authentication paths of neighboring
+/// leafs share digests, which are only sent once; how many are shared depends on the randomly
+/// sampled indices. For those, and everything depending on them, the estimate is an upper bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofEstimate {
+    pub padded_height: usize,
+    pub fri_domain_length: usize,
+    pub num_fri_rounds: usize,
+    pub num_colinearity_checks: usize,
+    pub max_revealed_rows: usize,
+    pub max_authentication_digests: usize,
+    pub item_lengths: ProofItemLengths,
+    pub verifier_hashes: VerifierHashCount,
+}
+
+impl ProofEstimate {
+    pub fn new(parameters: &StarkParameters, padded_height: usize) -> Self {
+        let (_, _, fri) = Stark::<StarkHasher>::degrees_and_fri(parameters, padded_height);
+        let fri_domain_length = fri.domain.length;
+        let merkle_tree_height = log_2_floor(fri_domain_length as u128) as usize;
+        let num_fri_rounds = fri.num_rounds().0 as usize;
+        let last_codeword_length = fri.domain_length(num_fri_rounds);
+        let num_colinearity_checks = parameters.num_colinearity_checks;
+
+        // only the current row at every FRI query position, the AIR is checked out of domain
+        let max_revealed_rows = num_colinearity_checks;
+        let num_revealed_quotient_paths = num_colinearity_checks;
+        let num_table_paths = 2 * max_revealed_rows;
+
+        // Without coset leaves, there is one response for the first round's "A" indices, and one
+        // for every round's indices of the elements that are folded together with the "A"s. With
+        // coset leaves, every round has one item of cosets and one of their authentication paths.
+        let num_siblings = parameters.fri_folding_factor - 1;
+        let fri_path_heights = (0..num_fri_rounds)
+            .map(|round| fri.domain_length(round) / fri.leaf_width(round))
+            .map(|num_leaves| log_2_floor(num_leaves as u128) as usize);
+        let (num_fri_responses, num_fri_response_elements, num_fri_coset_items) =
+            match parameters.fri_coset_leaves {
+                false => (
+                    num_fri_rounds + 1,
+                    num_colinearity_checks * (1 + num_siblings * num_fri_rounds),
+                    0,
+                ),
+                true => (0, 0, num_fri_rounds),
+            };
+        let fri_path_lengths = match parameters.fri_coset_leaves {
+            false => merkle_tree_height + num_siblings * fri_path_heights.sum::<usize>(),
+            true => fri_path_heights.sum(),
+        };
+        let fri_digests = num_colinearity_checks * fri_path_lengths;
+        let (fri_response_digests, fri_coset_digests) = match parameters.fri_coset_leaves {
+            false => (fri_digests, 0),
+            true => (0, fri_digests),
+        };
+        let num_fri_coset_paths = num_fri_coset_items * num_colinearity_checks;
+
+        let num_stark_paths = num_table_paths + num_revealed_quotient_paths;
+        let table_and_quotient_digests = num_stark_paths * merkle_tree_height;
+        let max_authentication_digests = fri_digests + table_and_quotient_digests;
+
+        let num_merkle_roots = 4 + num_fri_rounds + 1;
+        let base_row_length = ITEM_LENGTH_PREFIX + NUM_BASE_COLUMNS;
+        let ext_row_length = ITEM_LENGTH_PREFIX
+            + EXTENSION_DEGREE * (NUM_EXT_COLUMNS + parameters.num_randomizer_polynomials);
+        let fri_response_element_length = FRI_RESPONSE_ELEMENT_PREFIX + EXTENSION_DEGREE;
+        let fri_coset_length =
+            ITEM_LENGTH_PREFIX + parameters.fri_folding_factor * EXTENSION_DEGREE;
+        let out_of_domain_base_row_length =
+            ITEM_LENGTH_PREFIX + EXTENSION_DEGREE * NUM_BASE_COLUMNS;
+        let out_of_domain_ext_row_length = ITEM_LENGTH_PREFIX + EXTENSION_DEGREE * NUM_EXT_COLUMNS;
+        let num_compressed_authentication_paths = 3 + num_fri_coset_items;
+
+        // one nonce, in FRI
+        let num_proof_of_work_items = match parameters.num_grinding_bits {
+            0 => 0,
+            _ => 1,
+        };
+        let item_lengths = ProofItemLengths {
+            padded_height: ITEM_LENGTH_PREFIX + 1,
+            merkle_roots: num_merkle_roots * (ITEM_LENGTH_PREFIX + DIGEST_LENGTH),
+            master_base_table_rows: ITEM_LENGTH_PREFIX + max_revealed_rows * base_row_length,
+            master_ext_table_rows: ITEM_LENGTH_PREFIX + max_revealed_rows * ext_row_length,
+            out_of_domain_rows: 2 * (out_of_domain_base_row_length + out_of_domain_ext_row_length),
+            out_of_domain_quotient_value: ITEM_LENGTH_PREFIX + EXTENSION_DEGREE,
+            revealed_quotient_elements: ITEM_LENGTH_PREFIX
+                + num_colinearity_checks * EXTENSION_DEGREE,
+            fri_codeword: ITEM_LENGTH_PREFIX + last_codeword_length * EXTENSION_DEGREE,
+            fri_responses: num_fri_responses * ITEM_LENGTH_PREFIX
+                + num_fri_response_elements * fri_response_element_length
+                + fri_response_digests * DIGEST_LENGTH,
+            fri_cosets: num_fri_coset_items
+                * (ITEM_LENGTH_PREFIX + num_colinearity_checks * fri_coset_length),
+            compressed_authentication_paths: num_compressed_authentication_paths
+                * ITEM_LENGTH_PREFIX
+                + (num_stark_paths + num_fri_coset_paths) * AUTHENTICATION_PATH_PREFIX
+                + (table_and_quotient_digests + fri_coset_digests) * DIGEST_LENGTH,
+            proof_of_work: num_proof_of_work_items * (ITEM_LENGTH_PREFIX + 1),
+        };
+
+        // Every item is absorbed padded to a multiple of the sponge's rate. For groups of items of
+        // varying lengths, the number of absorptions is bounded from above.
+        let absorptions = |length: usize, num_items: usize| (length + num_items * RATE) / RATE;
+        let num_absorptions = absorptions(item_lengths.padded_height, 1)
+            + num_merkle_roots * absorptions(ITEM_LENGTH_PREFIX + DIGEST_LENGTH, 1)
+            + absorptions(item_lengths.master_base_table_rows, 1)
+            + absorptions(item_lengths.master_ext_table_rows, 1)
+            + 2 * absorptions(out_of_domain_base_row_length, 1)
+            + 2 * absorptions(out_of_domain_ext_row_length, 1)
+            + absorptions(item_lengths.out_of_domain_quotient_value, 1)
+            + absorptions(item_lengths.revealed_quotient_elements, 1)
+            + absorptions(item_lengths.fri_codeword, 1)
+            + absorptions(item_lengths.fri_responses, num_fri_responses)
+            + absorptions(item_lengths.fri_cosets, num_fri_coset_items)
+            + absorptions(
+                item_lengths.compressed_authentication_paths,
+                num_compressed_authentication_paths,
+            )
+            + num_proof_of_work_items * absorptions(ITEM_LENGTH_PREFIX + 1, 1);
+        // 4 seeds in the STARK, one per FRI round, one for FRI's indices, and one per nonce
+        let num_squeezes = 4 + num_fri_rounds + 1 + num_proof_of_work_items;
+        let fiat_shamir = num_absorptions + num_squeezes;
+        let num_quotient_combination_weights = 2 * num_all_table_quotients();
+        let num_out_of_domain_points = 1;
+        let num_combination_weights = 2 * (NUM_BASE_COLUMNS + NUM_EXT_COLUMNS) + 4;
+        let num_fri_index_samples = num_colinearity_checks * num_fri_rounds.max(1);
+        let sampling = AllChallenges::TOTAL_CHALLENGES
+            + num_quotient_combination_weights
+            + num_out_of_domain_points
+            + num_combination_weights
+            + num_fri_index_samples
+            + num_proof_of_work_items;
+        let leafs = 2 * max_revealed_rows
+            + num_revealed_quotient_paths
+            + num_fri_response_elements
+            + num_fri_coset_paths
+            + last_codeword_length;
+        let merkle_nodes = max_authentication_digests + last_codeword_length - 1;
+        let verifier_hashes = VerifierHashCount {
+            fiat_shamir,
+            sampling,
+            leafs,
+            merkle_nodes,
+        };
+
+        Self {
+            padded_height,
+            fri_domain_length,
+            num_fri_rounds,
+            num_colinearity_checks,
+            max_revealed_rows,
+            max_authentication_digests,
+            item_lengths,
+            verifier_hashes,
+        }
+    }
+
+    /// The length of the proof's transcript, in number of `BFieldElement`s, at most.
+    pub fn max_transcript_length(&self) -> usize {
+        self.item_lengths.total()
+    }
+}
+
+impl Display for ProofEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lengths = &self.item_lengths;
+        writeln!(
+            f,
+            "padded height:                  {:>10}",
+            self.padded_height
+        )?;
+        writeln!(
+            f,
+            "FRI domain length:              {:>10}",
+            self.fri_domain_length
+        )?;
+        writeln!(
+            f,
+            "FRI rounds:                     {:>10}",
+            self.num_fri_rounds
+        )?;
+        writeln!(
+            f,
+            "colinearity checks:             {:>10}",
+            self.num_colinearity_checks
+        )?;
+        writeln!(
+            f,
+            "revealed rows, at most:         {:>10}",
+            self.max_revealed_rows
+        )?;
+        writeln!(
+            f,
+            "authentication digests, at most:{:>10}",
+            self.max_authentication_digests
+        )?;
+        writeln!(f)?;
+        writeln!(f, "proof item lengths in BFieldElements, at most")?;
+        for (name, length) in [
+            ("padded height", lengths.padded_height),
+            ("Merkle roots", lengths.merkle_roots),
+            ("master base table rows", lengths.master_base_table_rows),
+            ("master ext table rows", lengths.master_ext_table_rows),
+            ("out-of-domain rows", lengths.out_of_domain_rows),
+            (
+                "out-of-domain quotient value",
+                lengths.out_of_domain_quotient_value,
+            ),
+            ("quotient elements", lengths.revealed_quotient_elements),
+            ("FRI codeword", lengths.fri_codeword),
+            ("FRI responses", lengths.fri_responses),
+            ("FRI cosets", lengths.fri_cosets),
+            (
+                "authentication paths",
+                lengths.compressed_authentication_paths,
+            ),
+            ("proof of work", lengths.proof_of_work),
+        ] {
+            writeln!(f, "  {name:<30}{length:>10}")?;
+        }
+        writeln!(f, "  {:<30}{:>10}", "total", lengths.total())?;
+        writeln!(f)?;
+        let hashes = &self.verifier_hashes;
+        writeln!(f, "verifier hashes, at most")?;
+        for (name, count) in [
+            ("Fiat-Shamir", hashes.fiat_shamir),
+            ("sampling", hashes.sampling),
+            ("leafs", hashes.leafs),
+            ("Merkle nodes", hashes.merkle_nodes),
+        ] {
+            writeln!(f, "  {name:<30}{count:>10}")?;
+        }
+        write!(f, "  {:<30}{:>10}", "total", hashes.total())
+    }
+}
+
+#[cfg(test)]
+mod proof_estimate_tests {
+    use crate::proof_item::MayBeUncast;
+    use crate::proof_item::ProofItem;
+    use crate::proof_stream::ProofStream;
+    use crate::shared_tests::parse_simulate_prove;
+    use crate::vm::triton_vm_tests::test_hash_nop_nop_lt;
+
+    use super::*;
+
+    #[test]
+    fn estimate_matches_actual_proof_test() {
+        let code_with_input = test_hash_nop_nop_lt();
+        let (stark, proof) = parse_simulate_prove(
+            &code_with_input.source_code,
+            code_with_input.input,
+            code_with_input.secret_input,
+            &mut None,
+        );
+        let estimate = ProofEstimate::new(&stark.parameters, proof.padded_height());
+        println!("{estimate}");
+
+        assert_eq!(stark.fri.domain.length, estimate.fri_domain_length);
+        assert_eq!(stark.fri.num_rounds().0 as usize, estimate.num_fri_rounds);
+
+        let transcript_length = proof.transcript.len();
+        let digest_slack = estimate.max_authentication_digests * DIGEST_LENGTH;
+        assert!(transcript_length <= estimate.max_transcript_length());
+        assert!(estimate.max_transcript_length() - digest_slack <= transcript_length);
+
+        let proof_stream =
+            ProofStream::<ProofItem, StarkHasher>::from_transcript(&proof.transcript).unwrap();
+        let item_lengths = proof_stream
+            .items
+            .iter()
+            .map(|item| item.uncast().len())
+            .collect::<Vec<_>>();
+        assert_eq!(2 * estimate.num_fri_rounds + 19, item_lengths.len());
+
+        // padded height, 3 Merkle roots, 5 out-of-domain items, then the combination root
+        let lengths = estimate.item_lengths;
+        let out_of_domain_items = 4..9;
+        let fri_codeword_position = estimate.num_fri_rounds + 11;
+        assert_eq!(lengths.padded_height, item_lengths[0]);
+        assert_eq!(
+            lengths.merkle_roots,
+            item_lengths[1..4].iter().sum::<usize>()
+                + item_lengths[9..fri_codeword_position].iter().sum::<usize>()
+        );
+        assert_eq!(
+            lengths.out_of_domain_rows + lengths.out_of_domain_quotient_value,
+            item_lengths[out_of_domain_items].iter().sum::<usize>()
+        );
+        assert_eq!(lengths.fri_codeword, item_lengths[fri_codeword_position]);
+        let quotient_elements_position = item_lengths.len() - 2;
+        assert_eq!(
+            lengths.revealed_quotient_elements,
+            item_lengths[quotient_elements_position]
+        );
+    }
+
+    #[test]
+    fn estimate_grows_with_padded_height_test() {
+        let parameters = StarkParameters::default();
+        let small = ProofEstimate::new(&parameters, 1 << 8);
+        let large = ProofEstimate::new(&parameters, 1 << 12);
+        assert!(small.fri_domain_length < large.fri_domain_length);
+        assert!(small.num_fri_rounds < large.num_fri_rounds);
+        assert!(small.max_transcript_length() < large.max_transcript_length());
+        assert!(small.verifier_hashes.total() < large.verifier_hashes.total());
+        assert_eq!(
+            small.item_lengths.master_base_table_rows,
+            large.item_lengths.master_base_table_rows
+        );
+    }
+}
@@ -109,9 +109,15 @@ pub enum ProofItem {
     MerkleRoot(Digest),
     AuthenticationPath(Vec<Digest>),
     RevealedCombinationElements(Vec<XFieldElement>),
+    RevealedQuotientElements(Vec<XFieldElement>),
+    OutOfDomainBaseRow(Vec<XFieldElement>),
+    OutOfDomainExtRow(Vec<XFieldElement>),
+    OutOfDomainQuotientValue(XFieldElement),
     FriCodeword(Vec<XFieldElement>),
     FriResponse(FriResponse),
+    FriCosets(Vec<Vec<XFieldElement>>),
     PaddedHeight(BFieldElement),
+    ProofOfWork(BFieldElement),
     Uncast(Vec<BFieldElement>),
 }
 
@@ -230,6 +236,66 @@ where
         }
     }
 
+    pub fn as_revealed_quotient_elements(&self) -> Result<Vec<XFieldElement>> {
+        match self {
+            Self::RevealedQuotientElements(xs) => Ok(xs.to_owned()),
+            Self::Uncast(str) => match Vec::<XFieldElement>::decode(str) {
+                Ok(revealed_quotient_elements) => Ok(*revealed_quotient_elements),
+                Err(_) => Err(anyhow::Error::new(ProofStreamError::new(
+                    "cast to revealed quotient elements failed",
+                ))),
+            },
+            _ => Err(anyhow::Error::new(ProofStreamError::new(
+                "expected revealed quotient elements, but got something else",
+            ))),
+        }
+    }
+
+    pub fn as_out_of_domain_base_row(&self) -> Result<Vec<XFieldElement>> {
+        match self {
+            Self::OutOfDomainBaseRow(xs) => Ok(xs.to_owned()),
+            Self::Uncast(str) => match Vec::<XFieldElement>::decode(str) {
+                Ok(out_of_domain_base_row) => Ok(*out_of_domain_base_row),
+                Err(_) => Err(anyhow::Error::new(ProofStreamError::new(
+                    "cast to out-of-domain base row failed",
+                ))),
+            },
+            _ => Err(anyhow::Error::new(ProofStreamError::new(
+                "expected out-of-domain base row, but got something else",
+            ))),
+        }
+    }
+
+    pub fn as_out_of_domain_ext_row(&self) -> Result<Vec<XFieldElement>> {
+        match self {
+            Self::OutOfDomainExtRow(xs) => Ok(xs.to_owned()),
+            Self::Uncast(str) => match Vec::<XFieldElement>::decode(str) {
+                Ok(out_of_domain_ext_row) => Ok(*out_of_domain_ext_row),
+                Err(_) => Err(anyhow::Error::new(ProofStreamError::new(
+                    "cast to out-of-domain extension row failed",
+                ))),
+            },
+            _ => Err(anyhow::Error::new(ProofStreamError::new(
+                "expected out-of-domain extension row, but got something else",
+            ))),
+        }
+    }
+
+    pub fn as_out_of_domain_quotient_value(&self) -> Result<XFieldElement> {
+        match self {
+            Self::OutOfDomainQuotientValue(x) => Ok(x.to_owned()),
+            Self::Uncast(str) => match XFieldElement::decode(str) {
+                Ok(out_of_domain_quotient_value) => Ok(*out_of_domain_quotient_value),
+                Err(_) => Err(anyhow::Error::new(ProofStreamError::new(
+                    "cast to out-of-domain quotient value failed",
+                ))),
+            },
+            _ => Err(anyhow::Error::new(ProofStreamError::new(
+                "expected out-of-domain quotient value, but got something else",
+            ))),
+        }
+    }
+
     pub fn as_fri_codeword(&self) -> Result<Vec<XFieldElement>> {
         match self {
             Self::FriCodeword(xs) => Ok(xs.to_owned()),
@@ -260,6 +326,21 @@ where
         }
     }
 
+    pub fn as_fri_cosets(&self) -> Result<Vec<Vec<XFieldElement>>> {
+        match self {
+            Self::FriCosets(xss) => Ok(xss.to_owned()),
+            Self::Uncast(str) => match Vec::<Vec<XFieldElement>>::decode(str) {
+                Ok(fri_cosets) => Ok(*fri_cosets),
+                Err(_) => Err(anyhow::Error::new(ProofStreamError::new(
+                    "cast to FRI cosets failed",
+                ))),
+            },
+            _ => Err(anyhow::Error::new(ProofStreamError::new(
+                "expected FRI cosets, but got something else",
+            ))),
+        }
+    }
+
     pub fn as_padded_heights(&self) -> Result<BFieldElement> {
         match self {
             Self::PaddedHeight(padded_height) => Ok(padded_height.to_owned()),
@@ -274,6 +355,21 @@ where
             ))),
         }
     }
+
+    pub fn as_proof_of_work(&self) -> Result<BFieldElement> {
+        match self {
+            Self::ProofOfWork(nonce) => Ok(nonce.to_owned()),
+            Self::Uncast(str) => match BFieldElement::decode(str) {
+                Ok(nonce) => Ok(*nonce),
+                Err(_) => Err(anyhow::Error::new(ProofStreamError::new(
+                    "cast to proof-of-work nonce failed",
+                ))),
+            },
+            _ => Err(anyhow::Error::new(ProofStreamError::new(
+                "expected proof-of-work nonce, but got something else",
+            ))),
+        }
+    }
 }
 
 impl BFieldCodec for ProofItem {
@@ -302,9 +398,15 @@ impl BFieldCodec for ProofItem {
             ProofItem::MerkleRoot(something) => something.encode(),
             ProofItem::AuthenticationPath(something) => something.encode(),
             ProofItem::RevealedCombinationElements(something) => something.encode(),
+            ProofItem::RevealedQuotientElements(something) => something.encode(),
+            ProofItem::OutOfDomainBaseRow(something) => something.encode(),
+            ProofItem::OutOfDomainExtRow(something) => something.encode(),
+            ProofItem::OutOfDomainQuotientValue(something) => something.encode(),
             ProofItem::FriCodeword(something) => something.encode(),
             ProofItem::FriResponse(something) => something.encode(),
+            ProofItem::FriCosets(something) => something.encode(),
             ProofItem::PaddedHeight(something) => something.encode(),
+            ProofItem::ProofOfWork(something) => something.encode(),
             ProofItem::Uncast(something) => something.encode(),
         };
         let head = BFieldElement::new(tail.len().try_into().unwrap());
@@ -413,10 +515,10 @@ mod proof_item_typed_tests {
         proof_stream.enqueue(&ProofItem::FriResponse(fri_response.clone()));
         fs.push(proof_stream.prover_fiat_shamir());
 
-        let proof = proof_stream.to_proof();
+        let transcript = proof_stream.to_transcript();
 
-        let mut proof_stream_ =
-            ProofStream::<ProofItem, H>::from_proof(&proof).expect("invalid parsing of proof");
+        let mut proof_stream_ = ProofStream::<ProofItem, H>::from_transcript(&transcript)
+            .expect("invalid parsing of proof");
 
         let mut fs_ = vec![];
         fs_.push(proof_stream_.verifier_fiat_shamir());
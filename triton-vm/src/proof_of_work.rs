@@ -0,0 +1,101 @@
+use anyhow::Result;
+use rayon::prelude::*;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::b_field_element::BFIELD_ZERO;
+use twenty_first::shared_math::rescue_prime_digest::Digest;
+use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
+
+use crate::proof::ProofHasher;
+use crate::proof_item::ProofItem;
+use crate::proof_stream::ProofStream;
+
+/// Find the smallest nonce that, hashed together with the `seed`, results in a digest with
+/// `num_bits` many leading zero bits. Every additional bit doubles the expected work.
+pub fn grind<H: AlgebraicHasher>(seed: &Digest, num_bits: usize) -> BFieldElement {
+    (0..BFieldElement::P)
+        .into_par_iter()
+        .map(BFieldElement::new)
+        .find_first(|&nonce| is_valid_nonce::<H>(seed, nonce, num_bits))
+        .expect("Some nonce must have the required number of leading zero bits.")
+}
+
+/// Whether the nonce, hashed together with the `seed`, results in a digest with `num_bits` many
+/// leading zero bits. Leading zero bits are counted in the digest's first element.
+pub fn is_valid_nonce<H: AlgebraicHasher>(
+    seed: &Digest,
+    nonce: BFieldElement,
+    num_bits: usize,
+) -> bool {
+    let nonce = Digest::new([nonce, BFIELD_ZERO, BFIELD_ZERO, BFIELD_ZERO, BFIELD_ZERO]);
+    let digest = H::hash_pair(seed, &nonce);
+    digest.values()[0].value().leading_zeros() as usize >= num_bits
+}
+
+/// Grind on the current transcript and enqueue the found nonce, making all challenges sampled
+/// afterwards more expensive to influence. Does nothing if `num_bits` is 0.
+pub(crate) fn enqueue_proof_of_work<H: ProofHasher>(
+    proof_stream: &mut ProofStream<ProofItem, H>,
+    num_bits: usize,
+) {
+    if num_bits == 0 {
+        return;
+    }
+    let nonce = grind::<H>(&proof_stream.prover_fiat_shamir(), num_bits);
+    proof_stream.enqueue(&ProofItem::ProofOfWork(nonce));
+}
+
+/// Dequeue the nonce [`enqueue_proof_of_work`] enqueued and check it. Does nothing if `num_bits` is
+/// 0.
+pub(crate) fn dequeue_and_check_proof_of_work<H: ProofHasher>(
+    proof_stream: &mut ProofStream<ProofItem, H>,
+    num_bits: usize,
+) -> Result<bool> {
+    if num_bits == 0 {
+        return Ok(true);
+    }
+    let seed = proof_stream.verifier_fiat_shamir();
+    let nonce = proof_stream.dequeue()?.as_proof_of_work()?;
+    Ok(is_valid_nonce::<H>(&seed, nonce, num_bits))
+}
+
+#[cfg(test)]
+mod proof_of_work_tests {
+    use rand::random;
+    use twenty_first::shared_math::rescue_prime_regular::RescuePrimeRegular;
+
+    use super::*;
+
+    #[test]
+    fn grinding_finds_smallest_valid_nonce_test() {
+        type H = RescuePrimeRegular;
+        let seed = H::hash(&BFieldElement::new(random()));
+        let num_bits = 8;
+
+        let nonce = grind::<H>(&seed, num_bits);
+        assert!(is_valid_nonce::<H>(&seed, nonce, num_bits));
+        assert!(is_valid_nonce::<H>(&seed, nonce, 0));
+        for smaller_nonce in 0..nonce.value() {
+            let smaller_nonce = BFieldElement::new(smaller_nonce);
+            assert!(!is_valid_nonce::<H>(&seed, smaller_nonce, num_bits));
+        }
+    }
+
+    #[test]
+    fn proof_of_work_round_trip_test() {
+        type H = RescuePrimeRegular;
+        let num_bits = 6;
+        let mut proof_stream = ProofStream::<ProofItem, H>::new();
+        proof_stream.enqueue(&ProofItem::MerkleRoot(H::hash(&BFieldElement::new(
+            random(),
+        ))));
+        enqueue_proof_of_work(&mut proof_stream, num_bits);
+        enqueue_proof_of_work(&mut proof_stream, 0);
+        assert_eq!(2, proof_stream.len());
+
+        let mut proof_stream =
+            ProofStream::<ProofItem, H>::from_transcript(&proof_stream.to_transcript()).unwrap();
+        proof_stream.dequeue().unwrap();
+        assert!(dequeue_and_check_proof_of_work(&mut proof_stream, num_bits).unwrap());
+        assert!(dequeue_and_check_proof_of_work(&mut proof_stream, 0).unwrap());
+    }
+}
@@ -1,20 +1,32 @@
 use std::error::Error;
 use std::fmt::Display;
-use std::marker::PhantomData;
+use std::iter::repeat;
 
 use anyhow::Result;
+use itertools::Itertools;
+use num_traits::One;
+use num_traits::Zero;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::other::roundup_nearest_multiple;
 use twenty_first::shared_math::rescue_prime_digest::Digest;
-use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
+use twenty_first::shared_math::rescue_prime_digest::DIGEST_LENGTH;
+use twenty_first::util_types::algebraic_hasher::RATE;
 
 use crate::bfield_codec::BFieldCodec;
-use crate::proof::Proof;
+use crate::proof::ProofHasher;
 use crate::proof_item::MayBeUncast;
 
-#[derive(Debug, PartialEq, Eq)]
-pub struct ProofStream<Item: Clone + BFieldCodec + MayBeUncast, H: AlgebraicHasher> {
+/// The proof items the prover sends to the verifier. Fiat-Shamir challenges are squeezed from a
+/// sponge that absorbs every item as it is sent, or, on the verifier's side, as it is received.
+/// The prover's and the verifier's challenges agree as long as both squeeze at the same points of
+/// the stream. Since both sides are kept separately, one stream can play both roles.
+#[derive(Debug, Clone)]
+pub struct ProofStream<Item: Clone + BFieldCodec + MayBeUncast, H: ProofHasher> {
     pub items: Vec<Item>,
     items_index: usize,
-    _hasher: PhantomData<H>,
+    public_data: Vec<BFieldElement>,
+    prover_sponge: H::SpongeState,
+    verifier_sponge: H::SpongeState,
 }
 
 #[derive(Debug, Clone)]
@@ -42,21 +54,25 @@ impl Error for ProofStreamError {}
 impl<Item, H> ProofStream<Item, H>
 where
     Item: Clone + BFieldCodec + MayBeUncast,
-    H: AlgebraicHasher,
+    H: ProofHasher,
 {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         ProofStream {
             items: vec![],
             items_index: 0,
-            _hasher: PhantomData,
+            public_data: vec![],
+            prover_sponge: H::sponge_init(),
+            verifier_sponge: H::sponge_init(),
         }
     }
 
-    /// Reset the counter counting how many items were read. For testing purposes, so
-    /// we don't have to re-run tests needlessly.
+    /// Reset the counter counting how many items were read, and the verifier's sponge. For testing
+    /// purposes, so we don't have to re-run tests needlessly.
     pub fn reset_for_verifier(&mut self) {
         self.items_index = 0;
+        self.verifier_sponge = H::sponge_init();
+        Self::absorb(&mut self.verifier_sponge, &self.public_data);
     }
 
     pub fn is_empty(&self) -> bool {
@@ -68,33 +84,33 @@ where
     }
 
     pub fn transcript_length(&self) -> usize {
-        self.to_proof().0.len()
+        self.to_transcript().len()
     }
 
-    /// Convert the proof stream (or its transcript really) into a
-    /// Proof.
-    pub fn to_proof(&self) -> Proof {
+    /// Convert the proof stream into its transcript, the encoding of all its items.
+    pub fn to_transcript(&self) -> Vec<BFieldElement> {
         let mut bfes = vec![];
         for item in self.items.iter() {
             bfes.append(&mut item.encode());
         }
-        Proof(bfes)
+        bfes
     }
 
-    /// Convert the proof into a proof stream for the verifier.
-    pub fn from_proof(proof: &Proof) -> Result<Self> {
+    /// Convert a proof's transcript into a proof stream for the verifier. The items count as
+    /// received once they are dequeued.
+    pub fn from_transcript(transcript: &[BFieldElement]) -> Result<Self> {
         let mut index = 0;
         let mut items = vec![];
-        while index < proof.0.len() {
-            let len = proof.0[index].value() as usize;
-            if proof.0.len() < index + 1 + len {
+        while index < transcript.len() {
+            let len = transcript[index].value() as usize;
+            if transcript.len() < index + 1 + len {
                 return Err(ProofStreamError::new(&format!(
                     "failed to decode proof; wrong length: have {} but expected {}",
-                    proof.0.len(),
+                    transcript.len(),
                     index + 1 + len
                 )));
             }
-            let str = &proof.0[index..(index + 1 + len)];
+            let str = &transcript[index..(index + 1 + len)];
             let maybe_item = Item::decode(str);
             match maybe_item {
                 Ok(item) => {
@@ -108,13 +124,22 @@ where
         }
         Ok(ProofStream {
             items,
-            items_index: 0,
-            _hasher: PhantomData,
+            ..Self::new()
         })
     }
 
+    /// Bind data both prover and verifier know, like the claim, into all subsequent Fiat-Shamir
+    /// challenges. The data does not become part of the transcript. Must happen before any item is
+    /// sent or received.
+    pub fn absorb_public_data(&mut self, data: &[BFieldElement]) {
+        self.public_data.extend_from_slice(data);
+        Self::absorb(&mut self.prover_sponge, data);
+        Self::absorb(&mut self.verifier_sponge, data);
+    }
+
     /// Send a proof item as prover to verifier.
     pub fn enqueue(&mut self, item: &Item) {
+        Self::absorb(&mut self.prover_sponge, &item.encode());
         self.items.push(item.clone());
     }
 
@@ -125,24 +150,44 @@ where
             .get(self.items_index)
             .ok_or_else(|| ProofStreamError::new("Could not dequeue, queue empty"))?;
 
+        Self::absorb(&mut self.verifier_sponge, &item.uncast());
         self.items_index += 1;
         Ok(item.clone())
     }
 
-    pub fn prover_fiat_shamir(&self) -> Digest {
-        let mut transcript = vec![];
-        for item in self.items.iter() {
-            transcript.append(&mut item.encode());
-        }
-        H::hash_slice(&transcript)
+    /// The seed of the next challenges, squeezed from the sponge that absorbed all items sent so
+    /// far.
+    pub fn prover_fiat_shamir(&mut self) -> Digest {
+        Self::squeeze(&mut self.prover_sponge)
     }
 
-    pub fn verifier_fiat_shamir(&self) -> Digest {
-        let mut transcript = vec![];
-        for item in self.items[0..self.items_index].iter() {
-            transcript.append(&mut item.uncast());
+    /// The seed of the next challenges, squeezed from the sponge that absorbed all items received
+    /// so far.
+    pub fn verifier_fiat_shamir(&mut self) -> Digest {
+        Self::squeeze(&mut self.verifier_sponge)
+    }
+
+    /// Absorb `elements` followed by a 1 and as many 0s as needed to fill the sponge's rate. The
+    /// padding separates consecutive items.
+    fn absorb(sponge: &mut H::SpongeState, elements: &[BFieldElement]) {
+        let padding = [BFieldElement::one()]
+            .into_iter()
+            .chain(repeat(BFieldElement::zero()));
+        let num_padded_elements = roundup_nearest_multiple(elements.len() + 1, RATE);
+        let padded_elements = elements
+            .iter()
+            .copied()
+            .chain(padding)
+            .take(num_padded_elements)
+            .collect_vec();
+        for chunk in padded_elements.chunks_exact(RATE) {
+            H::sponge_absorb(sponge, chunk.try_into().unwrap());
         }
-        H::hash_slice(&transcript)
+    }
+
+    fn squeeze(sponge: &mut H::SpongeState) -> Digest {
+        let output = H::sponge_squeeze(sponge);
+        Digest::new(output[..DIGEST_LENGTH].try_into().unwrap())
     }
 }
 
@@ -155,6 +200,7 @@ mod proof_stream_typed_tests {
     use twenty_first::shared_math::other::random_elements;
     use twenty_first::shared_math::rescue_prime_regular::RescuePrimeRegular;
     use twenty_first::shared_math::x_field_element::XFieldElement;
+    use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
 
     use super::*;
 
@@ -267,9 +313,11 @@ mod proof_stream_typed_tests {
         let digest_2 = H::hash(&BFieldElement::one());
         ps.enqueue(&TestItem::ManyB(digest_2.values().to_vec()));
 
+        // squeezing changes the sponges, so look at a copy
+        let mut ps_copy = ps.clone();
         assert_ne!(
-            ps.prover_fiat_shamir(),
-            ps.verifier_fiat_shamir(),
+            ps_copy.prover_fiat_shamir(),
+            ps_copy.verifier_fiat_shamir(),
             "prover_fiat_shamir() and verifier_fiat_shamir() are different when the stream isn't fully read"
         );
 
@@ -282,6 +330,19 @@ mod proof_stream_typed_tests {
         );
     }
 
+    #[test]
+    fn consecutive_challenges_differ_test() {
+        fn consecutive_challenges_differ<H: ProofHasher>() {
+            let mut proof_stream = ProofStream::<TestItem, H>::new();
+            proof_stream.enqueue(&TestItem::ManyB(random_elements(10)));
+            let first_challenge = proof_stream.prover_fiat_shamir();
+            let second_challenge = proof_stream.prover_fiat_shamir();
+            assert_ne!(first_challenge, second_challenge);
+        }
+        consecutive_challenges_differ::<RescuePrimeRegular>();
+        consecutive_challenges_differ::<blake3::Hasher>();
+    }
+
     #[test]
     fn test_serialize_proof_with_fiat_shamir() {
         type H = RescuePrimeRegular;
@@ -298,10 +359,10 @@ mod proof_stream_typed_tests {
         proof_stream.enqueue(&TestItem::ManyB(manyb2.clone()));
         let fs4 = proof_stream.prover_fiat_shamir();
 
-        let proof = proof_stream.to_proof();
+        let transcript = proof_stream.to_transcript();
 
-        let mut proof_stream =
-            ProofStream::<TestItem, H>::from_proof(&proof).expect("invalid parsing of proof");
+        let mut proof_stream = ProofStream::<TestItem, H>::from_transcript(&transcript)
+            .expect("invalid parsing of proof");
 
         let fs1_ = proof_stream.verifier_fiat_shamir();
         match proof_stream.dequeue().expect("can't dequeue item").as_bs() {
@@ -328,4 +389,28 @@ mod proof_stream_typed_tests {
         assert_eq!(fs3, fs3_);
         assert_eq!(fs4, fs4_);
     }
+
+    #[test]
+    fn public_data_is_bound_but_not_sent_test() {
+        type H = RescuePrimeRegular;
+        let public_data: Vec<BFieldElement> = random_elements(7);
+        let manyb: Vec<BFieldElement> = random_elements(10);
+
+        let mut proof_stream = ProofStream::<TestItem, H>::new();
+        proof_stream.enqueue(&TestItem::ManyB(manyb.clone()));
+        let fs_without_public_data = proof_stream.prover_fiat_shamir();
+
+        let mut proof_stream = ProofStream::<TestItem, H>::new();
+        proof_stream.absorb_public_data(&public_data);
+        proof_stream.enqueue(&TestItem::ManyB(manyb));
+        let fs = proof_stream.prover_fiat_shamir();
+        assert_ne!(fs_without_public_data, fs);
+
+        let transcript = proof_stream.to_transcript();
+        let mut verifier_proof_stream = ProofStream::<TestItem, H>::from_transcript(&transcript)
+            .expect("invalid parsing of proof");
+        verifier_proof_stream.absorb_public_data(&public_data);
+        verifier_proof_stream.dequeue().unwrap();
+        assert_eq!(fs, verifier_proof_stream.verifier_fiat_shamir());
+    }
 }
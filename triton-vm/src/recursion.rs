@@ -0,0 +1,935 @@
+//! Triton assembly that verifies FRI proofs and evaluates AIR constraints, the building blocks
+//! for proving the verification of proofs with Triton VM itself.
+//!
+//! The generated programs check
+//! - Merkle authentication paths, using `divine_sibling`, `hash`, and `assert_vector`,
+//! - FRI proofs: deriving the folding challenges, the proof-of-work, and the indices of the
+//!   colinearity checks from the transcript using `absorb_init`, `absorb`, and `squeeze`,
+//!   authenticating the revealed codeword values, the colinearity checks of all rounds, and the
+//!   last codeword's Merkle root and degree, see [`FriQueryVerifier`], and
+//! - the AIR, by evaluating constraint circuits at given rows, see
+//!   [`constraint_evaluation_program`].
+//!
+//! The scope of this module is the verification of stand-alone FRI proofs, i.e., of proof streams
+//! holding nothing but FRI's items, and constraint evaluation. It is not a recursive verifier for
+//! [`Stark`](crate::stark::Stark) proofs: deriving the STARK's challenges, checking the
+//! combination of the quotients, and evaluating the AIR at the out-of-domain point are out of
+//! scope, as are FRI's folding factors other than 2 and coset leaves. Only [`StarkHasher`] can be
+//! verified, since it is the hash function of Triton VM's `hash` and Sponge instructions.
+
+use std::collections::HashMap;
+use std::iter::once;
+use std::iter::repeat;
+
+use anyhow::anyhow;
+use anyhow::Result;
+use itertools::Itertools;
+use ndarray::ArrayView2;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::other::roundup_nearest_multiple;
+use twenty_first::shared_math::rescue_prime_digest::Digest;
+use twenty_first::shared_math::rescue_prime_digest::DIGEST_LENGTH;
+use twenty_first::shared_math::traits::Inverse;
+use twenty_first::shared_math::traits::ModPowU32;
+use twenty_first::shared_math::x_field_element::XFieldElement;
+use twenty_first::shared_math::x_field_element::EXTENSION_DEGREE;
+use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
+use twenty_first::util_types::algebraic_hasher::RATE;
+
+use crate::fri::Fri;
+use crate::input::InputStream;
+use crate::proof_item::FriResponse;
+use crate::proof_item::MayBeUncast;
+use crate::stark::StarkHasher;
+use crate::stark::StarkProofStream;
+use crate::table::challenges::TableChallenges;
+use crate::table::constraint_circuit::BinOp;
+use crate::table::constraint_circuit::CircuitExpression;
+use crate::table::constraint_circuit::ConstraintCircuit;
+use crate::table::constraint_circuit::InputIndicator;
+use crate::table::master_table::NUM_BASE_COLUMNS;
+use crate::table::master_table::NUM_EXT_COLUMNS;
+
+/// Hashes the extension field element on top of the stack the way [`StarkHasher::hash`] does,
+/// i.e., with padding for variable-length input, using the Sponge instructions.
+///
+/// Stack before: `_ [xfe]`, stack after: `_ [digest]`.
+const HASH_XFE: &str = "
+    hash_xfe:
+        push 0 push 0 push 0 push 0 push 0 push 0 push 1
+        swap7 swap1 swap8 swap1 swap2 swap9 swap2 swap3 swap7 swap3
+        absorb_init squeeze
+        swap5 pop swap5 pop swap5 pop swap5 pop swap5 pop
+        return
+";
+
+/// Raises a base field element to a u32 exponent by square-and-multiply.
+///
+/// Stack before: `_ base exponent`, stack after: `_ base^exponent`.
+const BFE_POW: &str = "
+    bfe_pow:
+        push 1 swap2 swap1
+        call bfe_pow_loop
+        pop pop
+        return
+    bfe_pow_loop:            // _ acc base exponent
+        dup0 push 0 eq skiz return
+        push 2 swap1 div     // _ acc base (exponent / 2) (exponent % 2)
+        skiz call bfe_pow_multiply
+        swap1 dup0 mul swap1
+        recurse
+    bfe_pow_multiply:        // _ acc base exponent
+        swap2 dup1 mul swap2
+        return
+";
+
+/// Squeezes the Sponge and keeps the first [`DIGEST_LENGTH`] elements, like
+/// [`ProofStream::verifier_fiat_shamir`](crate::proof_stream::ProofStream::verifier_fiat_shamir).
+///
+/// Stack before: `_`, stack after: `_ [digest]`.
+const SQUEEZE_DIGEST: &str = "
+    push 0 push 0 push 0 push 0 push 0 push 0 push 0 push 0 push 0 push 0 squeeze
+    swap5 pop swap5 pop swap5 pop swap5 pop swap5 pop
+";
+
+/// Removes the word at `address` from the list in RAM ending at `end` by moving all following
+/// words one address down.
+///
+/// Stack before: `_ address end`, stack after: `_ end end`.
+const REMOVE_FROM_LIST: &str = "
+    remove_from_list:        // _ address end
+        dup1 dup1 eq skiz return
+        dup1 push 1 add push 0 read_mem swap1 pop
+        dup2 swap1 write_mem pop pop
+        swap1 push 1 add swap1
+        recurse
+";
+
+/// Adds the two extension field elements on top of the stack, consuming both.
+const XX_ADD: &str = "xxadd swap3 pop swap3 pop swap3 pop";
+
+/// Multiplies the two extension field elements on top of the stack, consuming both.
+const XX_MUL: &str = "xxmul swap3 pop swap3 pop swap3 pop";
+
+/// Removes the extension field element below the one on top of the stack.
+const DROP_SECOND_XFE: &str = "swap3 pop swap3 pop swap3 pop";
+
+/// Generates a Triton assembly program verifying a FRI proof for [`StarkHasher`], and the
+/// program's input for a given proof.
+///
+/// The public input is the proof's transcript up to and including the proof-of-work: all Merkle
+/// roots, the last codeword, and, if FRI grinds, the nonce, each encoded like in the proof
+/// stream. The program absorbs every item into the Sponge like
+/// [`ProofStream::dequeue`](crate::proof_stream::ProofStream::dequeue) does, and derives the
+/// folding challenges and the indices of the colinearity checks by squeezing. The revealed
+/// codeword values and their authentication paths are secret input. The program writes nothing
+/// to its output; it halts if and only if the FRI proof is valid.
+///
+/// Only FRI with a folding factor of 2 and without coset leaves is supported.
+#[derive(Debug, Clone)]
+pub struct FriQueryVerifier {
+    fri: Fri<StarkHasher>,
+}
+
+impl FriQueryVerifier {
+    pub fn new(fri: Fri<StarkHasher>) -> Self {
+        assert_eq!(
+            2, fri.folding_factor,
+            "The FRI query verifier only supports a folding factor of 2."
+        );
+        assert!(
+            !fri.coset_leaves,
+            "The FRI query verifier does not support coset leaves."
+        );
+        assert!(
+            fri.num_grinding_bits <= 32,
+            "The FRI query verifier supports at most 32 grinding bits."
+        );
+        Self { fri }
+    }
+
+    fn num_rounds(&self) -> usize {
+        self.fri.num_rounds().0 as usize
+    }
+
+    fn max_degree_of_last_codeword(&self) -> usize {
+        self.fri.num_rounds().1 as usize
+    }
+
+    fn domain_length(&self, round: usize) -> usize {
+        self.fri.domain.length >> round
+    }
+
+    fn last_codeword_length(&self) -> usize {
+        self.domain_length(self.num_rounds())
+    }
+
+    fn root_address(&self, round: usize) -> usize {
+        round * DIGEST_LENGTH
+    }
+
+    fn alpha_address(&self, round: usize) -> usize {
+        self.root_address(self.num_rounds() + 1) + round * EXTENSION_DEGREE
+    }
+
+    fn last_codeword_address(&self, index: usize) -> usize {
+        self.alpha_address(self.num_rounds()) + index * EXTENSION_DEGREE
+    }
+
+    fn index_address(&self, check: usize) -> usize {
+        self.last_codeword_address(self.last_codeword_length()) + check
+    }
+
+    /// The indices of the last codeword that have not been sampled yet.
+    fn remaining_index_address(&self, position: usize) -> usize {
+        self.index_address(self.fri.colinearity_checks_count) + position
+    }
+
+    fn nonce_address(&self) -> usize {
+        self.remaining_index_address(self.last_codeword_length())
+    }
+
+    /// The Triton assembly program verifying FRI.
+    pub fn code(&self) -> String {
+        let mut code = vec![
+            self.commit_phase(),
+            self.proof_of_work_check(),
+            self.sample_indices(),
+            self.last_codeword_root_check(),
+            self.last_codeword_degree_check(),
+        ];
+        for check in 0..self.fri.colinearity_checks_count {
+            let index = read_from_memory(self.index_address(check));
+            code.push(format!("{index} call verify_query"));
+        }
+        code.push("halt".to_string());
+
+        code.push(self.verify_query());
+        for round in 0..=self.num_rounds() {
+            code.push(self.merkle_authentication(round));
+        }
+        code.push(HASH_XFE.to_string());
+        code.push(BFE_POW.to_string());
+        code.push(REMOVE_FROM_LIST.to_string());
+        code.join("\n")
+    }
+
+    /// Read the Merkle roots and the last codeword from public input into RAM, absorbing each of
+    /// them, and squeeze the folding challenge of every round after absorbing that round's root.
+    fn commit_phase(&self) -> String {
+        let mut code = vec![];
+        for round in 0..=self.num_rounds() {
+            if round > 0 {
+                code.push(self.squeeze_alpha(round - 1));
+            }
+            code.push(read_item_into_memory(
+                self.root_address(round),
+                DIGEST_LENGTH,
+                round == 0,
+            ));
+        }
+        code.push(read_item_into_memory(
+            self.last_codeword_address(0),
+            EXTENSION_DEGREE * self.last_codeword_length(),
+            false,
+        ));
+        code.join("\n")
+    }
+
+    /// Squeeze the folding challenge of the given round and store it in RAM. Like
+    /// [`XFieldElement::sample`], the challenge's coefficients are the squeezed digest's elements
+    /// 2, 3, and 4.
+    fn squeeze_alpha(&self, round: usize) -> String {
+        let mut code = vec![SQUEEZE_DIGEST.to_string(), "pop pop".to_string()];
+        for coefficient in 0..EXTENSION_DEGREE {
+            let address = self.alpha_address(round) + coefficient;
+            code.push(format!("push {address} swap1 write_mem pop pop"));
+        }
+        code.join("\n")
+    }
+
+    /// Read the nonce from public input and check that, hashed together with the squeezed seed,
+    /// the result's first element has the required number of leading zero bits, like
+    /// [`is_valid_nonce`](crate::proof_of_work::is_valid_nonce) does. Then absorb the nonce.
+    fn proof_of_work_check(&self) -> String {
+        let num_bits = self.fri.num_grinding_bits;
+        if num_bits == 0 {
+            return String::new();
+        }
+        let nonce_address = self.nonce_address();
+        let upper_bound_of_high_bits = 1_u64 << (32 - num_bits);
+        [
+            "read_io push 1 eq assert".to_string(),
+            "push 0 push 0 push 0 push 0".to_string(),
+            format!("push {nonce_address} read_io write_mem swap1 pop"),
+            SQUEEZE_DIGEST.to_string(),
+            "hash pop pop pop pop pop".to_string(),
+            format!("split pop push {upper_bound_of_high_bits} swap1 lt assert"),
+            "pop pop pop pop".to_string(),
+            absorb(
+                vec!["push 1".to_string(), read_from_memory(nonce_address)],
+                false,
+            ),
+        ]
+        .join("\n")
+    }
+
+    /// Squeeze the seed of the indices and sample them like [`Fri::sample_indices`] does: every
+    /// index of the last codeword is drawn without replacement from the remaining ones, then lifted
+    /// through all rounds into the first codeword by a coset position sampled for every index and
+    /// every round.
+    fn sample_indices(&self) -> String {
+        let num_checks = self.fri.colinearity_checks_count;
+        let last_codeword_length = self.last_codeword_length();
+        let mut code = vec![SQUEEZE_DIGEST.to_string()];
+        for position in 0..last_codeword_length {
+            let address = self.remaining_index_address(position);
+            code.push(format!("push {address} push {position} write_mem pop pop"));
+        }
+
+        for check in 0..num_checks {
+            let num_remaining = last_codeword_length - check;
+            let sample_bound = (1_u64 << 16) * (num_remaining as u64).next_power_of_two();
+            code.push(self.hash_seed_with_counter(check));
+            code.push(format!("split swap1 pop push {} and", sample_bound - 1));
+            code.push(format!("push {num_remaining} swap1 div swap1 pop"));
+            code.push(format!("push {} add", self.remaining_index_address(0)));
+            code.push(format!(
+                "push {} dup1 push 0 read_mem swap1 pop write_mem pop pop",
+                self.index_address(check)
+            ));
+            code.push(format!(
+                "push {} call remove_from_list pop pop",
+                self.remaining_index_address(num_remaining - 1)
+            ));
+        }
+
+        let mut counter = num_checks;
+        for round in (0..self.num_rounds()).rev() {
+            let folded_codeword_length = self.domain_length(round + 1);
+            for check in 0..num_checks {
+                let address = self.index_address(check);
+                let index = read_from_memory(address);
+                code.push(self.hash_seed_with_counter(counter + check));
+                code.push(format!(
+                    "split swap1 pop push {} and push {folded_codeword_length} mul",
+                    self.fri.folding_factor - 1
+                ));
+                code.push(format!(
+                    "{index} add push {address} swap1 write_mem pop pop"
+                ));
+            }
+            counter += num_checks;
+        }
+        code.push("pop pop pop pop pop".to_string());
+        code.join("\n")
+    }
+
+    /// Hash the seed of the indices together with the counter, and keep the digest's element 4,
+    /// from which [`AlgebraicHasher::sample_index`] samples.
+    ///
+    /// Stack before: `_ [seed]`, stack after: `_ [seed] digest_element_4`.
+    fn hash_seed_with_counter(&self, counter: usize) -> String {
+        [
+            format!("push 0 push 0 push 0 push 1 push {counter}"),
+            "dup9 dup9 dup9 dup9 dup9 absorb_init".to_string(),
+            ["pop"; RATE].join(" "),
+            SQUEEZE_DIGEST.to_string(),
+            "pop pop pop pop".to_string(),
+        ]
+        .join("\n")
+    }
+
+    /// Recompute the Merkle root of the last codeword and compare it to the last root. The
+    /// leaves are hashed from last to first, such that two sibling subtrees are on top of the
+    /// stack in the order instruction `hash` expects them.
+    fn last_codeword_root_check(&self) -> String {
+        let mut code = vec![];
+        let mut subtree_sizes: Vec<usize> = vec![];
+        for index in (0..self.last_codeword_length()).rev() {
+            code.push(read_xfe_from_memory(self.last_codeword_address(index)));
+            code.push("call hash_xfe".to_string());
+            subtree_sizes.push(1);
+            while subtree_sizes.len() > 1
+                && subtree_sizes[subtree_sizes.len() - 1] == subtree_sizes[subtree_sizes.len() - 2]
+            {
+                code.push("hash pop pop pop pop pop".to_string());
+                let merged_size = subtree_sizes.pop().unwrap() + subtree_sizes.pop().unwrap();
+                subtree_sizes.push(merged_size);
+            }
+        }
+        code.push(read_digest_from_memory(
+            self.root_address(self.num_rounds()),
+        ));
+        code.push("assert_vector".to_string());
+        code.push(["pop"; 2 * DIGEST_LENGTH].join(" "));
+        code.join("\n")
+    }
+
+    /// Check that the polynomial interpolating the last codeword has sufficiently low degree by
+    /// asserting that the high coefficients of its inverse NTT vanish.
+    fn last_codeword_degree_check(&self) -> String {
+        let length = self.last_codeword_length();
+        let generator_inverse = self
+            .fri
+            .domain
+            .generator
+            .mod_pow_u32(1 << self.num_rounds())
+            .inverse();
+
+        let mut code = vec![];
+        for coefficient_index in self.max_degree_of_last_codeword() + 1..length {
+            code.push("push 0 push 0 push 0".to_string());
+            for index in 0..length {
+                let weight = generator_inverse.mod_pow_u32((coefficient_index * index) as u32);
+                code.push(read_xfe_from_memory(self.last_codeword_address(index)));
+                code.push(format!("push {weight} xbmul {XX_ADD}"));
+            }
+            code.push("push 0 eq assert push 0 eq assert push 0 eq assert".to_string());
+        }
+        code.join("\n")
+    }
+
+    /// Verify one query: authenticate the revealed value of the first codeword at the queried
+    /// index, which is on top of the stack, then, for every round, authenticate the value at the
+    /// opposite index and fold the two values into the next round's value. The final value must
+    /// match the last codeword.
+    fn verify_query(&self) -> String {
+        let domain_length = self.domain_length(0);
+        let mut code = vec![
+            "verify_query:".to_string(),
+            "divine divine divine".to_string(),
+            format!("dup2 dup2 dup2 dup6 push {domain_length} add swap3 swap2 swap1"),
+            "call hash_xfe".to_string(),
+            "call merkle_authentication_0".to_string(),
+        ];
+        for round in 0..self.num_rounds() {
+            let half_domain_length = self.domain_length(round) / 2;
+            code.push("divine divine divine".to_string());
+            code.push(format!(
+                "dup2 dup2 dup2 dup9 push {half_domain_length} xor push {} add swap3 swap2 swap1",
+                self.domain_length(round)
+            ));
+            code.push("call hash_xfe".to_string());
+            code.push(format!("call merkle_authentication_{round}"));
+            code.push(self.fold(round));
+        }
+
+        // compare with the last codeword
+        for (coefficient, depth) in (0..EXTENSION_DEGREE).rev().zip(3..) {
+            code.push(format!(
+                "dup{depth} push 3 mul push {} add push 0 read_mem swap1 pop",
+                self.last_codeword_address(0) + coefficient
+            ));
+        }
+        code.push("push -1 xbmul xxadd".to_string());
+        code.push("push 0 eq assert push 0 eq assert push 0 eq assert".to_string());
+        code.push("pop pop pop pop return".to_string());
+        code.join("\n")
+    }
+
+    /// Compute the value of the next round's codeword from the values `a` at index `i` and `b`
+    /// at index `i + n/2`. The corresponding domain points are `x` and `-x`, so the line through
+    /// both points evaluates to `(a + b)/2 + α·(a - b)/(2x)` in the folding challenge `α`.
+    ///
+    /// Stack before: `_ i [a] [b]`, stack after: `_ (i mod n/2) [c]`.
+    fn fold(&self, round: usize) -> String {
+        let round_exponent = 1 << round;
+        let generator_inverse = self
+            .fri
+            .domain
+            .generator
+            .mod_pow_u32(round_exponent)
+            .inverse();
+        let two_inverse = BFieldElement::new(2).inverse();
+        let scale = two_inverse * self.fri.domain.offset.mod_pow_u32(round_exponent).inverse();
+        let index_mask = self.domain_length(round) / 2 - 1;
+
+        [
+            "dup5 dup5 dup5 dup5 dup5 dup5".to_string(),
+            XX_ADD.to_string(),
+            format!("push {two_inverse} xbmul"),
+            "dup5 dup5 dup5 push -1 xbmul dup11 dup11 dup11".to_string(),
+            XX_ADD.to_string(),
+            read_xfe_from_memory(self.alpha_address(round)),
+            XX_MUL.to_string(),
+            format!("dup12 push {generator_inverse} swap1 call bfe_pow"),
+            format!("push {scale} mul xbmul"),
+            XX_ADD.to_string(),
+            DROP_SECOND_XFE.to_string(),
+            DROP_SECOND_XFE.to_string(),
+            format!("swap3 push {index_mask} and swap3"),
+        ]
+        .join("\n")
+    }
+
+    /// Authenticate a leaf against the Merkle root of the given round.
+    ///
+    /// Stack before: `_ node_index [leaf_digest]`, stack after: `_`. The siblings are read from
+    /// secret input.
+    fn merkle_authentication(&self, round: usize) -> String {
+        let tree_height = self.domain_length(round).trailing_zeros() as usize;
+        [
+            format!("merkle_authentication_{round}:"),
+            "push 0 push 0 push 0 push 0 push 0".to_string(),
+            vec!["divine_sibling hash"; tree_height].join(" "),
+            "pop pop pop pop pop".to_string(),
+            read_digest_from_memory(self.root_address(round)),
+            "assert_vector".to_string(),
+            ["pop"; 2 * DIGEST_LENGTH].join(" "),
+            "push 1 eq assert".to_string(),
+            "return".to_string(),
+        ]
+        .join("\n")
+    }
+
+    /// Extract the public and the secret input for the program from a FRI proof. Consumes the
+    /// FRI part of the proof stream like [`Fri::verify`] does.
+    pub fn input(
+        &self,
+        proof_stream: &mut StarkProofStream,
+    ) -> Result<(Vec<BFieldElement>, Vec<BFieldElement>)> {
+        let mut public_input = vec![];
+        for round in 0..=self.num_rounds() {
+            if round > 0 {
+                proof_stream.verifier_fiat_shamir();
+            }
+            let root = proof_stream.dequeue()?;
+            root.as_merkle_root()?;
+            public_input.extend(root.uncast());
+        }
+        let last_codeword = proof_stream.dequeue()?;
+        last_codeword.as_fri_codeword()?;
+        public_input.extend(last_codeword.uncast());
+        if self.fri.num_grinding_bits > 0 {
+            proof_stream.verifier_fiat_shamir();
+            let nonce = proof_stream.dequeue()?;
+            nonce.as_proof_of_work()?;
+            public_input.extend(nonce.uncast());
+        }
+        let a_indices = self
+            .fri
+            .sample_indices(&proof_stream.verifier_fiat_shamir());
+
+        let mut secret_inputs = vec![InputStream::new(); a_indices.len()];
+        let a_response = proof_stream.dequeue()?.as_fri_response()?;
+        let a_openings = full_authentication_paths(&a_indices, a_response)?;
+        for (secret_input, opening) in secret_inputs.iter_mut().zip_eq(a_openings) {
+            push_opening(secret_input, opening);
+        }
+        let mut b_indices = a_indices;
+        for round in 0..self.num_rounds() {
+            let domain_length = self.domain_length(round);
+            b_indices = b_indices
+                .iter()
+                .map(|&i| (i + domain_length / 2) % domain_length)
+                .collect();
+            let b_response = proof_stream.dequeue()?.as_fri_response()?;
+            let b_openings = full_authentication_paths(&b_indices, b_response)?;
+            for (secret_input, opening) in secret_inputs.iter_mut().zip_eq(b_openings) {
+                push_opening(secret_input, opening);
+            }
+        }
+
+        let secret_input = secret_inputs.iter().flat_map(|s| s.to_vec()).collect();
+        Ok((public_input, secret_input))
+    }
+}
+
+/// Read a proof item of the given length from public input into RAM, and absorb it like
+/// [`ProofStream::dequeue`](crate::proof_stream::ProofStream::dequeue) does, i.e., including its
+/// length prefix. The item's length prefix must match.
+fn read_item_into_memory(address: usize, length: usize, is_first_absorption: bool) -> String {
+    let mut code = vec![format!("read_io push {length} eq assert")];
+    for offset in 0..length {
+        let word_address = address + offset;
+        code.push(format!("push {word_address} read_io write_mem pop pop"));
+    }
+    let words = once(format!("push {length}"))
+        .chain((0..length).map(|offset| read_from_memory(address + offset)))
+        .collect();
+    code.push(absorb(words, is_first_absorption));
+    code.join("\n")
+}
+
+/// Absorb the given words, each one given as the code pushing it, padded with a 1 and as many 0s
+/// as needed to fill the Sponge's rate, like the [`ProofStream`](crate::proof_stream::ProofStream)
+/// pads items. Absorbing into a fresh Sponge uses `absorb_init`.
+fn absorb(words: Vec<String>, is_first_absorption: bool) -> String {
+    let padded_length = roundup_nearest_multiple(words.len() + 1, RATE);
+    let padding = once("push 1".to_string()).chain(repeat("push 0".to_string()));
+    let padded_words = words
+        .into_iter()
+        .chain(padding)
+        .take(padded_length)
+        .collect_vec();
+
+    let mut code = vec![];
+    for (chunk_index, chunk) in padded_words.chunks(RATE).enumerate() {
+        code.extend(chunk.iter().rev().cloned());
+        match is_first_absorption && chunk_index == 0 {
+            true => code.push("absorb_init".to_string()),
+            false => code.push("absorb".to_string()),
+        }
+        code.push(["pop"; RATE].join(" "));
+    }
+    code.join("\n")
+}
+
+/// Append a revealed value and its authentication path to the secret input, which instructions
+/// `divine` and `divine_sibling` read them from.
+fn push_opening(
+    secret_input: &mut InputStream,
+    (value, authentication_path): (XFieldElement, Vec<Digest>),
+) {
+    secret_input.push(&value);
+    secret_input.push_authentication_path(&authentication_path);
+}
+
+/// Recover the full authentication paths from the deduplicated ones in a FRI response. The
+/// omitted nodes are those that can be computed from the other revealed values.
+fn full_authentication_paths(
+    indices: &[usize],
+    response: FriResponse,
+) -> Result<Vec<(XFieldElement, Vec<Digest>)>> {
+    let tree_height = match response.0.first() {
+        Some((partial_path, _)) => partial_path.0.len(),
+        None => return Ok(vec![]),
+    };
+
+    let mut nodes: HashMap<usize, Digest> = HashMap::new();
+    for (&index, (partial_path, value)) in indices.iter().zip_eq(response.0.iter()) {
+        let node_index = index + (1 << tree_height);
+        nodes.insert(node_index, StarkHasher::hash(value));
+        for (level, sibling) in partial_path.0.iter().enumerate() {
+            if let Some(sibling) = sibling {
+                nodes.insert((node_index >> level) ^ 1, *sibling);
+            }
+        }
+    }
+    for level in 0..tree_height {
+        let layer = (1 << (tree_height - level))..(1 << (tree_height - level + 1));
+        let left_children = nodes
+            .keys()
+            .filter(|&&i| layer.contains(&i) && i % 2 == 0)
+            .copied()
+            .collect_vec();
+        for left_child in left_children {
+            if let Some(right) = nodes.get(&(left_child + 1)) {
+                let parent = StarkHasher::hash_pair(&nodes[&left_child], right);
+                nodes.entry(left_child / 2).or_insert(parent);
+            }
+        }
+    }
+
+    let mut openings = vec![];
+    for (&index, (_, value)) in indices.iter().zip_eq(response.0) {
+        let node_index = index + (1 << tree_height);
+        let authentication_path = (0..tree_height)
+            .map(|level| {
+                nodes
+                    .get(&((node_index >> level) ^ 1))
+                    .copied()
+                    .ok_or_else(|| anyhow!("Authentication path for index {index} is incomplete."))
+            })
+            .collect::<Result<_>>()?;
+        openings.push((value, authentication_path));
+    }
+    Ok(openings)
+}
+
+/// Pushes the word stored in RAM at `address`.
+fn read_from_memory(address: usize) -> String {
+    format!("push {address} push 0 read_mem swap1 pop")
+}
+
+/// Pushes the extension field element stored in RAM starting at `address`.
+fn read_xfe_from_memory(address: usize) -> String {
+    (0..EXTENSION_DEGREE)
+        .rev()
+        .map(|coefficient| read_from_memory(address + coefficient))
+        .join(" ")
+}
+
+/// Pushes the digest stored in RAM starting at `address`.
+fn read_digest_from_memory(address: usize) -> String {
+    (0..DIGEST_LENGTH)
+        .rev()
+        .map(|element| read_from_memory(address + element))
+        .join(" ")
+}
+
+/// The width of one row of the master tables in RAM, with extension field elements taking up
+/// three words.
+const ROW_WIDTH: usize = NUM_BASE_COLUMNS + EXTENSION_DEGREE * NUM_EXT_COLUMNS;
+
+/// A Triton assembly program evaluating the given constraint circuits. The program reads the
+/// challenges and rows, as encoded by [`constraint_evaluation_input`], from public input into
+/// RAM, and writes the value of every circuit to its output, one extension field element each.
+///
+/// Every node of the circuit is evaluated on each path reaching it, i.e., shared subexpressions
+/// are not reused.
+pub fn constraint_evaluation_program<T: TableChallenges, II: InputIndicator>(
+    circuits: &[ConstraintCircuit<T, II>],
+) -> String {
+    let mut code = vec!["push 0 read_io call load_input pop pop".to_string()];
+    for circuit in circuits {
+        evaluate_circuit::<T, II>(circuit, &mut code);
+        code.push("write_io write_io write_io".to_string());
+    }
+    code.push("halt".to_string());
+    code.push(
+        "
+        load_input:              // _ address remaining
+            dup0 push 0 eq skiz return
+            push -1 add swap1 read_io write_mem pop push 1 add swap1
+            recurse
+        "
+        .to_string(),
+    );
+    code.join("\n")
+}
+
+/// The input for a [`constraint_evaluation_program`]: the number of words to store in RAM,
+/// followed by the challenges and the rows of the master base and extension tables.
+pub fn constraint_evaluation_input<T: TableChallenges>(
+    challenges: &T,
+    base_rows: ArrayView2<BFieldElement>,
+    ext_rows: ArrayView2<XFieldElement>,
+) -> Vec<BFieldElement> {
+    let mut input = challenges
+        .to_vec()
+        .into_iter()
+        .flat_map(|challenge| challenge.coefficients)
+        .collect_vec();
+    for (base_row, ext_row) in base_rows.rows().into_iter().zip_eq(ext_rows.rows()) {
+        input.extend(base_row.iter());
+        input.extend(ext_row.iter().flat_map(|x| x.coefficients));
+    }
+    [vec![BFieldElement::new(input.len() as u64)], input].concat()
+}
+
+/// Emit code pushing the value of `circuit`.
+fn evaluate_circuit<T: TableChallenges, II: InputIndicator>(
+    circuit: &ConstraintCircuit<T, II>,
+    code: &mut Vec<String>,
+) {
+    let challenges_length = EXTENSION_DEGREE * T::count();
+    match &circuit.expression {
+        CircuitExpression::XConstant(xfe) => {
+            let [c0, c1, c2] = xfe.coefficients;
+            code.push(format!("push {c2} push {c1} push {c0}"));
+        }
+        CircuitExpression::BConstant(bfe) => code.push(format!("push 0 push 0 push {bfe}")),
+        CircuitExpression::Input(input) => {
+            let row_address = challenges_length + input.row_offset() * ROW_WIDTH;
+            match input.is_base_table_row() {
+                true => code.push(format!(
+                    "push 0 push 0 {}",
+                    read_from_memory(row_address + input.base_row_index())
+                )),
+                false => code.push(read_xfe_from_memory(
+                    row_address + NUM_BASE_COLUMNS + EXTENSION_DEGREE * input.ext_row_index(),
+                )),
+            }
+        }
+        CircuitExpression::Challenge(id) => {
+            let index: usize = (*id).into();
+            code.push(read_xfe_from_memory(EXTENSION_DEGREE * index));
+        }
+        CircuitExpression::BinaryOperation(binop, lhs, rhs) => {
+            evaluate_circuit(&lhs.as_ref().borrow(), code);
+            evaluate_circuit(&rhs.as_ref().borrow(), code);
+            match binop {
+                BinOp::Add => code.push(XX_ADD.to_string()),
+                BinOp::Sub => code.push(format!("push -1 xbmul {XX_ADD}")),
+                BinOp::Mul => code.push(XX_MUL.to_string()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod recursion_tests {
+    use ndarray::Array2;
+    use num_traits::Zero;
+    use rand::random;
+    use triton_opcodes::program::Program;
+    use twenty_first::shared_math::other::random_elements;
+    use twenty_first::shared_math::polynomial::Polynomial;
+
+    use crate::proof_stream::ProofStream;
+    use crate::shared_tests::parse_simulate_prove;
+    use crate::shared_tests::SourceCodeAndInput;
+    use crate::stark::triton_stark_tests::triton_table_constraints_evaluate_to_zero;
+    use crate::table::challenges::AllChallenges;
+    use crate::table::jump_stack_table::ExtJumpStackTable;
+    use crate::vm::run;
+
+    use super::*;
+
+    fn run_program(
+        code: &str,
+        input: Vec<BFieldElement>,
+        secret_input: Vec<BFieldElement>,
+    ) -> Result<Vec<BFieldElement>> {
+        let program = Program::from_code(code)?;
+        let (_, output, err) = run(&program, input, secret_input);
+        match err {
+            Some(err) => Err(err),
+            None => Ok(output),
+        }
+    }
+
+    /// FRI with 2 colinearity checks and a folding factor of 2.
+    fn fri(
+        domain_length: usize,
+        expansion_factor: usize,
+        num_grinding_bits: usize,
+    ) -> Fri<StarkHasher> {
+        let offset = BFieldElement::generator();
+        Fri::new(
+            offset,
+            domain_length,
+            expansion_factor,
+            2,
+            2,
+            num_grinding_bits,
+        )
+    }
+
+    /// A FRI proof for a random polynomial of the given degree, and the corresponding query
+    /// verifier with its public and secret input.
+    fn fri_query_verifier_with_input(
+        fri: Fri<StarkHasher>,
+        degree: usize,
+    ) -> (FriQueryVerifier, Vec<BFieldElement>, Vec<BFieldElement>) {
+        let polynomial = Polynomial::new(random_elements::<XFieldElement>(degree + 1));
+        let codeword = fri.domain.evaluate(&polynomial);
+        let mut proof_stream: StarkProofStream = ProofStream::new();
+        fri.prove(&codeword, &mut proof_stream).unwrap();
+
+        let fri_query_verifier = FriQueryVerifier::new(fri);
+        let (input, secret_input) = fri_query_verifier.input(&mut proof_stream).unwrap();
+        (fri_query_verifier, input, secret_input)
+    }
+
+    #[test]
+    fn hash_xfe_test() {
+        let code = format!("divine divine divine call hash_xfe {} halt {HASH_XFE}", {
+            ["write_io"; DIGEST_LENGTH].join(" ")
+        });
+        let xfe: XFieldElement = random_elements(1)[0];
+        let mut secret_input = InputStream::new();
+        secret_input.push(&xfe);
+        let output = run_program(&code, vec![], secret_input.to_vec()).unwrap();
+        assert_eq!(StarkHasher::hash(&xfe).values().to_vec(), output);
+    }
+
+    #[test]
+    fn bfe_pow_test() {
+        let code = format!("read_io read_io call bfe_pow write_io halt {BFE_POW}");
+        let base: BFieldElement = random_elements(1)[0];
+        for exponent in [0, 1, 2, 7, 1 << 20, random::<u32>()] {
+            let input = vec![base, BFieldElement::new(exponent as u64)];
+            let output = run_program(&code, input, vec![]).unwrap();
+            assert_eq!(vec![base.mod_pow_u32(exponent)], output);
+        }
+    }
+
+    #[test]
+    fn fri_query_verifier_accepts_valid_proof_test() {
+        for (domain_length, num_grinding_bits) in [(8, 0), (32, 0), (128, 0), (32, 6)] {
+            let degree = domain_length / 4 - 1;
+            let fri = fri(domain_length, 4, num_grinding_bits);
+            let (verifier, input, secret_input) = fri_query_verifier_with_input(fri, degree);
+            if let Err(err) = run_program(&verifier.code(), input, secret_input) {
+                panic!("Verifying FRI proof for domain length {domain_length} failed: {err}");
+            }
+        }
+    }
+
+    #[test]
+    fn fri_query_verifier_rejects_invalid_proof_test() {
+        let (verifier, input, secret_input) = fri_query_verifier_with_input(fri(64, 4, 0), 15);
+        let code = verifier.code();
+
+        // a revealed codeword value does not belong to the committed codeword
+        let mut bad_secret_input = secret_input.clone();
+        bad_secret_input[0].increment();
+        assert!(run_program(&code, input.clone(), bad_secret_input).is_err());
+
+        // a different Merkle root, changing all challenges derived after it
+        let root_index = 1 + verifier.root_address(1) + 1;
+        let mut bad_input = input.clone();
+        bad_input[root_index].increment();
+        assert!(run_program(&code, bad_input, secret_input.clone()).is_err());
+
+        // the last codeword does not match its Merkle root
+        let last_codeword_index = (verifier.num_rounds() + 1) * (1 + DIGEST_LENGTH) + 1;
+        let mut bad_input = input.clone();
+        bad_input[last_codeword_index].increment();
+        assert!(run_program(&code, bad_input, secret_input).is_err());
+
+        // the committed polynomial's degree is too high
+        let (verifier, input, secret_input) = fri_query_verifier_with_input(fri(64, 4, 0), 16);
+        assert!(run_program(&verifier.code(), input, secret_input).is_err());
+
+        // the nonce does not have enough leading zero bits: grinding finds the smallest valid one
+        let (verifier, mut input, secret_input) = loop {
+            let verifier_with_input = fri_query_verifier_with_input(fri(32, 4, 6), 7);
+            if !verifier_with_input.1.last().unwrap().is_zero() {
+                break verifier_with_input;
+            }
+        };
+        input.last_mut().unwrap().decrement();
+        assert!(run_program(&verifier.code(), input, secret_input).is_err());
+    }
+
+    #[test]
+    fn fri_query_verifier_trace_satisfies_air_test() {
+        let (verifier, input, secret_input) = fri_query_verifier_with_input(fri(32, 4, 0), 7);
+        triton_table_constraints_evaluate_to_zero(SourceCodeAndInput {
+            source_code: verifier.code(),
+            input,
+            secret_input,
+        });
+    }
+
+    #[test]
+    fn prove_fri_query_verifier_test() {
+        // the smallest FRI keeps the padded height, and thus the time to prove, low
+        let fri = Fri::new(BFieldElement::generator(), 2, 2, 2, 1, 0);
+        let (verifier, input, secret_input) = fri_query_verifier_with_input(fri, 0);
+        let (stark, proof) = parse_simulate_prove(&verifier.code(), input, secret_input, &mut None);
+        let verdict = stark.verify(proof, &mut None).unwrap();
+        assert!(verdict);
+    }
+
+    #[test]
+    fn constraint_evaluation_program_test() {
+        let circuits = ExtJumpStackTable::ext_transition_constraints_as_circuits();
+        let challenges = AllChallenges::placeholder(&[], &[]).jump_stack_table_challenges;
+        let base_rows =
+            Array2::from_shape_vec([2, NUM_BASE_COLUMNS], random_elements(2 * NUM_BASE_COLUMNS))
+                .unwrap();
+        let ext_rows =
+            Array2::from_shape_vec([2, NUM_EXT_COLUMNS], random_elements(2 * NUM_EXT_COLUMNS))
+                .unwrap();
+
+        let code = constraint_evaluation_program(&circuits);
+        let input = constraint_evaluation_input(&challenges, base_rows.view(), ext_rows.view());
+        let output = run_program(&code, input, vec![]).unwrap();
+
+        let expected_output = circuits
+            .iter()
+            .flat_map(|circuit| {
+                circuit
+                    .evaluate(base_rows.view(), ext_rows.view(), &challenges)
+                    .coefficients
+            })
+            .collect_vec();
+        assert_eq!(expected_output, output);
+        assert!(output.iter().any(|element| !element.is_zero()));
+    }
+}
@@ -13,6 +13,7 @@ use triton_profiler::triton_profiler::TritonProfiler;
 use twenty_first::shared_math::b_field_element::BFieldElement;
 
 use crate::proof::Claim;
+use crate::proof::ClaimedProgram;
 use crate::proof::Proof;
 use crate::stark::Stark;
 use crate::stark::StarkParameters;
@@ -57,14 +58,14 @@ pub fn parse_simulate_prove(
     let padded_height = MasterBaseTable::padded_height(&aet, &program.to_bwords());
     let claim = Claim {
         input: input_symbols,
-        program: program.to_bwords(),
+        program: ClaimedProgram::Code(program.to_bwords()),
         output: output_symbols,
         padded_height,
     };
     let log_expansion_factor = 2;
     let security_level = 32;
     let parameters = StarkParameters::new(security_level, 1 << log_expansion_factor);
-    let stark = Stark::new(claim, parameters);
+    let stark = Stark::new(claim, parameters).unwrap();
 
     prof_start!(maybe_profiler, "prove");
     let proof = stark.prove(aet, maybe_profiler);
@@ -144,7 +145,7 @@ pub fn load_proof(filename: &str) -> Result<Proof> {
     let mut file_handle = File::open(full_filename)?;
     let i = file_handle.read_to_end(&mut contents)?;
     println!("Read {i} bytes of proof data from disk.");
-    let proof: Proof = bincode::deserialize(&contents).expect("Cannot deserialize proof.");
+    let proof = Proof::from_bytes(&contents)?;
 
     Ok(proof)
 }
@@ -158,10 +159,7 @@ pub fn save_proof(filename: &str, proof: Proof) -> Result<()> {
         Ok(fh) => fh,
         Err(e) => panic!("Cannot write proof to disk at {full_filename}: {e:?}"),
     };
-    let binary = match bincode::serialize(&proof) {
-        Ok(b) => b,
-        Err(e) => panic!("Cannot serialize proof: {e:?}"),
-    };
+    let binary = proof.to_bytes();
     let amount = file_handle.write(&binary)?;
     println!("Wrote {amount} bytes of proof data to disk.");
     Ok(())
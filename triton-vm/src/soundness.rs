@@ -0,0 +1,412 @@
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+use anyhow::bail;
+use anyhow::Result;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::x_field_element::EXTENSION_DEGREE;
+
+use crate::proof_estimate::ProofEstimate;
+use crate::stark::Stark;
+use crate::stark::StarkHasher;
+use crate::stark::StarkParameters;
+use crate::table::master_table::max_degree_with_origin;
+
+/// The largest FRI expansion factor the [parameter solver](minimal_parameters) considers.
+const MAX_LOG2_FRI_EXPANSION_FACTOR: usize = 6;
+
+/// The range of the Johnson bound's proximity parameter `m` the proven soundness is optimized over.
+const JOHNSON_PROXIMITY_PARAMETERS: RangeInclusive<usize> = 3..=128;
+
+/// Which of the two soundness notions to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundnessKind {
+    /// Relies on the conjecture that Reed-Solomon codes behave well up to the list-decoding
+    /// capacity, as most deployed STARKs do.
+    Conjectured,
+
+    /// Only relies on what is proven, which is security up to the Johnson bound.
+    Proven,
+}
+
+/// Everything the soundness of a proof depends on. All fields are public so that the influence of
+/// each of them can be explored, _e.g._, the one of a different extension field.
+///
+/// The soundness error is made up of the following parts, where ρ is the inverse of the FRI
+/// expansion factor, n the FRI domain length, and |F| the size of the extension field.
+/// - The FRI query phase, in which every colinearity check is passed by a cheating prover with
+///   probability ρ (conjectured) or √ρ·(1 + 1/2m) (proven, for the Johnson bound's proximity
+///   parameter m ≥ 3). The combination codeword, which holds the DEEP quotients of the trace and
+///   of the quotients' combination, is checked against the master table rows opened at the same
+///   positions. Those positions are distinct and each is uniform over the entire FRI domain, so
+///   all of them avoid the places where the combination codeword and the rows disagree with at
+///   most the probability of independent checks, and the same bound covers both checks. This
+///   only holds because the positions are not confined to a predictable part of the domain.
+/// - Sampling challenges and the out-of-domain point from the field. A cheating trace satisfies
+///   the randomized AIR, checked at the out-of-domain point, with probability at most
+///   (d·h + n)/|F| (conjectured), where d is the maximum degree of the AIR and h the padded
+///   height. The proven bound adds the FRI commit phase error of the ethSTARK documentation,
+///   (m + ½)⁷·n² / (3·ρ^(3/2)·|F|) + (2m + 1)·(n + 1)·(k - 1)·r / (√ρ·|F|), where r is the
+///   number of folding rounds and k the FRI folding factor, since each round's folded codeword
+///   has degree k - 1 in the folding challenge.
+///
+/// Grinding adds its bits to the FRI query phase.
+///
+/// The conjectured soundness is the minimum of the parts, the proven soundness the union bound.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoundnessAnalysis {
+    pub padded_height: usize,
+    pub fri_expansion_factor: usize,
+    pub fri_folding_factor: usize,
+    pub fri_domain_length: usize,
+    pub num_fri_rounds: usize,
+    pub num_colinearity_checks: usize,
+    pub num_grinding_bits: usize,
+    pub max_air_degree: usize,
+    pub log2_field_size: f64,
+}
+
+impl SoundnessAnalysis {
+    pub fn new(parameters: &StarkParameters, padded_height: usize) -> Self {
+        let (interpolant_degree, _, fri) =
+            Stark::<StarkHasher>::degrees_and_fri(parameters, padded_height);
+        let max_degree = max_degree_with_origin(interpolant_degree, padded_height);
+        let max_air_degree = match max_degree.interpolant_degree {
+            0 => 0,
+            id => (max_degree.degree + max_degree.zerofier_degree) / id,
+        };
+
+        Self {
+            padded_height,
+            fri_expansion_factor: parameters.fri_expansion_factor,
+            fri_folding_factor: parameters.fri_folding_factor,
+            fri_domain_length: fri.domain.length,
+            num_fri_rounds: fri.num_rounds().0 as usize,
+            num_colinearity_checks: parameters.num_colinearity_checks,
+            num_grinding_bits: parameters.num_grinding_bits,
+            max_air_degree: max_air_degree.max(1) as usize,
+            log2_field_size: EXTENSION_DEGREE as f64 * (BFieldElement::P as f64).log2(),
+        }
+    }
+
+    pub fn bits(&self, kind: SoundnessKind) -> f64 {
+        match kind {
+            SoundnessKind::Conjectured => self.conjectured_bits(),
+            SoundnessKind::Proven => self.proven_bits(),
+        }
+    }
+
+    pub fn conjectured_bits(&self) -> f64 {
+        let log2_inverse_rate = (self.fri_expansion_factor as f64).log2();
+        let grinding_bits = self.num_grinding_bits as f64;
+        let query_bits = self.num_colinearity_checks as f64 * log2_inverse_rate + grinding_bits;
+        let field_bits = self.log2_field_size - self.log2_air_degree_times_domain();
+        query_bits.min(field_bits)
+    }
+
+    pub fn proven_bits(&self) -> f64 {
+        JOHNSON_PROXIMITY_PARAMETERS
+            .map(|m| self.proven_bits_for_proximity_parameter(m as f64))
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    fn proven_bits_for_proximity_parameter(&self, m: f64) -> f64 {
+        let log2_rate = -(self.fri_expansion_factor as f64).log2();
+        let log2_domain_length = (self.fri_domain_length as f64).log2();
+        let log2_field_size = self.log2_field_size;
+
+        let log2_check_error = 0.5 * log2_rate + (1.0 + 1.0 / (2.0 * m)).log2();
+        let grinding_bits = self.num_grinding_bits as f64;
+        let query_error = self.num_colinearity_checks as f64 * log2_check_error - grinding_bits;
+
+        let fri_commit_proximity_error = 7.0 * (m + 0.5).log2() + 2.0 * log2_domain_length
+            - 3.0_f64.log2()
+            - 1.5 * log2_rate
+            - log2_field_size;
+        let fri_commit_folding_error = (2.0 * m + 1.0).log2()
+            + (self.fri_domain_length as f64 + 1.0).log2()
+            + (self.fri_folding_factor as f64 - 1.0).log2()
+            + (self.num_fri_rounds.max(1) as f64).log2()
+            - 0.5 * log2_rate
+            - log2_field_size;
+        let air_error = self.log2_air_degree_times_domain() - log2_field_size;
+
+        -log2_of_sum(&[
+            query_error,
+            fri_commit_proximity_error,
+            fri_commit_folding_error,
+            air_error,
+        ])
+    }
+
+    /// log₂(d·h + n)
+    fn log2_air_degree_times_domain(&self) -> f64 {
+        let air_degree_times_height = self.max_air_degree as f64 * self.padded_height as f64;
+        (air_degree_times_height + self.fri_domain_length as f64).log2()
+    }
+}
+
+impl Display for SoundnessAnalysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "padded height:          {:>8}", self.padded_height)?;
+        writeln!(
+            f,
+            "FRI expansion factor:   {:>8}",
+            self.fri_expansion_factor
+        )?;
+        writeln!(f, "FRI folding factor:     {:>8}", self.fri_folding_factor)?;
+        writeln!(f, "FRI domain length:      {:>8}", self.fri_domain_length)?;
+        writeln!(f, "FRI rounds:             {:>8}", self.num_fri_rounds)?;
+        writeln!(
+            f,
+            "colinearity checks:     {:>8}",
+            self.num_colinearity_checks
+        )?;
+        writeln!(f, "max AIR degree:         {:>8}", self.max_air_degree)?;
+        writeln!(f, "log₂ of field size:     {:>8.1}", self.log2_field_size)?;
+        writeln!(
+            f,
+            "conjectured soundness:  {:>8.1} bits",
+            self.conjectured_bits()
+        )?;
+        write!(
+            f,
+            "proven soundness:       {:>8.1} bits",
+            self.proven_bits()
+        )
+    }
+}
+
+/// Given log₂(xᵢ), compute log₂(Σ xᵢ) without leaving the logarithmic domain.
+fn log2_of_sum(log2_terms: &[f64]) -> f64 {
+    let max = log2_terms.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let sum_of_scaled_terms: f64 = log2_terms.iter().map(|&t| (t - max).exp2()).sum();
+    max + sum_of_scaled_terms.log2()
+}
+
+/// The parameters reaching `target_bits` of soundness of the given kind for proofs of the given
+/// padded height with the shortest proofs, as predicted by [`ProofEstimate`]. Every FRI expansion
+/// factor up to 2^6 is considered. Grinding for `num_grinding_bits` reduces the number of checks.
+/// Fails if no parameters reach the target, which happens if the field is too small for it.
+pub fn minimal_parameters(
+    target_bits: usize,
+    padded_height: usize,
+    num_grinding_bits: usize,
+    kind: SoundnessKind,
+) -> Result<StarkParameters> {
+    let mut best_parameters: Option<(usize, StarkParameters)> = None;
+    for log2_expansion_factor in 1..=MAX_LOG2_FRI_EXPANSION_FACTOR {
+        let Some(parameters) = minimal_parameters_for_expansion_factor(
+            target_bits,
+            padded_height,
+            num_grinding_bits,
+            kind,
+            log2_expansion_factor,
+        ) else {
+            continue;
+        };
+        let proof_length = ProofEstimate::new(&parameters, padded_height).max_transcript_length();
+        let is_shorter = match &best_parameters {
+            Some((best_length, _)) => proof_length < *best_length,
+            None => true,
+        };
+        if is_shorter {
+            best_parameters = Some((proof_length, parameters));
+        }
+    }
+
+    match best_parameters {
+        Some((_, parameters)) => Ok(parameters),
+        None => bail!(
+            "No STARK parameters reach {target_bits} bits of {kind:?} soundness \
+            for padded height {padded_height}."
+        ),
+    }
+}
+
+fn minimal_parameters_for_expansion_factor(
+    target_bits: usize,
+    padded_height: usize,
+    num_grinding_bits: usize,
+    kind: SoundnessKind,
+    log2_expansion_factor: usize,
+) -> Option<StarkParameters> {
+    let bits_per_check = match kind {
+        SoundnessKind::Conjectured => log2_expansion_factor as f64,
+        SoundnessKind::Proven => 0.5 * log2_expansion_factor as f64,
+    };
+    let bits_from_checks = target_bits.saturating_sub(num_grinding_bits);
+    let mut num_checks = ((bits_from_checks as f64 / bits_per_check).ceil() as usize).max(1);
+
+    // A few more checks make up for the union bound of the proven soundness. If those don't
+    // suffice, the field is too small for the target, and more checks don't help.
+    let max_num_checks = num_checks + 8;
+    while num_checks <= max_num_checks {
+        let parameters = parameters_with_checks(
+            target_bits,
+            1 << log2_expansion_factor,
+            num_checks,
+            num_grinding_bits,
+        );
+        let analysis = SoundnessAnalysis::new(&parameters, padded_height);
+        if analysis.bits(kind) >= target_bits as f64 {
+            return Some(parameters);
+        }
+        num_checks += 1;
+    }
+    None
+}
+
+fn parameters_with_checks(
+    security_level: usize,
+    fri_expansion_factor: usize,
+    num_checks: usize,
+    num_grinding_bits: usize,
+) -> StarkParameters {
+    StarkParameters {
+        num_colinearity_checks: num_checks,
+        num_trace_randomizers: 2 * num_checks,
+        num_grinding_bits,
+        ..StarkParameters::new(security_level, fri_expansion_factor)
+    }
+}
+
+#[cfg(test)]
+mod soundness_tests {
+    use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
+
+    use crate::stark::StarkParametersError;
+
+    use super::*;
+
+    #[test]
+    fn default_parameters_reach_claimed_conjectured_soundness_test() {
+        let parameters = StarkParameters::default();
+        let analysis = SoundnessAnalysis::new(&parameters, 1 << 10);
+        println!("{analysis}");
+        assert!(analysis.conjectured_bits() >= parameters.security_level as f64);
+        assert!(analysis.proven_bits() < analysis.conjectured_bits());
+    }
+
+    #[test]
+    fn soundness_grows_with_checks_and_field_size_test() {
+        let parameters = StarkParameters::new(64, 4);
+        let analysis = SoundnessAnalysis::new(&parameters, 1 << 10);
+        let more_checks = SoundnessAnalysis {
+            num_colinearity_checks: 2 * analysis.num_colinearity_checks,
+            ..analysis
+        };
+        assert!(analysis.conjectured_bits() < more_checks.conjectured_bits());
+        assert!(analysis.proven_bits() < more_checks.proven_bits());
+
+        let smaller_field = SoundnessAnalysis {
+            log2_field_size: 64.0,
+            ..more_checks
+        };
+        assert!(smaller_field.conjectured_bits() < more_checks.conjectured_bits());
+        assert!(smaller_field.proven_bits() < more_checks.proven_bits());
+    }
+
+    #[test]
+    fn minimal_parameters_reach_target_with_fewest_checks_test() {
+        let padded_height = 1 << 10;
+        for (target_bits, kind) in [
+            (100, SoundnessKind::Conjectured),
+            (80, SoundnessKind::Proven),
+        ] {
+            let parameters = minimal_parameters(target_bits, padded_height, 0, kind).unwrap();
+            let analysis = SoundnessAnalysis::new(&parameters, padded_height);
+            assert!(analysis.bits(kind) >= target_bits as f64);
+
+            let fewer_checks = parameters_with_checks(
+                target_bits,
+                parameters.fri_expansion_factor,
+                parameters.num_colinearity_checks - 1,
+                0,
+            );
+            let fewer_checks_analysis = SoundnessAnalysis::new(&fewer_checks, padded_height);
+            assert!(fewer_checks_analysis.bits(kind) < target_bits as f64);
+        }
+    }
+
+    #[test]
+    fn grinding_reduces_number_of_checks_test() {
+        let padded_height = 1 << 10;
+        let kind = SoundnessKind::Conjectured;
+        let without_grinding = minimal_parameters(100, padded_height, 0, kind).unwrap();
+        let with_grinding = minimal_parameters(100, padded_height, 20, kind).unwrap();
+        assert_eq!(20, with_grinding.num_grinding_bits);
+        assert!(with_grinding.num_colinearity_checks < without_grinding.num_colinearity_checks);
+
+        let analysis = SoundnessAnalysis::new(&with_grinding, padded_height);
+        assert!(analysis.conjectured_bits() >= 100.0);
+    }
+
+    #[test]
+    fn parameters_with_too_few_checks_fail_validation_test() {
+        let padded_height = 1 << 10;
+        let parameters = StarkParameters::new(64, 4);
+        assert!(parameters.validate_soundness(padded_height).is_ok());
+
+        let too_few_checks = StarkParameters {
+            num_colinearity_checks: 16,
+            ..StarkParameters::new(64, 4)
+        };
+        let Err(StarkParametersError::InsufficientSoundness {
+            security_level,
+            conjectured_bits,
+        }) = too_few_checks.validate_soundness(padded_height)
+        else {
+            panic!("Parameters with too few colinearity checks must fail validation.");
+        };
+        assert_eq!(64, security_level);
+        assert_eq!(32.0, conjectured_bits);
+    }
+
+    /// A cheating prover passes a check if its index hits one of the positions where its
+    /// codewords are consistent. The analysis assumes that every check hits them independently
+    /// with probability ρ. For sets of positions of density ρ, the indices FRI actually samples
+    /// must not pass all checks more often than that.
+    #[test]
+    fn query_error_bounds_hitting_rate_of_sampled_indices_test() {
+        let padded_height = 1 << 8;
+        let parameters = StarkParameters::new(4, 4).with_fri_folding_factor(4);
+        let analysis = SoundnessAnalysis::new(&parameters, padded_height);
+        let (_, _, fri) = Stark::<StarkHasher>::degrees_and_fri(&parameters, padded_height);
+        let domain_length = fri.domain.length;
+        let density = 1.0 / parameters.fri_expansion_factor as f64;
+        let query_error = density.powi(parameters.num_colinearity_checks as i32);
+        assert_eq!(analysis.conjectured_bits(), -query_error.log2());
+
+        let num_seeds = 4000;
+        let hitting_rate = |is_consistent: &dyn Fn(usize) -> bool| {
+            let num_passes = (0..num_seeds)
+                .filter(|&seed| {
+                    let seed = StarkHasher::hash(&BFieldElement::new(seed));
+                    fri.sample_indices(&seed).into_iter().all(is_consistent)
+                })
+                .count();
+            num_passes as f64 / num_seeds as f64
+        };
+
+        let quarter = domain_length / 4;
+        let hitting_rates = [
+            ("first coset block", hitting_rate(&|i| i < quarter)),
+            ("last coset block", hitting_rate(&|i| i >= 3 * quarter)),
+            ("every fourth position", hitting_rate(&|i| i % 4 == 0)),
+        ];
+        for (name, hitting_rate) in hitting_rates {
+            // the standard deviation of the hitting rate is less than 0.004
+            assert!(
+                hitting_rate < query_error + 0.02,
+                "Sampled indices hit the {name} with rate {hitting_rate}, \
+                but the analysis assumes at most {query_error}."
+            );
+        }
+    }
+
+    #[test]
+    fn unreachable_target_is_rejected_test() {
+        assert!(minimal_parameters(300, 1 << 10, 0, SoundnessKind::Conjectured).is_err());
+    }
+}
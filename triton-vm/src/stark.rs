@@ -1,19 +1,24 @@
-use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 use std::ops::Add;
+use std::ops::Mul;
 
 use anyhow::anyhow;
 use anyhow::bail;
 use anyhow::Result;
+use itertools::izip;
 use itertools::Itertools;
 use ndarray::s;
 use ndarray::Array1;
 use ndarray::ArrayBase;
+use ndarray::ArrayView1;
 use ndarray::ArrayView2;
 use ndarray::Zip;
 use num_traits::One;
 use num_traits::Zero;
+use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
 use rayon::prelude::*;
 use triton_profiler::prof_itr0;
 use triton_profiler::prof_start;
@@ -29,36 +34,74 @@ use twenty_first::shared_math::traits::FiniteField;
 use twenty_first::shared_math::traits::Inverse;
 use twenty_first::shared_math::traits::ModPowU32;
 use twenty_first::shared_math::x_field_element::XFieldElement;
-use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
 use twenty_first::util_types::merkle_tree::CpuParallel;
 use twenty_first::util_types::merkle_tree::MerkleTree;
 use twenty_first::util_types::merkle_tree_maker::MerkleTreeMaker;
 
 use crate::arithmetic_domain::ArithmeticDomain;
+use crate::bfield_codec::BFieldCodec;
+use crate::execution_trace::ExecutionTrace;
 use crate::fri::Fri;
 use crate::fri::FriValidationError;
 use crate::proof::Claim;
 use crate::proof::Proof;
+use crate::proof::ProofFormatError;
+use crate::proof::ProofHasher;
+use crate::proof::ProofHeader;
+use crate::proof::PROOF_FORMAT_VERSION;
 use crate::proof_item::ProofItem;
 use crate::proof_stream::ProofStream;
+use crate::soundness::SoundnessAnalysis;
 use crate::table::challenges::AllChallenges;
 use crate::table::master_table::*;
-use crate::vm::AlgebraicExecutionTrace;
 
+/// The hasher of [`Stark::new`]. Being algebraic, it allows verifying proofs inside Triton VM.
 pub type StarkHasher = RescuePrimeRegular;
 pub type Maker = CpuParallel;
 pub type StarkProofStream = ProofStream<ProofItem, StarkHasher>;
 
+/// The length of the longest FRI domain, given by the largest subgroup of the B-field's
+/// multiplicative group whose order is a power of two.
+pub const MAX_FRI_DOMAIN_LENGTH: usize = 1 << 32;
+
+/// No nonce can have more leading zero bits than a B-field element has bits.
+pub const MAX_NUM_GRINDING_BITS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StarkParameters {
     pub security_level: usize,
     pub fri_expansion_factor: usize,
+
+    /// The number of codeword elements FRI folds into one per round. A higher folding factor
+    /// means fewer rounds, hence fewer Merkle roots and authentication paths in the proof, at the
+    /// cost of revealing more codeword elements per round. See
+    /// [`StarkParameters::with_fri_folding_factor`].
+    pub fri_folding_factor: usize,
+
+    /// Whether FRI's Merkle trees commit to all codeword elements that are folded together as one
+    /// leaf. See [`StarkParameters::with_fri_coset_leaves`].
+    pub fri_coset_leaves: bool,
     pub num_trace_randomizers: usize,
     pub num_randomizer_polynomials: usize,
     pub num_colinearity_checks: usize,
-    pub num_non_linear_codeword_checks: usize,
+
+    /// The number of leading zero bits the prover has to grind for before indices are sampled.
+    /// Every grinding bit adds one bit of soundness at the cost of doubling the prover's grinding
+    /// work, and allows for fewer checks.
+    pub num_grinding_bits: usize,
+
+    /// Whether proofs hide the secret input. If not, there are neither trace randomizers nor
+    /// randomizer polynomials. See [`StarkParameters::without_zero_knowledge`].
+    pub zero_knowledge: bool,
 }
 
 impl StarkParameters {
+    /// Parameters for `security_level` bits of conjectured soundness. Every colinearity check of
+    /// FRI contributes log₂ of the FRI expansion factor many bits. The master table rows are
+    /// opened where FRI's colinearity checks open the combination codeword, so they need no checks
+    /// of their own. See [`SoundnessAnalysis`] for the full accounting, and
+    /// [`minimal_parameters`](crate::soundness::minimal_parameters) for parameters with fewer
+    /// checks.
     pub fn new(security_level: usize, fri_expansion_factor: usize) -> Self {
         let num_randomizer_polynomials = 1; // over the XField
 
@@ -77,19 +120,168 @@ impl StarkParameters {
         }
         // post-condition: 2^(log2_of_fri_expansion_factor) == fri_expansion_factor
 
-        let num_colinearity_checks = security_level / log2_of_fri_expansion_factor;
+        let num_colinearity_checks = security_level.div_ceil(log2_of_fri_expansion_factor);
         let num_trace_randomizers = num_colinearity_checks * 2;
-        let num_non_linear_codeword_checks = security_level;
 
         StarkParameters {
             security_level,
             fri_expansion_factor,
+            fri_folding_factor: 2,
+            fri_coset_leaves: false,
             num_trace_randomizers,
             num_randomizer_polynomials,
             num_colinearity_checks,
-            num_non_linear_codeword_checks,
+            num_grinding_bits: 0,
+            zero_knowledge: true,
         }
     }
+
+    /// The same parameters, but for proofs that don't hide the secret input, for example for
+    /// succinctly verifying public computations. Without trace randomizers, the randomized trace
+    /// domain is only as long as the padded height, which makes all domains shorter, proving
+    /// faster, and proofs smaller. Without randomizer polynomials, the revealed rows are shorter.
+    pub fn without_zero_knowledge(self) -> Self {
+        Self {
+            num_trace_randomizers: 0,
+            num_randomizer_polynomials: 0,
+            zero_knowledge: false,
+            ..self
+        }
+    }
+
+    /// The same parameters, but FRI folds `fri_folding_factor` many codeword elements into one
+    /// per round instead of two. Folding by 4, 8, or 16 divides the number of FRI rounds by 2, 3,
+    /// or 4, respectively. Every round, each colinearity check reveals `fri_folding_factor - 1`
+    /// codeword elements in addition to the one it already knows.
+    pub fn with_fri_folding_factor(self, fri_folding_factor: usize) -> Self {
+        assert!(
+            is_power_of_two(fri_folding_factor),
+            "FRI folding factor must be a power of two, but got {fri_folding_factor}."
+        );
+        assert!(
+            fri_folding_factor > 1,
+            "FRI folding factor must be greater than one, but got {fri_folding_factor}."
+        );
+        Self {
+            fri_folding_factor,
+            ..self
+        }
+    }
+
+    /// The same parameters, but every leaf of FRI's Merkle trees holds an entire coset, i.e., the
+    /// `fri_folding_factor` many codeword elements that are folded together, except for the last
+    /// codeword. Every colinearity check then opens one leaf with one authentication path per
+    /// round instead of one per element, which saves hashing for the verifier. The combination
+    /// codeword's checks reveal entire leaves as well, which is why proofs don't necessarily get
+    /// shorter.
+    pub fn with_fri_coset_leaves(self) -> Self {
+        Self {
+            fri_coset_leaves: true,
+            ..self
+        }
+    }
+
+    /// Whether the parameters reach `security_level` bits of conjectured soundness for proofs of
+    /// the given padded height. See [`SoundnessAnalysis`].
+    pub fn validate_soundness(&self, padded_height: usize) -> Result<(), StarkParametersError> {
+        let conjectured_bits = SoundnessAnalysis::new(self, padded_height).conjectured_bits();
+        if conjectured_bits < self.security_level as f64 {
+            return Err(StarkParametersError::InsufficientSoundness {
+                security_level: self.security_level,
+                conjectured_bits,
+            });
+        }
+        Ok(())
+    }
+
+    /// The parameters a proof with the given header was made with, for verifying proofs without
+    /// knowing their parameters up front.
+    pub fn from_header(header: &ProofHeader) -> Result<Self> {
+        let expansion_factor = header.fri_expansion_factor;
+        if expansion_factor < 2 || !is_power_of_two(expansion_factor) {
+            bail!(
+                "FRI expansion factor must be a power of two greater than one, \
+                but got {expansion_factor}."
+            );
+        }
+        let folding_factor = header.fri_folding_factor;
+        if folding_factor < 2 || !is_power_of_two(folding_factor) {
+            bail!(
+                "FRI folding factor must be a power of two greater than one, \
+                but got {folding_factor}."
+            );
+        }
+
+        if !header.zero_knowledge && header.num_trace_randomizers != 0 {
+            bail!("Proofs without zero-knowledge must not have trace randomizers.");
+        }
+        let padded_height = header.padded_height;
+        if !is_power_of_two(padded_height) || padded_height > MAX_FRI_DOMAIN_LENGTH {
+            bail!(
+                "Padded height must be a power of two of at most {MAX_FRI_DOMAIN_LENGTH}, \
+                but got {padded_height}."
+            );
+        }
+        let num_trace_randomizers = header.num_trace_randomizers;
+        if expansion_factor > MAX_FRI_DOMAIN_LENGTH || num_trace_randomizers > MAX_FRI_DOMAIN_LENGTH
+        {
+            bail!("FRI expansion factor and number of trace randomizers must fit the FRI domain.");
+        }
+        let num_grinding_bits = header.num_grinding_bits;
+        if num_grinding_bits > MAX_NUM_GRINDING_BITS {
+            bail!(
+                "Number of grinding bits must be at most {MAX_NUM_GRINDING_BITS}, \
+                but got {num_grinding_bits}."
+            );
+        }
+
+        let parameters = Self {
+            fri_coset_leaves: header.fri_coset_leaves,
+            num_trace_randomizers: header.num_trace_randomizers,
+            num_colinearity_checks: header.num_colinearity_checks,
+            num_grinding_bits: header.num_grinding_bits,
+            ..Self::new(header.security_level, expansion_factor)
+                .with_fri_folding_factor(folding_factor)
+        };
+        let parameters = match header.zero_knowledge {
+            true => parameters,
+            false => parameters.without_zero_knowledge(),
+        };
+
+        let Some(fri_domain_length) = parameters.fri_domain_length(padded_height) else {
+            bail!("The FRI domain for padded height {padded_height} is too long.");
+        };
+        if fri_domain_length > MAX_FRI_DOMAIN_LENGTH {
+            bail!(
+                "The FRI domain for padded height {padded_height} must have length at most \
+                {MAX_FRI_DOMAIN_LENGTH}, but has length {fri_domain_length}."
+            );
+        }
+        let num_colinearity_checks = parameters.num_colinearity_checks;
+        if num_colinearity_checks == 0 || num_colinearity_checks > fri_domain_length {
+            bail!(
+                "Number of colinearity checks must be between 1 and the FRI domain length \
+                {fri_domain_length}, but got {num_colinearity_checks}."
+            );
+        }
+        Ok(parameters)
+    }
+
+    /// The interpolant degree and the max degree of proofs of the given padded height.
+    fn degrees(&self, padded_height: usize) -> (Degree, Degree) {
+        let interpolant_degree = interpolant_degree(padded_height, self.num_trace_randomizers);
+        let max_degree_with_origin = max_degree_with_origin(interpolant_degree, padded_height);
+        let max_degree = (roundup_npo2(max_degree_with_origin.degree as u64) - 1) as Degree;
+        (interpolant_degree, max_degree)
+    }
+
+    /// The length of the FRI domain of proofs of the given padded height, or `None` if it
+    /// overflows.
+    fn fri_domain_length(&self, padded_height: usize) -> Option<usize> {
+        let (_, max_degree) = self.degrees(padded_height);
+        self.fri_expansion_factor
+            .checked_mul(max_degree as usize + 1)
+    }
 }
 
 impl Default for StarkParameters {
@@ -104,7 +296,10 @@ impl Default for StarkParameters {
 #[derive(PartialEq, Eq, Debug)]
 pub enum StarkValidationError {
     CombinationLeafInequality,
+    OutOfDomainQuotientValueInequality,
     PaddedHeightInequality,
+    ParametersInequality,
+    HashFunctionInequality,
     FriValidationError(FriValidationError),
 }
 
@@ -116,48 +311,272 @@ impl fmt::Display for StarkValidationError {
     }
 }
 
-pub struct Stark {
+#[derive(PartialEq, Debug)]
+pub enum StarkParametersError {
+    InsufficientSoundness {
+        security_level: usize,
+        conjectured_bits: f64,
+    },
+}
+
+impl Error for StarkParametersError {}
+
+impl fmt::Display for StarkParametersError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "STARK parameters error: {self:?}")
+    }
+}
+
+/// The parts of proving that depend only on the padded height and the STARK parameters, not on
+/// the claim or the execution trace.
+struct ProverPrecomputation {
+    trace_domain: ArithmeticDomain,
+    quotient_domain: ArithmeticDomain,
+    zerofier_inverses: QuotientZerofierInverses,
+    quotient_degree_bounds: Vec<Degree>,
+}
+
+impl ProverPrecomputation {
+    fn new<H: ProofHasher>(stark: &Stark<H>) -> Self {
+        let padded_height = stark.claim.padded_height;
+        let trace_domain = ArithmeticDomain::new_no_offset(padded_height);
+        let quotient_domain = stark.quotient_domain();
+        Self {
+            trace_domain,
+            quotient_domain,
+            zerofier_inverses: QuotientZerofierInverses::new(trace_domain, quotient_domain),
+            quotient_degree_bounds: all_quotient_degree_bounds(
+                stark.interpolant_degree,
+                padded_height,
+            ),
+        }
+    }
+}
+
+/// The DEEP part of the combination codeword. Let `z` be the out-of-domain point, `z·ω` the point
+/// of the next row, `A` and `B` the sums of all base and extension columns weighted with the first
+/// and the second weight per column, respectively, and `Q` the quotient combination. Then the
+/// combination codeword's value at `x`, randomizers aside, is
+///
+/// `(a + b·x^s)·((A(x) - A(z))/(x - z) + (B(x) - B(z·ω))/(x - z·ω)) + (c + d·x)·(Q(x) - Q(z))/(x - z)`
+///
+/// where the shift `s` raises the degree of the columns' DEEP quotients to the max degree.
+struct DeepCombination {
+    point: XFieldElement,
+    next_row_point: XFieldElement,
+    weighted_row_sum: XFieldElement,
+    weighted_next_row_sum: XFieldElement,
+    quotient_value: XFieldElement,
+    shift_weights: [XFieldElement; 4],
+    row_shift: Degree,
+}
+
+impl DeepCombination {
+    /// The value of the combination codeword at `x`, given the weighted sums of the row at `x`, the
+    /// value of the quotient combination at `x`, and the inverses of `x - z` and `x - z·ω`.
+    fn evaluate(
+        &self,
+        x: BFieldElement,
+        weighted_row_sums: [XFieldElement; 2],
+        quotient_value: XFieldElement,
+        inverses: [XFieldElement; 2],
+    ) -> XFieldElement {
+        let [a, b, c, d] = self.shift_weights;
+        let [weighted_row_sum, weighted_next_row_sum] = weighted_row_sums;
+        let [point_inverse, next_row_point_inverse] = inverses;
+        let row_quotient = (weighted_row_sum - self.weighted_row_sum) * point_inverse
+            + (weighted_next_row_sum - self.weighted_next_row_sum) * next_row_point_inverse;
+        let quotient_quotient = (quotient_value - self.quotient_value) * point_inverse;
+        (a + b * x.mod_pow_u32(self.row_shift as u32)) * row_quotient
+            + (c + d * x) * quotient_quotient
+    }
+}
+
+pub struct Stark<H: ProofHasher = StarkHasher> {
     pub parameters: StarkParameters,
     pub claim: Claim,
     pub max_degree: Degree,
     pub interpolant_degree: Degree,
-    pub fri: Fri<StarkHasher>,
+    pub fri: Fri<H>,
+
+    /// The seed of all randomness the prover uses, like the trace randomizers and the randomizer
+    /// polynomials. If `None`, every proof uses fresh randomness.
+    pub prover_seed: Option<<ChaCha20Rng as SeedableRng>::Seed>,
 }
 
 impl Stark {
-    pub fn new(claim: Claim, parameters: StarkParameters) -> Self {
-        let interpolant_degree =
-            interpolant_degree(claim.padded_height, parameters.num_trace_randomizers);
-        let max_degree_with_origin =
-            max_degree_with_origin(interpolant_degree, claim.padded_height);
-        let max_degree = (roundup_npo2(max_degree_with_origin.degree as u64) - 1) as Degree;
-        let fri_domain_length = parameters.fri_expansion_factor * (max_degree as usize + 1);
-        let coset_offset = BFieldElement::generator();
-        let fri = Fri::new(
-            coset_offset,
-            fri_domain_length,
-            parameters.fri_expansion_factor,
-            parameters.num_colinearity_checks,
-        );
+    /// A STARK using the default hasher, [`StarkHasher`]. Fails if the parameters don't reach
+    /// their security level, see [`Stark::with_hasher`].
+    pub fn new(claim: Claim, parameters: StarkParameters) -> Result<Self, StarkParametersError> {
+        Self::with_hasher(claim, parameters)
+    }
+
+    /// Like [`Stark::new`], but without checking the parameters' soundness. Only use this for
+    /// parameters that are deliberately weak, _e.g._, to test or benchmark small proofs.
+    pub fn new_unchecked(claim: Claim, parameters: StarkParameters) -> Self {
+        Self::with_hasher_unchecked(claim, parameters)
+    }
+}
+
+impl<H: ProofHasher> Stark<H> {
+    /// A STARK using hasher `H` for its Merkle trees and Fiat-Shamir challenges. It only accepts
+    /// proofs made with the same hasher. Fails if the parameters don't reach `security_level` bits
+    /// of conjectured soundness for the claim's padded height, see
+    /// [`StarkParameters::validate_soundness`].
+    pub fn with_hasher(
+        claim: Claim,
+        parameters: StarkParameters,
+    ) -> Result<Self, StarkParametersError> {
+        parameters.validate_soundness(claim.padded_height)?;
+        Ok(Self::with_hasher_unchecked(claim, parameters))
+    }
+
+    /// Like [`Stark::with_hasher`], but without checking the parameters' soundness.
+    pub fn with_hasher_unchecked(claim: Claim, parameters: StarkParameters) -> Self {
+        assert!(
+            parameters.zero_knowledge
+                || parameters.num_trace_randomizers == 0
+                    && parameters.num_randomizer_polynomials == 0,
+            "Parameters without zero-knowledge must have neither trace randomizers \
+            nor randomizer polynomials."
+        );
+        let (interpolant_degree, max_degree, fri) =
+            Self::degrees_and_fri(&parameters, claim.padded_height);
         Self {
             parameters,
             claim,
             max_degree,
             interpolant_degree,
             fri,
+            prover_seed: None,
         }
     }
 
+    /// Make the prover deterministic: proofs of the same claim with the same seed are identical,
+    /// which is useful for regression tests and for debugging. Since the seed determines all
+    /// randomizers, a proof made with a known seed does not hide the secret input.
+    pub fn with_prover_seed(self, seed: <ChaCha20Rng as SeedableRng>::Seed) -> Self {
+        Self {
+            prover_seed: Some(seed),
+            ..self
+        }
+    }
+
+    /// The interpolant degree, the max degree, and the FRI of proofs of the given padded height.
+    pub(crate) fn degrees_and_fri(
+        parameters: &StarkParameters,
+        padded_height: usize,
+    ) -> (Degree, Degree, Fri<H>) {
+        let (interpolant_degree, max_degree) = parameters.degrees(padded_height);
+        let fri_domain_length = parameters.fri_expansion_factor * (max_degree as usize + 1);
+        let coset_offset = BFieldElement::generator();
+        let mut fri = Fri::new(
+            coset_offset,
+            fri_domain_length,
+            parameters.fri_expansion_factor,
+            parameters.fri_folding_factor,
+            parameters.num_colinearity_checks,
+            parameters.num_grinding_bits,
+        );
+        fri.coset_leaves = parameters.fri_coset_leaves;
+        (interpolant_degree, max_degree, fri)
+    }
+
     pub fn prove(
         &self,
-        aet: AlgebraicExecutionTrace,
+        aet: impl ExecutionTrace,
         maybe_profiler: &mut Option<TritonProfiler>,
     ) -> Proof {
+        prof_start!(maybe_profiler, "precomputation");
+        let precomputation = ProverPrecomputation::new(self);
+        prof_stop!(maybe_profiler, "precomputation");
+        self.prove_claim(&self.claim, aet, 0, &precomputation, maybe_profiler)
+    }
+
+    /// Prove many claims of the same padded height at once, one proof per claim, in the order of
+    /// `claims_and_traces`. The FRI domain and everything that depends only on the padded height,
+    /// like the quotient domain and the zerofiers of the constraints, are computed once for the
+    /// whole batch. The claims are proven in parallel.
+    ///
+    /// Every proof can be verified on its own, like a proof from [`Stark::prove`]. Every execution
+    /// trace must result in its claim's padded height, which is checked before any work is done.
+    ///
+    /// With a `prover_seed`, see [`Stark::with_prover_seed`], the randomness of every claim is
+    /// derived from the seed and the claim's position in the batch. No two proofs of a batch share
+    /// randomizers, and the first proof is the same as the one [`Stark::prove`] makes.
+    ///
+    /// Fails if the parameters don't reach their security level, like [`Stark::with_hasher`].
+    pub fn prove_batch<T: ExecutionTrace + Send>(
+        parameters: StarkParameters,
+        claims_and_traces: Vec<(Claim, T)>,
+        prover_seed: Option<<ChaCha20Rng as SeedableRng>::Seed>,
+    ) -> Result<Vec<Proof>> {
+        let Some((first_claim, _)) = claims_and_traces.first() else {
+            return Ok(vec![]);
+        };
+        let padded_height = first_claim.padded_height;
+        if let Some((claim, _)) = claims_and_traces
+            .iter()
+            .find(|(claim, _)| claim.padded_height != padded_height)
+        {
+            bail!(
+                "All claims of a batch must have the same padded height, \
+                but found padded heights {padded_height} and {}.",
+                claim.padded_height
+            );
+        }
+        for (claim, aet) in claims_and_traces.iter() {
+            let Some(program) = claim.program.code() else {
+                bail!("The prover needs the full program, not only its digest.");
+            };
+            if MasterBaseTable::padded_height(aet, program) != claim.padded_height {
+                return Err(anyhow!(StarkValidationError::PaddedHeightInequality));
+            }
+        }
+
+        let stark = Self {
+            prover_seed,
+            ..Self::with_hasher(first_claim.clone(), parameters)?
+        };
+        let precomputation = ProverPrecomputation::new(&stark);
+        let proofs = claims_and_traces
+            .into_par_iter()
+            .enumerate()
+            .map(|(claim_index, (claim, aet))| {
+                stark.prove_claim(&claim, aet, claim_index, &precomputation, &mut None)
+            })
+            .collect();
+        Ok(proofs)
+    }
+
+    /// Prove the claim at position `claim_index` of a batch. With a prover seed, the claim's
+    /// randomness comes from the stream of the seeded RNG with the claim's index.
+    fn prove_claim(
+        &self,
+        claim: &Claim,
+        aet: impl ExecutionTrace,
+        claim_index: usize,
+        precomputation: &ProverPrecomputation,
+        maybe_profiler: &mut Option<TritonProfiler>,
+    ) -> Proof {
+        let mut rng = match self.prover_seed {
+            Some(seed) => {
+                let mut rng = ChaCha20Rng::from_seed(seed);
+                rng.set_stream(claim_index as u64);
+                rng
+            }
+            None => ChaCha20Rng::from_entropy(),
+        };
+
         prof_start!(maybe_profiler, "base tables");
         prof_start!(maybe_profiler, "create");
+        let program = claim
+            .program
+            .code()
+            .expect("The prover needs the full program, not only its digest.");
         let mut master_base_table = MasterBaseTable::new(
             aet,
-            &self.claim.program,
+            program,
             self.parameters.num_trace_randomizers,
             self.fri.domain,
         );
@@ -168,60 +587,54 @@ impl Stark {
         prof_stop!(maybe_profiler, "pad");
 
         prof_start!(maybe_profiler, "LDE");
-        master_base_table.randomize_trace();
+        master_base_table.randomize_trace(rng.gen());
         let fri_domain_master_base_table = master_base_table.to_fri_domain_table();
         prof_stop!(maybe_profiler, "LDE");
 
         prof_start!(maybe_profiler, "Merkle tree");
-        let base_merkle_tree = fri_domain_master_base_table.merkle_tree(maybe_profiler);
+        let base_merkle_tree = fri_domain_master_base_table.merkle_tree::<H>(maybe_profiler);
         let base_merkle_tree_root = base_merkle_tree.get_root();
         prof_stop!(maybe_profiler, "Merkle tree");
 
         prof_start!(maybe_profiler, "Fiat-Shamir");
         let padded_height = BFieldElement::new(master_base_table.padded_height as u64);
-        let mut proof_stream = StarkProofStream::new();
+        let mut proof_stream = ProofStream::<ProofItem, H>::new();
+        proof_stream.absorb_public_data(&claim.with_program_digest().encode());
         proof_stream.enqueue(&ProofItem::PaddedHeight(padded_height));
         proof_stream.enqueue(&ProofItem::MerkleRoot(base_merkle_tree_root));
         let extension_weights = Self::sample_weights(
             proof_stream.prover_fiat_shamir(),
             AllChallenges::TOTAL_CHALLENGES,
         );
-        let extension_challenges = AllChallenges::create_challenges(
-            extension_weights,
-            &self.claim.input,
-            &self.claim.output,
-        );
+        let extension_challenges =
+            AllChallenges::create_challenges(extension_weights, &claim.input, &claim.output);
         prof_stop!(maybe_profiler, "Fiat-Shamir");
 
         prof_start!(maybe_profiler, "extend");
         let mut master_ext_table = master_base_table.extend(
             &extension_challenges,
             self.parameters.num_randomizer_polynomials,
+            rng.gen(),
         );
         prof_stop!(maybe_profiler, "extend");
         prof_stop!(maybe_profiler, "base tables");
 
         prof_start!(maybe_profiler, "ext tables");
         prof_start!(maybe_profiler, "LDE");
-        master_ext_table.randomize_trace();
+        master_ext_table.randomize_trace(rng.gen());
         let fri_domain_ext_master_table = master_ext_table.to_fri_domain_table();
         prof_stop!(maybe_profiler, "LDE");
 
         prof_start!(maybe_profiler, "Merkle tree");
-        let ext_merkle_tree = fri_domain_ext_master_table.merkle_tree(maybe_profiler);
+        let ext_merkle_tree = fri_domain_ext_master_table.merkle_tree::<H>(maybe_profiler);
         let ext_merkle_tree_root = ext_merkle_tree.get_root();
         proof_stream.enqueue(&ProofItem::MerkleRoot(ext_merkle_tree_root));
         prof_stop!(maybe_profiler, "Merkle tree");
         prof_stop!(maybe_profiler, "ext tables");
 
-        prof_start!(maybe_profiler, "quotient degree bounds");
-        let quotient_degree_bounds =
-            all_quotient_degree_bounds(self.interpolant_degree, master_base_table.padded_height);
-        prof_stop!(maybe_profiler, "quotient degree bounds");
-
         prof_start!(maybe_profiler, "quotient-domain codewords");
-        let trace_domain = ArithmeticDomain::new_no_offset(master_base_table.padded_height);
-        let quotient_domain = self.quotient_domain();
+        let trace_domain = precomputation.trace_domain;
+        let quotient_domain = precomputation.quotient_domain;
         let unit_distance = self.fri.domain.length / quotient_domain.length;
         let base_quotient_domain_codewords = fri_domain_master_base_table
             .master_base_matrix
@@ -237,20 +650,99 @@ impl Stark {
             extension_quotient_domain_codewords,
             trace_domain,
             quotient_domain,
+            &precomputation.zerofier_inverses,
             &extension_challenges,
             maybe_profiler,
         );
         prof_stop!(maybe_profiler, "quotient codewords");
 
-        // Get weights for nonlinear combination. Concretely, sample 2 weights for each base
-        // polynomial, each extension polynomial, and each quotient. The factor is 2 because
-        // transition constraints check 2 rows.
+        // Get weights for combining the quotients. Concretely, sample 2 weights for each quotient:
+        // one for the quotient, one for the quotient shifted to the max degree.
+        prof_start!(maybe_profiler, "Fiat-Shamir");
+        let quotient_combination_weights = Self::sample_weights(
+            proof_stream.prover_fiat_shamir(),
+            2 * num_all_table_quotients(),
+        );
+        prof_stop!(maybe_profiler, "Fiat-Shamir");
+
+        prof_start!(maybe_profiler, "quotient combination");
+        let quotient_combination_codeword = self.create_quotient_combination_codeword(
+            quotient_domain,
+            master_quotient_table.view(),
+            &quotient_combination_weights,
+            precomputation.quotient_degree_bounds.clone(),
+        );
+        let quotient_combination_polynomial =
+            quotient_domain.interpolate(&quotient_combination_codeword);
+        let fri_quotient_combination_codeword =
+            self.fri.domain.evaluate(&quotient_combination_polynomial);
+        prof_stop!(maybe_profiler, "quotient combination");
+
+        prof_start!(maybe_profiler, "Merkle tree");
+        let quotient_combination_digests = fri_quotient_combination_codeword
+            .par_iter()
+            .map(H::hash)
+            .collect::<Vec<_>>();
+        let quotient_tree: MerkleTree<H, _> = Maker::from_digests(&quotient_combination_digests);
+        proof_stream.enqueue(&ProofItem::MerkleRoot(quotient_tree.get_root()));
+        prof_stop!(maybe_profiler, "Merkle tree");
+
+        // Evaluate all base and extension columns at the out-of-domain point and at the point
+        // corresponding to the next row, as well as the combination of the quotients. This allows
+        // the verifier to check the AIR once, at the out-of-domain point.
+        prof_start!(maybe_profiler, "out-of-domain rows");
+        let out_of_domain_point = Self::sample_weights(proof_stream.prover_fiat_shamir(), 1)[0];
+        let out_of_domain_next_row_point = out_of_domain_point * trace_domain.generator;
+        let trace_base_table = master_base_table.master_base_matrix.view();
+        let trace_ext_table = master_ext_table
+            .master_ext_matrix
+            .slice(s![.., ..NUM_EXT_COLUMNS]);
+        let out_of_domain_base_row = Self::out_of_domain_row(trace_base_table, out_of_domain_point);
+        let out_of_domain_ext_row = Self::out_of_domain_row(trace_ext_table, out_of_domain_point);
+        let out_of_domain_next_base_row =
+            Self::out_of_domain_row(trace_base_table, out_of_domain_next_row_point);
+        let out_of_domain_next_ext_row =
+            Self::out_of_domain_row(trace_ext_table, out_of_domain_next_row_point);
+        let out_of_domain_quotient_value =
+            quotient_combination_polynomial.evaluate(&out_of_domain_point);
+        proof_stream.enqueue(&ProofItem::OutOfDomainBaseRow(
+            out_of_domain_base_row.clone(),
+        ));
+        proof_stream.enqueue(&ProofItem::OutOfDomainExtRow(out_of_domain_ext_row.clone()));
+        proof_stream.enqueue(&ProofItem::OutOfDomainBaseRow(
+            out_of_domain_next_base_row.clone(),
+        ));
+        proof_stream.enqueue(&ProofItem::OutOfDomainExtRow(
+            out_of_domain_next_ext_row.clone(),
+        ));
+        proof_stream.enqueue(&ProofItem::OutOfDomainQuotientValue(
+            out_of_domain_quotient_value,
+        ));
+        prof_stop!(maybe_profiler, "out-of-domain rows");
+
+        // Get weights for the combination codeword. Concretely, sample 2 weights for each base
+        // polynomial and each extension polynomial, one for the out-of-domain point and one for
+        // the next row's point, plus 4 weights for shifting the degrees of the DEEP quotients.
         prof_start!(maybe_profiler, "Fiat-Shamir");
-        let non_lin_combi_weights_seed = proof_stream.prover_fiat_shamir();
-        let num_non_lin_combi_weights =
-            2 * (NUM_BASE_COLUMNS + NUM_EXT_COLUMNS + num_all_table_quotients());
-        let non_lin_combi_weights =
-            Self::sample_weights(non_lin_combi_weights_seed, num_non_lin_combi_weights);
+        let (row_weights, shift_weights) =
+            Self::sample_combination_weights(proof_stream.prover_fiat_shamir());
+        let deep_combination = DeepCombination {
+            point: out_of_domain_point,
+            next_row_point: out_of_domain_next_row_point,
+            weighted_row_sum: Self::weighted_row_sums(
+                Array1::from(out_of_domain_base_row).view(),
+                Array1::from(out_of_domain_ext_row).view(),
+                &row_weights,
+            )[0],
+            weighted_next_row_sum: Self::weighted_row_sums(
+                Array1::from(out_of_domain_next_base_row).view(),
+                Array1::from(out_of_domain_next_ext_row).view(),
+                &row_weights,
+            )[1],
+            quotient_value: out_of_domain_quotient_value,
+            shift_weights,
+            row_shift: self.max_degree - self.interpolant_degree + 1,
+        };
         prof_stop!(maybe_profiler, "Fiat-Shamir");
 
         prof_start!(maybe_profiler, "nonlinear combination");
@@ -259,9 +751,9 @@ impl Stark {
             quotient_domain,
             base_quotient_domain_codewords,
             extension_quotient_domain_codewords.slice(s![.., ..NUM_EXT_COLUMNS]),
-            master_quotient_table.view(),
-            &non_lin_combi_weights,
-            quotient_degree_bounds,
+            &quotient_combination_codeword,
+            &row_weights,
+            &deep_combination,
         );
         prof_stop!(maybe_profiler, "create combination codeword");
 
@@ -279,75 +771,59 @@ impl Stark {
         prof_stop!(maybe_profiler, "nonlinear combination");
 
         prof_start!(maybe_profiler, "Merkle tree 3");
-        let combination_codeword_digests = fri_combination_codeword
-            .par_iter()
-            .map(StarkHasher::hash)
-            .collect::<Vec<_>>();
-        let combination_tree: MerkleTree<StarkHasher, _> =
-            Maker::from_digests(&combination_codeword_digests);
-        let combination_root = combination_tree.get_root();
+        let combination_root = self
+            .fri
+            .merkle_tree(&fri_combination_codeword, 0)
+            .get_root();
         proof_stream.enqueue(&ProofItem::MerkleRoot(combination_root));
         prof_stop!(maybe_profiler, "Merkle tree 3");
 
-        // Get indices of master table rows to prove nonlinear combination
-        prof_start!(maybe_profiler, "Fiat-Shamir 3");
-        let indices_seed = proof_stream.prover_fiat_shamir();
-        let revealed_current_row_indices = StarkHasher::sample_indices(
-            &indices_seed,
-            self.fri.domain.length,
-            self.parameters.num_non_linear_codeword_checks,
-        );
-        prof_stop!(maybe_profiler, "Fiat-Shamir 3");
-
+        // The master table rows are opened where FRI opens the combination codeword. Those
+        // indices range over the entire FRI domain, which the soundness analysis relies on.
         prof_start!(maybe_profiler, "FRI");
-        match self.fri.prove(&fri_combination_codeword, &mut proof_stream) {
-            Ok((_, fri_first_round_merkle_root)) => assert_eq!(
-                combination_root, fri_first_round_merkle_root,
-                "Combination root from STARK and from FRI must agree."
-            ),
+        let revealed_indices = match self.fri.prove(&fri_combination_codeword, &mut proof_stream) {
+            Ok((fri_first_round_indices, fri_first_round_merkle_root)) => {
+                assert_eq!(
+                    combination_root, fri_first_round_merkle_root,
+                    "Combination root from STARK and from FRI must agree."
+                );
+                fri_first_round_indices
+            }
             Err(e) => panic!("The FRI prover failed because of: {e}"),
-        }
+        };
         prof_stop!(maybe_profiler, "FRI");
 
+        // Open leafs of zipped codewords at FRI's query positions. Since the AIR is checked at the
+        // out-of-domain point, the next rows are not needed. FRI has already opened the
+        // combination codeword at these positions.
         prof_start!(maybe_profiler, "open trace leafs");
-        // the relation between the FRI domain and the trace domain
-        let unit_distance = self.fri.domain.length / master_base_table.padded_height;
-        // Open leafs of zipped codewords at indicated positions
-        let revealed_current_and_next_row_indices = self
-            .revealed_current_and_next_row_indices(unit_distance, &revealed_current_row_indices);
-
         let revealed_base_elems = Self::get_revealed_elements(
             fri_domain_master_base_table.master_base_matrix.view(),
-            &revealed_current_and_next_row_indices,
+            &revealed_indices,
         );
-        let auth_paths_base =
-            base_merkle_tree.get_authentication_structure(&revealed_current_and_next_row_indices);
+        let auth_paths_base = base_merkle_tree.get_authentication_structure(&revealed_indices);
         proof_stream.enqueue(&ProofItem::MasterBaseTableRows(revealed_base_elems));
         proof_stream.enqueue(&ProofItem::CompressedAuthenticationPaths(auth_paths_base));
 
         let revealed_ext_elems = Self::get_revealed_elements(
             fri_domain_ext_master_table.master_ext_matrix.view(),
-            &revealed_current_and_next_row_indices,
+            &revealed_indices,
         );
-        let auth_paths_ext =
-            ext_merkle_tree.get_authentication_structure(&revealed_current_and_next_row_indices);
+        let auth_paths_ext = ext_merkle_tree.get_authentication_structure(&revealed_indices);
         proof_stream.enqueue(&ProofItem::MasterExtTableRows(revealed_ext_elems));
         proof_stream.enqueue(&ProofItem::CompressedAuthenticationPaths(auth_paths_ext));
 
-        // Open combination codeword at the same positions as base & ext codewords.
-        // Use `revealed_current_row_indices`, not `revealed_current_and_next_row_indices`, as
-        // the latter is only needed to check transition constraints.
-        let revealed_combination_elements = revealed_current_row_indices
+        let revealed_quotient_elements = revealed_indices
             .iter()
-            .map(|&i| fri_combination_codeword[i])
+            .map(|&i| fri_quotient_combination_codeword[i])
             .collect_vec();
-        let revealed_combination_auth_paths =
-            combination_tree.get_authentication_structure(&revealed_current_row_indices);
-        proof_stream.enqueue(&ProofItem::RevealedCombinationElements(
-            revealed_combination_elements,
+        let revealed_quotient_auth_paths =
+            quotient_tree.get_authentication_structure(&revealed_indices);
+        proof_stream.enqueue(&ProofItem::RevealedQuotientElements(
+            revealed_quotient_elements,
         ));
         proof_stream.enqueue(&ProofItem::CompressedAuthenticationPaths(
-            revealed_combination_auth_paths,
+            revealed_quotient_auth_paths,
         ));
         prof_stop!(maybe_profiler, "open trace leafs");
 
@@ -358,7 +834,23 @@ impl Stark {
             );
         }
 
-        proof_stream.to_proof()
+        let header = ProofHeader {
+            version: PROOF_FORMAT_VERSION,
+            hash_function: H::HASH_FUNCTION,
+            security_level: self.parameters.security_level,
+            fri_expansion_factor: self.parameters.fri_expansion_factor,
+            fri_folding_factor: self.parameters.fri_folding_factor,
+            fri_coset_leaves: self.parameters.fri_coset_leaves,
+            num_colinearity_checks: self.parameters.num_colinearity_checks,
+            num_trace_randomizers: self.parameters.num_trace_randomizers,
+            num_grinding_bits: self.parameters.num_grinding_bits,
+            zero_knowledge: self.parameters.zero_knowledge,
+            padded_height: master_base_table.padded_height,
+        };
+        Proof {
+            header,
+            transcript: proof_stream.to_transcript(),
+        }
     }
 
     fn quotient_domain(&self) -> ArithmeticDomain {
@@ -379,21 +871,6 @@ impl Stark {
         }
     }
 
-    fn revealed_current_and_next_row_indices(
-        &self,
-        unit_distance: usize,
-        revealed_current_rows_indices: &[usize],
-    ) -> Vec<usize> {
-        let mut indices = vec![];
-        for &index in revealed_current_rows_indices.iter() {
-            indices.push(index);
-            indices.push((index + unit_distance) % self.fri.domain.length);
-        }
-        indices.sort_unstable();
-        indices.dedup();
-        indices
-    }
-
     fn get_revealed_elements<FF: FiniteField>(
         master_matrix: ArrayView2<FF>,
         revealed_indices: &[usize],
@@ -404,82 +881,148 @@ impl Stark {
             .collect_vec()
     }
 
-    fn create_combination_codeword(
+    /// Combine the quotients into one codeword. Every quotient is added once as is and once
+    /// shifted to the max degree, each with its own weight.
+    fn create_quotient_combination_codeword(
         &self,
         quotient_domain: ArithmeticDomain,
-        base_codewords: ArrayView2<BFieldElement>,
-        extension_codewords: ArrayView2<XFieldElement>,
         quotient_codewords: ArrayView2<XFieldElement>,
         weights: &[XFieldElement],
         quotient_degree_bounds: Vec<Degree>,
     ) -> Vec<XFieldElement> {
-        let (base_weights, remaining_weights) = weights.split_at(2 * NUM_BASE_COLUMNS);
-        let (ext_weights, quot_weights) = remaining_weights.split_at(2 * NUM_EXT_COLUMNS);
-
-        assert_eq!(base_weights.len(), 2 * base_codewords.ncols());
-        assert_eq!(ext_weights.len(), 2 * extension_codewords.ncols());
-        assert_eq!(quot_weights.len(), 2 * quotient_codewords.ncols());
+        assert_eq!(weights.len(), 2 * quotient_codewords.ncols());
 
-        let base_and_ext_col_shift = self.max_degree - self.interpolant_degree;
         let quotient_domain_values = quotient_domain.domain_values();
-        let shifted_domain_values =
-            Self::degree_shift_domain(&quotient_domain_values, base_and_ext_col_shift);
-
-        let mut combination_codeword = vec![XFieldElement::zero(); quotient_domain.length];
-
-        for (idx, (codeword, weights)) in base_codewords
-            .columns()
-            .into_iter()
-            .zip_eq(base_weights.chunks_exact(2))
-            .enumerate()
-        {
-            Zip::from(&mut combination_codeword)
-                .and(codeword)
-                .and(shifted_domain_values.view())
-                .par_for_each(|acc, &bfe, &shift| {
-                    *acc += weights[0] * bfe + weights[1] * bfe * shift
-                });
-            self.debug_check_degree(idx, &combination_codeword, quotient_domain);
-        }
-        if std::env::var("DEBUG").is_ok() {
-            println!(" --- next up: extension codewords");
-        }
-        for (idx, (codeword, weights)) in extension_codewords
-            .columns()
-            .into_iter()
-            .zip_eq(ext_weights.chunks_exact(2))
-            .enumerate()
-        {
-            Zip::from(&mut combination_codeword)
-                .and(codeword)
-                .and(shifted_domain_values.view())
-                .par_for_each(|acc, &xfe, &shift| {
-                    *acc += weights[0] * xfe + weights[1] * xfe * shift
-                });
-            self.debug_check_degree(idx, &combination_codeword, quotient_domain);
-        }
-        if std::env::var("DEBUG").is_ok() {
-            println!(" --- next up: quotient codewords");
-        }
+        let mut quotient_combination_codeword = vec![XFieldElement::zero(); quotient_domain.length];
         for (idx, ((codeword, weights), degree_bound)) in quotient_codewords
             .columns()
             .into_iter()
-            .zip_eq(quot_weights.chunks_exact(2))
+            .zip_eq(weights.chunks_exact(2))
             .zip_eq(quotient_degree_bounds)
             .enumerate()
         {
             let shifted_domain_values =
                 Self::degree_shift_domain(&quotient_domain_values, self.max_degree - degree_bound);
-            Zip::from(&mut combination_codeword)
+            Zip::from(&mut quotient_combination_codeword)
                 .and(codeword)
                 .and(shifted_domain_values.view())
                 .par_for_each(|acc, &xfe, &shift| {
                     *acc += weights[0] * xfe + weights[1] * xfe * shift
                 });
-            self.debug_check_degree(idx, &combination_codeword, quotient_domain);
+            self.debug_check_degree(idx, &quotient_combination_codeword, quotient_domain);
         }
+        quotient_combination_codeword
+    }
+
+    /// The codeword FRI proves to be of low degree: the DEEP quotients of all base and extension
+    /// columns, and of the quotient combination, see [`DeepCombination`]. The randomizer
+    /// polynomials are added after low-degree extending the returned codeword to the FRI domain.
+    fn create_combination_codeword(
+        &self,
+        quotient_domain: ArithmeticDomain,
+        base_codewords: ArrayView2<BFieldElement>,
+        extension_codewords: ArrayView2<XFieldElement>,
+        quotient_combination_codeword: &[XFieldElement],
+        row_weights: &[XFieldElement],
+        deep_combination: &DeepCombination,
+    ) -> Vec<XFieldElement> {
+        assert_eq!(
+            row_weights.len(),
+            2 * (base_codewords.ncols() + extension_codewords.ncols())
+        );
+
+        let quotient_domain_values = quotient_domain.domain_values();
+        let point_inverses = XFieldElement::batch_inversion(
+            quotient_domain_values
+                .iter()
+                .map(|&x| x - deep_combination.point)
+                .collect(),
+        );
+        let next_row_point_inverses = XFieldElement::batch_inversion(
+            quotient_domain_values
+                .iter()
+                .map(|&x| x - deep_combination.next_row_point)
+                .collect(),
+        );
 
-        combination_codeword
+        (0..quotient_domain.length)
+            .into_par_iter()
+            .map(|i| {
+                let weighted_row_sums = Self::weighted_row_sums(
+                    base_codewords.row(i),
+                    extension_codewords.row(i),
+                    row_weights,
+                );
+                deep_combination.evaluate(
+                    quotient_domain_values[i],
+                    weighted_row_sums,
+                    quotient_combination_codeword[i],
+                    [point_inverses[i], next_row_point_inverses[i]],
+                )
+            })
+            .collect()
+    }
+
+    /// The sums of the row's elements, weighted with the first and with the second of the two
+    /// weights per column, respectively.
+    fn weighted_row_sums<FF>(
+        base_row: ArrayView1<FF>,
+        ext_row: ArrayView1<XFieldElement>,
+        row_weights: &[XFieldElement],
+    ) -> [XFieldElement; 2]
+    where
+        FF: FiniteField,
+        FF: Mul<XFieldElement, Output = XFieldElement>,
+    {
+        let (base_weights, ext_weights) = row_weights.split_at(2 * base_row.len());
+        let mut weighted_row_sums = [XFieldElement::zero(); 2];
+        for (&element, weights) in base_row.iter().zip_eq(base_weights.chunks_exact(2)) {
+            weighted_row_sums[0] += element * weights[0];
+            weighted_row_sums[1] += element * weights[1];
+        }
+        for (&element, weights) in ext_row.iter().zip_eq(ext_weights.chunks_exact(2)) {
+            weighted_row_sums[0] += weights[0] * element;
+            weighted_row_sums[1] += weights[1] * element;
+        }
+        weighted_row_sums
+    }
+
+    /// Evaluate the interpolants of all columns of the randomized trace table at the given point,
+    /// using the barycentric formula over the randomized trace domain.
+    fn out_of_domain_row<FF>(
+        randomized_trace_table: ArrayView2<FF>,
+        point: XFieldElement,
+    ) -> Vec<XFieldElement>
+    where
+        FF: FiniteField,
+        FF: Mul<XFieldElement, Output = XFieldElement>,
+    {
+        let randomized_trace_domain =
+            ArithmeticDomain::new_no_offset(randomized_trace_table.nrows());
+        let domain_values = randomized_trace_domain.domain_values();
+        let inverses =
+            XFieldElement::batch_inversion(domain_values.iter().map(|&x| point - x).collect());
+        let domain_length = BFieldElement::new(randomized_trace_domain.length as u64);
+        let vanishing_value =
+            point.mod_pow_u32(randomized_trace_domain.length as u32) - XFieldElement::one();
+        let factor = vanishing_value * domain_length.inverse();
+        let barycentric_weights = domain_values
+            .iter()
+            .zip_eq(inverses)
+            .map(|(&x, inverse)| factor * x * inverse)
+            .collect_vec();
+
+        (0..randomized_trace_table.ncols())
+            .into_par_iter()
+            .map(|column_index| {
+                randomized_trace_table
+                    .column(column_index)
+                    .iter()
+                    .zip_eq(barycentric_weights.iter())
+                    .map(|(&value, &weight)| value * weight)
+                    .sum()
+            })
+            .collect()
     }
 
     fn degree_shift_domain(
@@ -518,19 +1061,58 @@ impl Stark {
     }
 
     fn sample_weights(seed: Digest, num_weights: usize) -> Vec<XFieldElement> {
-        StarkHasher::get_n_hash_rounds(&seed, num_weights)
+        H::get_n_hash_rounds(&seed, num_weights)
             .iter()
             .map(XFieldElement::sample)
             .collect()
     }
 
+    /// The weights of the combination codeword: 2 per base and extension column, and the 4 weights
+    /// of [`DeepCombination`].
+    fn sample_combination_weights(seed: Digest) -> (Vec<XFieldElement>, [XFieldElement; 4]) {
+        let num_row_weights = 2 * (NUM_BASE_COLUMNS + NUM_EXT_COLUMNS);
+        let mut row_weights = Self::sample_weights(seed, num_row_weights + 4);
+        let shift_weights = row_weights.split_off(num_row_weights);
+        (row_weights, shift_weights.try_into().unwrap())
+    }
+
+    /// Reject proofs of a different format version, made with a different hasher or different
+    /// STARK parameters, or for a different padded height, before looking at the transcript.
+    fn verify_proof_header(&self, header: &ProofHeader) -> Result<()> {
+        if header.version != PROOF_FORMAT_VERSION {
+            return Err(anyhow!(ProofFormatError::UnsupportedVersion(
+                header.version
+            )));
+        }
+        if header.hash_function != H::HASH_FUNCTION {
+            return Err(anyhow!(StarkValidationError::HashFunctionInequality));
+        }
+        if header.security_level != self.parameters.security_level
+            || header.fri_expansion_factor != self.parameters.fri_expansion_factor
+            || header.fri_folding_factor != self.parameters.fri_folding_factor
+            || header.fri_coset_leaves != self.parameters.fri_coset_leaves
+            || header.num_colinearity_checks != self.parameters.num_colinearity_checks
+            || header.num_trace_randomizers != self.parameters.num_trace_randomizers
+            || header.num_grinding_bits != self.parameters.num_grinding_bits
+            || header.zero_knowledge != self.parameters.zero_knowledge
+        {
+            return Err(anyhow!(StarkValidationError::ParametersInequality));
+        }
+        if header.padded_height != self.claim.padded_height {
+            return Err(anyhow!(StarkValidationError::PaddedHeightInequality));
+        }
+        Ok(())
+    }
+
     pub fn verify(
         &self,
         proof: Proof,
         maybe_profiler: &mut Option<TritonProfiler>,
     ) -> Result<bool> {
         prof_start!(maybe_profiler, "deserialize");
-        let mut proof_stream = StarkProofStream::from_proof(&proof)?;
+        self.verify_proof_header(&proof.header)?;
+        let mut proof_stream = ProofStream::<ProofItem, H>::from_transcript(&proof.transcript)?;
+        proof_stream.absorb_public_data(&self.claim.with_program_digest().encode());
         prof_stop!(maybe_profiler, "deserialize");
 
         prof_start!(maybe_profiler, "Fiat-Shamir 1");
@@ -554,43 +1136,153 @@ impl Stark {
         let extension_tree_merkle_root = proof_stream.dequeue()?.as_merkle_root()?;
         prof_stop!(maybe_profiler, "dequeue");
 
-        // Get weights for nonlinear combination. Concretely, sample 2 weights for each base
-        // polynomial, each extension polynomial, and each quotient. The factor is 2 because
-        // transition constraints check 2 rows.
         prof_start!(maybe_profiler, "Fiat-Shamir 2");
-        let non_lin_combi_weights_seed = proof_stream.verifier_fiat_shamir();
-        let num_non_lin_combi_weights =
-            2 * (NUM_BASE_COLUMNS + NUM_EXT_COLUMNS + num_all_table_quotients());
-        let non_lin_combi_weights = Array1::from(Self::sample_weights(
-            non_lin_combi_weights_seed,
-            num_non_lin_combi_weights,
-        ));
+        let quotient_combination_weights = Self::sample_weights(
+            proof_stream.verifier_fiat_shamir(),
+            2 * num_all_table_quotients(),
+        );
+        let quotient_tree_merkle_root = proof_stream.dequeue()?.as_merkle_root()?;
+        let out_of_domain_point = Self::sample_weights(proof_stream.verifier_fiat_shamir(), 1)[0];
         prof_stop!(maybe_profiler, "Fiat-Shamir 2");
 
+        prof_start!(maybe_profiler, "dequeue out-of-domain rows");
+        let out_of_domain_base_row = proof_stream.dequeue()?.as_out_of_domain_base_row()?;
+        let out_of_domain_ext_row = proof_stream.dequeue()?.as_out_of_domain_ext_row()?;
+        let out_of_domain_next_base_row = proof_stream.dequeue()?.as_out_of_domain_base_row()?;
+        let out_of_domain_next_ext_row = proof_stream.dequeue()?.as_out_of_domain_ext_row()?;
+        let out_of_domain_quotient_value =
+            proof_stream.dequeue()?.as_out_of_domain_quotient_value()?;
+        if out_of_domain_base_row.len() != NUM_BASE_COLUMNS
+            || out_of_domain_next_base_row.len() != NUM_BASE_COLUMNS
+            || out_of_domain_ext_row.len() != NUM_EXT_COLUMNS
+            || out_of_domain_next_ext_row.len() != NUM_EXT_COLUMNS
+        {
+            bail!(
+                "Out-of-domain rows must have as many elements as the master tables have columns"
+            );
+        }
+        let out_of_domain_base_row = Array1::from(out_of_domain_base_row);
+        let out_of_domain_ext_row = Array1::from(out_of_domain_ext_row);
+        let out_of_domain_next_base_row = Array1::from(out_of_domain_next_base_row);
+        let out_of_domain_next_ext_row = Array1::from(out_of_domain_next_ext_row);
+        prof_stop!(maybe_profiler, "dequeue out-of-domain rows");
+
+        // Check the AIR once, at the out-of-domain point: the quotients' combination, computed from
+        // the out-of-domain rows, must match the claimed out-of-domain value.
+        prof_start!(maybe_profiler, "out-of-domain quotient");
+        prof_start!(maybe_profiler, "zerofiers");
+        let one = XFieldElement::one();
+        let trace_domain_generator = derive_domain_generator(padded_height as u64);
+        let trace_domain_generator_inverse = trace_domain_generator.inverse();
+        let initial_zerofier_inverse = (out_of_domain_point - one).inverse();
+        let consistency_zerofier_inverse =
+            (out_of_domain_point.mod_pow_u32(padded_height as u32) - one).inverse();
+        let except_last_row = out_of_domain_point - trace_domain_generator_inverse;
+        let transition_zerofier_inverse = except_last_row * consistency_zerofier_inverse;
+        let terminal_zerofier_inverse = except_last_row.inverse(); // i.e., only last row
+        prof_stop!(maybe_profiler, "zerofiers");
+
+        prof_start!(maybe_profiler, "evaluate AIR");
+        let evaluated_initial_constraints = evaluate_all_initial_constraints(
+            out_of_domain_base_row.view(),
+            out_of_domain_ext_row.view(),
+            &challenges,
+        );
+        let evaluated_consistency_constraints = evaluate_all_consistency_constraints(
+            out_of_domain_base_row.view(),
+            out_of_domain_ext_row.view(),
+            &challenges,
+        );
+        let evaluated_transition_constraints = evaluate_all_transition_constraints(
+            out_of_domain_base_row.view(),
+            out_of_domain_ext_row.view(),
+            out_of_domain_next_base_row.view(),
+            out_of_domain_next_ext_row.view(),
+            &challenges,
+        );
+        let evaluated_terminal_constraints = evaluate_all_terminal_constraints(
+            out_of_domain_base_row.view(),
+            out_of_domain_ext_row.view(),
+            &challenges,
+        );
+        prof_stop!(maybe_profiler, "evaluate AIR");
+
+        prof_start!(maybe_profiler, "combine quotients");
+        let quotients = [
+            (evaluated_initial_constraints, initial_zerofier_inverse),
+            (
+                evaluated_consistency_constraints,
+                consistency_zerofier_inverse,
+            ),
+            (
+                evaluated_transition_constraints,
+                transition_zerofier_inverse,
+            ),
+            (evaluated_terminal_constraints, terminal_zerofier_inverse),
+        ]
+        .into_iter()
+        .flat_map(|(evaluated_constraints, zerofier_inverse)| {
+            evaluated_constraints
+                .into_iter()
+                .map(move |evaluated_constraint| evaluated_constraint * zerofier_inverse)
+        });
+        let quotient_degree_bounds =
+            all_quotient_degree_bounds(self.interpolant_degree, padded_height);
+        let mut quotient_combination_value = XFieldElement::zero();
+        for ((quotient, weights), degree_bound) in quotients
+            .zip_eq(quotient_combination_weights.chunks_exact(2))
+            .zip_eq(quotient_degree_bounds)
+        {
+            let shift = self.max_degree - degree_bound;
+            let shifted_point = out_of_domain_point.mod_pow_u32(shift as u32);
+            quotient_combination_value += (weights[0] + weights[1] * shifted_point) * quotient;
+        }
+        if quotient_combination_value != out_of_domain_quotient_value {
+            return Err(anyhow!(
+                StarkValidationError::OutOfDomainQuotientValueInequality
+            ));
+        }
+        prof_stop!(maybe_profiler, "combine quotients");
+        prof_stop!(maybe_profiler, "out-of-domain quotient");
+
+        // Get weights for the combination codeword. Concretely, sample 2 weights for each base
+        // polynomial and each extension polynomial, one for the out-of-domain point and one for
+        // the next row's point, plus 4 weights for shifting the degrees of the DEEP quotients.
         prof_start!(maybe_profiler, "Fiat-Shamir 3");
+        let (row_weights, shift_weights) =
+            Self::sample_combination_weights(proof_stream.verifier_fiat_shamir());
+        let deep_combination = DeepCombination {
+            point: out_of_domain_point,
+            next_row_point: out_of_domain_point * trace_domain_generator,
+            weighted_row_sum: Self::weighted_row_sums(
+                out_of_domain_base_row.view(),
+                out_of_domain_ext_row.view(),
+                &row_weights,
+            )[0],
+            weighted_next_row_sum: Self::weighted_row_sums(
+                out_of_domain_next_base_row.view(),
+                out_of_domain_next_ext_row.view(),
+                &row_weights,
+            )[1],
+            quotient_value: out_of_domain_quotient_value,
+            shift_weights,
+            row_shift: self.max_degree - self.interpolant_degree + 1,
+        };
+
         let combination_root = proof_stream.dequeue()?.as_merkle_root()?;
-        let indices_seed = proof_stream.verifier_fiat_shamir();
-        let revealed_current_row_indices = StarkHasher::sample_indices(
-            &indices_seed,
-            self.fri.domain.length,
-            self.parameters.num_non_linear_codeword_checks,
-        );
         prof_stop!(maybe_profiler, "Fiat-Shamir 3");
 
-        // verify low degree of combination polynomial with FRI
+        // Verify low degree of combination polynomial with FRI. The master table rows are checked
+        // at the positions where FRI opened the combination codeword.
         prof_start!(maybe_profiler, "FRI");
-        self.fri
-            .verify(&mut proof_stream, &combination_root, maybe_profiler)?;
+        let (revealed_indices, revealed_combination_leafs): (Vec<_>, Vec<_>) = self
+            .fri
+            .verify(&mut proof_stream, &combination_root, maybe_profiler)?
+            .into_iter()
+            .unzip();
         prof_stop!(maybe_profiler, "FRI");
 
         prof_start!(maybe_profiler, "check leafs");
-        prof_start!(maybe_profiler, "get indices");
-        // the relation between the FRI domain and the trace domain
-        let unit_distance = self.fri.domain.length / padded_height;
-        let revealed_current_and_next_row_indices = self
-            .revealed_current_and_next_row_indices(unit_distance, &revealed_current_row_indices);
-        prof_stop!(maybe_profiler, "get indices");
-
         prof_start!(maybe_profiler, "dequeue base elements");
         let base_table_rows = proof_stream.dequeue()?.as_master_base_table_rows()?;
         let base_auth_paths = proof_stream
@@ -598,14 +1290,14 @@ impl Stark {
             .as_compressed_authentication_paths()?;
         let leaf_digests_base: Vec<_> = base_table_rows
             .par_iter()
-            .map(|revealed_base_elem| StarkHasher::hash_slice(revealed_base_elem))
+            .map(|revealed_base_elem| H::hash_slice(revealed_base_elem))
             .collect();
         prof_stop!(maybe_profiler, "dequeue base elements");
 
         prof_start!(maybe_profiler, "Merkle verify (base tree)");
-        if !MerkleTree::<StarkHasher, Maker>::verify_authentication_structure_from_leaves(
+        if !MerkleTree::<H, Maker>::verify_authentication_structure_from_leaves(
             base_merkle_tree_root,
-            &revealed_current_and_next_row_indices,
+            &revealed_indices,
             &leaf_digests_base,
             &base_auth_paths,
         ) {
@@ -625,15 +1317,15 @@ impl Stark {
                     .iter()
                     .flat_map(|xfe| xfe.coefficients.to_vec())
                     .collect_vec();
-                StarkHasher::hash_slice(&bvalues)
+                H::hash_slice(&bvalues)
             })
             .collect::<Vec<_>>();
         prof_stop!(maybe_profiler, "dequeue extension elements");
 
         prof_start!(maybe_profiler, "Merkle verify (extension tree)");
-        if !MerkleTree::<StarkHasher, Maker>::verify_authentication_structure_from_leaves(
+        if !MerkleTree::<H, Maker>::verify_authentication_structure_from_leaves(
             extension_tree_merkle_root,
-            &revealed_current_and_next_row_indices,
+            &revealed_indices,
             &leaf_digests_ext,
             &auth_paths_ext,
         ) {
@@ -641,232 +1333,60 @@ impl Stark {
         }
         prof_stop!(maybe_profiler, "Merkle verify (extension tree)");
 
-        // Verify Merkle authentication path for combination elements
-        prof_start!(maybe_profiler, "Merkle verify (combination tree)");
-        let revealed_combination_leafs =
-            proof_stream.dequeue()?.as_revealed_combination_elements()?;
-        let revealed_combination_digests = revealed_combination_leafs
+        prof_start!(maybe_profiler, "Merkle verify (quotient tree)");
+        let revealed_quotient_leafs = proof_stream.dequeue()?.as_revealed_quotient_elements()?;
+        let revealed_quotient_digests = revealed_quotient_leafs
             .par_iter()
-            .map(StarkHasher::hash)
+            .map(H::hash)
             .collect::<Vec<_>>();
-        let revealed_combination_auth_paths = proof_stream
+        let revealed_quotient_auth_paths = proof_stream
             .dequeue()?
             .as_compressed_authentication_paths()?;
-        if !MerkleTree::<StarkHasher, Maker>::verify_authentication_structure_from_leaves(
-            combination_root,
-            &revealed_current_row_indices,
-            &revealed_combination_digests,
-            &revealed_combination_auth_paths,
+        if !MerkleTree::<H, Maker>::verify_authentication_structure_from_leaves(
+            quotient_tree_merkle_root,
+            &revealed_indices,
+            &revealed_quotient_digests,
+            &revealed_quotient_auth_paths,
         ) {
-            bail!("Failed to verify authentication path for combination codeword");
+            bail!("Failed to verify authentication path for quotient combination codeword");
         }
-        prof_stop!(maybe_profiler, "Merkle verify (combination tree)");
+        prof_stop!(maybe_profiler, "Merkle verify (quotient tree)");
         prof_stop!(maybe_profiler, "check leafs");
 
         prof_start!(maybe_profiler, "nonlinear combination");
-        prof_start!(maybe_profiler, "index");
-        let (indexed_base_table_rows, indexed_ext_table_rows, indexed_randomizer_rows) =
-            Self::index_revealed_rows(
-                revealed_current_and_next_row_indices,
-                base_table_rows,
-                ext_table_rows,
-            );
-        prof_stop!(maybe_profiler, "index");
-
-        // verify non-linear combination
-        prof_start!(maybe_profiler, "degree bounds");
-        let base_and_ext_col_shift = self.max_degree - self.interpolant_degree;
-        let initial_quotient_degree_bounds =
-            all_initial_quotient_degree_bounds(self.interpolant_degree);
-        let consistency_quotient_degree_bounds =
-            all_consistency_quotient_degree_bounds(self.interpolant_degree, padded_height);
-        let transition_quotient_degree_bounds =
-            all_transition_quotient_degree_bounds(self.interpolant_degree, padded_height);
-        let terminal_quotient_degree_bounds =
-            all_terminal_quotient_degree_bounds(self.interpolant_degree);
-        prof_stop!(maybe_profiler, "degree bounds");
-
-        prof_start!(maybe_profiler, "pre-compute all shifts");
-        let mut all_shifts = vec![self.interpolant_degree]
-            .iter()
-            .chain(initial_quotient_degree_bounds.iter())
-            .chain(consistency_quotient_degree_bounds.iter())
-            .chain(transition_quotient_degree_bounds.iter())
-            .chain(terminal_quotient_degree_bounds.iter())
-            .map(|degree_bound| self.max_degree - degree_bound)
-            .collect_vec();
-        all_shifts.sort();
-        all_shifts.dedup();
-        let mut all_shifted_fri_domain_values = HashMap::new();
-        prof_stop!(maybe_profiler, "pre-compute all shifts");
-
-        prof_start!(maybe_profiler, "main loop");
-        let trace_domain_generator = derive_domain_generator(padded_height as u64);
-        let trace_domain_generator_inverse = trace_domain_generator.inverse();
-        for (current_row_idx, revealed_combination_leaf) in revealed_current_row_indices
-            .into_iter()
-            .zip_eq(revealed_combination_leafs)
-        {
-            prof_itr0!(maybe_profiler, "main loop");
-            let next_row_idx = (current_row_idx + unit_distance) % self.fri.domain.length;
-            let current_base_row = indexed_base_table_rows[&current_row_idx].view();
-            let current_ext_row = indexed_ext_table_rows[&current_row_idx].view();
-            let next_base_row = indexed_base_table_rows[&next_row_idx].view();
-            let next_ext_row = indexed_ext_table_rows[&next_row_idx].view();
-
-            prof_start!(maybe_profiler, "zerofiers");
-            let one = BFieldElement::one();
-            let current_fri_domain_value = self.fri.domain.domain_value(current_row_idx as u32);
-            let initial_zerofier_inverse = (current_fri_domain_value - one).inverse();
-            let consistency_zerofier_inverse =
-                (current_fri_domain_value.mod_pow_u32(padded_height as u32) - one).inverse();
-            let except_last_row = current_fri_domain_value - trace_domain_generator_inverse;
-            let transition_zerofier_inverse = except_last_row * consistency_zerofier_inverse;
-            let terminal_zerofier_inverse = except_last_row.inverse(); // i.e., only last row
-            prof_stop!(maybe_profiler, "zerofiers");
-
-            prof_start!(maybe_profiler, "shifted FRI domain values");
-            // Minimize the respective exponents and thus work spent exponentiating by using the
-            // fact that `all_shifts` is sorted. Concretely, use
-            // 1. `x^curr_shift = x^(prev_shift + shift_diff) = x^prev_shift * x^shift_diff`,
-            // 2. memoization of `x^prev_shift`, and
-            // 3. the fact that exponentiation by a smaller exponent is computationally cheaper.
-            let mut previous_shift = all_shifts[0];
-            let mut previously_shifted_fri_domain_value =
-                current_fri_domain_value.mod_pow_u32(previous_shift as u32);
-            all_shifted_fri_domain_values
-                .insert(previous_shift, previously_shifted_fri_domain_value);
-            for &shift in all_shifts.iter().skip(1) {
-                let current_shifted_fri_domain_value = previously_shifted_fri_domain_value
-                    * current_fri_domain_value.mod_pow_u32((shift - previous_shift) as u32);
-                all_shifted_fri_domain_values.insert(shift, current_shifted_fri_domain_value);
-                previous_shift = shift;
-                previously_shifted_fri_domain_value = current_shifted_fri_domain_value;
+        for (revealed_index, base_row, ext_row, quotient_leaf, combination_leaf) in izip!(
+            revealed_indices,
+            base_table_rows,
+            ext_table_rows,
+            revealed_quotient_leafs,
+            revealed_combination_leafs,
+        ) {
+            prof_itr0!(maybe_profiler, "nonlinear combination");
+            if base_row.len() != NUM_BASE_COLUMNS || ext_row.len() < NUM_EXT_COLUMNS {
+                bail!("Revealed rows must have as many elements as the master tables have columns");
             }
-            prof_stop!(maybe_profiler, "shifted FRI domain values");
-
-            prof_start!(maybe_profiler, "evaluate AIR");
-            let evaluated_initial_constraints =
-                evaluate_all_initial_constraints(current_base_row, current_ext_row, &challenges);
-            let evaluated_consistency_constraints = evaluate_all_consistency_constraints(
-                current_base_row,
-                current_ext_row,
-                &challenges,
+            let (ext_row, randomizer_row) = ext_row.split_at(NUM_EXT_COLUMNS);
+            let weighted_row_sums = Self::weighted_row_sums(
+                ArrayView1::from(&base_row),
+                ArrayView1::from(ext_row),
+                &row_weights,
             );
-            let evaluated_transition_constraints = evaluate_all_transition_constraints(
-                current_base_row,
-                current_ext_row,
-                next_base_row,
-                next_ext_row,
-                &challenges,
-            );
-            let evaluated_terminal_constraints =
-                evaluate_all_terminal_constraints(current_base_row, current_ext_row, &challenges);
-            prof_stop!(maybe_profiler, "evaluate AIR");
-
-            prof_start!(maybe_profiler, "populate base & ext elements");
-            // populate summands with a the revealed FRI domain master table rows and their shifts
-            let base_ext_fri_domain_value_shifted =
-                all_shifted_fri_domain_values[&base_and_ext_col_shift];
-            let mut summands = Vec::with_capacity(non_lin_combi_weights.len());
-            for &base_row_element in current_base_row.iter() {
-                let base_row_element_shifted = base_row_element * base_ext_fri_domain_value_shifted;
-                summands.push(base_row_element.lift());
-                summands.push(base_row_element_shifted.lift());
-            }
-
-            for &ext_row_element in current_ext_row.iter() {
-                let ext_row_element_shifted = ext_row_element * base_ext_fri_domain_value_shifted;
-                summands.push(ext_row_element);
-                summands.push(ext_row_element_shifted);
-            }
-            prof_stop!(maybe_profiler, "populate base & ext elements");
-
-            prof_start!(maybe_profiler, "populate quotient elements");
-            for (degree_bound_category, evaluated_constraints_category, zerofier_inverse) in [
-                (
-                    &initial_quotient_degree_bounds,
-                    evaluated_initial_constraints,
-                    initial_zerofier_inverse,
-                ),
-                (
-                    &consistency_quotient_degree_bounds,
-                    evaluated_consistency_constraints,
-                    consistency_zerofier_inverse,
-                ),
-                (
-                    &transition_quotient_degree_bounds,
-                    evaluated_transition_constraints,
-                    transition_zerofier_inverse,
-                ),
-                (
-                    &terminal_quotient_degree_bounds,
-                    evaluated_terminal_constraints,
-                    terminal_zerofier_inverse,
-                ),
-            ] {
-                for (degree_bound, evaluated_constraint) in degree_bound_category
-                    .iter()
-                    .zip_eq(evaluated_constraints_category.into_iter())
-                {
-                    let shift = self.max_degree - degree_bound;
-                    let quotient = evaluated_constraint * zerofier_inverse;
-                    let quotient_shifted = quotient * all_shifted_fri_domain_values[&shift];
-                    summands.push(quotient);
-                    summands.push(quotient_shifted);
-                }
-            }
-            prof_stop!(maybe_profiler, "populate quotient elements");
-
-            prof_start!(maybe_profiler, "compute inner product");
-            let inner_product = (&non_lin_combi_weights * &Array1::from(summands)).sum();
-            let randomizer_codewords_contribution = indexed_randomizer_rows[&current_row_idx].sum();
-            if revealed_combination_leaf != inner_product + randomizer_codewords_contribution {
+            let x = self.fri.domain.domain_value(revealed_index as u32);
+            let inverses = [
+                (x - deep_combination.point).inverse(),
+                (x - deep_combination.next_row_point).inverse(),
+            ];
+            let combination_value =
+                deep_combination.evaluate(x, weighted_row_sums, quotient_leaf, inverses);
+            let randomizer_codewords_contribution: XFieldElement =
+                randomizer_row.iter().copied().sum();
+            if combination_leaf != combination_value + randomizer_codewords_contribution {
                 return Err(anyhow!(StarkValidationError::CombinationLeafInequality));
             }
-            prof_stop!(maybe_profiler, "compute inner product");
         }
-        prof_stop!(maybe_profiler, "main loop");
         prof_stop!(maybe_profiler, "nonlinear combination");
         Ok(true)
     }
-
-    /// Hash-maps for base, extension, and randomizer rows that allow doing
-    /// `indexed_revealed_base_rows[revealed_index]` instead of
-    /// `revealed_base_rows[revealed_indices.iter().position(|&i| i == revealed_index).unwrap()]`.
-    #[allow(clippy::type_complexity)]
-    fn index_revealed_rows(
-        revealed_indices: Vec<usize>,
-        revealed_base_rows: Vec<Vec<BFieldElement>>,
-        revealed_ext_rows: Vec<Vec<XFieldElement>>,
-    ) -> (
-        HashMap<usize, Array1<BFieldElement>>,
-        HashMap<usize, Array1<XFieldElement>>,
-        HashMap<usize, Array1<XFieldElement>>,
-    ) {
-        assert_eq!(revealed_indices.len(), revealed_base_rows.len());
-        assert_eq!(revealed_indices.len(), revealed_ext_rows.len());
-
-        let mut indexed_revealed_base_rows: HashMap<usize, Array1<BFieldElement>> = HashMap::new();
-        let mut indexed_revealed_ext_rows: HashMap<usize, Array1<XFieldElement>> = HashMap::new();
-        let mut indexed_revealed_rand_rows: HashMap<usize, Array1<XFieldElement>> = HashMap::new();
-
-        for (i, &idx) in revealed_indices.iter().enumerate() {
-            let base_row = Array1::from(revealed_base_rows[i].to_vec());
-            let ext_row = Array1::from(revealed_ext_rows[i][..NUM_EXT_COLUMNS].to_vec());
-            let rand_row = Array1::from(revealed_ext_rows[i][NUM_EXT_COLUMNS..].to_vec());
-
-            indexed_revealed_base_rows.insert(idx, base_row);
-            indexed_revealed_ext_rows.insert(idx, ext_row);
-            indexed_revealed_rand_rows.insert(idx, rand_row);
-        }
-
-        (
-            indexed_revealed_base_rows,
-            indexed_revealed_ext_rows,
-            indexed_revealed_rand_rows,
-        )
-    }
 }
 
 #[cfg(test)]
@@ -875,12 +1395,20 @@ pub(crate) mod triton_stark_tests {
     use ndarray::Array1;
     use num_traits::Zero;
     use rand::prelude::ThreadRng;
+    use rand::random;
     use rand_core::RngCore;
 
     use triton_opcodes::instruction::AnInstruction;
     use triton_opcodes::program::Program;
+    use twenty_first::shared_math::rescue_prime_digest::DIGEST_LENGTH;
 
+    use crate::proof::ClaimedProgram;
+    use crate::proof::HashFunction;
+    use crate::proof_estimate::ProofEstimate;
+    use crate::proof_item::MayBeUncast;
     use crate::shared_tests::*;
+    use crate::soundness::minimal_parameters;
+    use crate::soundness::SoundnessKind;
     use crate::table::cross_table_argument::CrossTableArg;
     use crate::table::cross_table_argument::EvalArg;
     use crate::table::cross_table_argument::GrandCrossTableArg;
@@ -940,18 +1468,18 @@ pub(crate) mod triton_stark_tests {
         let padded_height = MasterBaseTable::padded_height(&aet, &instructions);
         let claim = Claim {
             input: stdin,
-            program: instructions,
+            program: ClaimedProgram::Code(instructions),
             output: stdout,
             padded_height,
         };
         let log_expansion_factor = 2;
         let security_level = 32;
         let parameters = StarkParameters::new(security_level, 1 << log_expansion_factor);
-        let stark = Stark::new(claim, parameters);
+        let stark = Stark::new(claim, parameters).unwrap();
 
         let mut master_base_table = MasterBaseTable::new(
             aet,
-            &stark.claim.program,
+            stark.claim.program.code().unwrap(),
             stark.parameters.num_trace_randomizers,
             stark.fri.domain,
         );
@@ -980,6 +1508,7 @@ pub(crate) mod triton_stark_tests {
         let master_ext_table = master_base_table.extend(
             &dummy_challenges,
             stark.parameters.num_randomizer_polynomials,
+            random(),
         );
 
         (
@@ -1171,7 +1700,7 @@ pub(crate) mod triton_stark_tests {
     #[test]
     fn constraint_polynomials_use_right_variable_count_test() {
         let challenges = AllChallenges::placeholder(&[], &[]);
-        let base_row = Array1::zeros(NUM_BASE_COLUMNS);
+        let base_row = Array1::<BFieldElement>::zeros(NUM_BASE_COLUMNS);
         let ext_row = Array1::zeros(NUM_EXT_COLUMNS);
 
         let br = base_row.view();
@@ -1302,7 +1831,7 @@ pub(crate) mod triton_stark_tests {
 
     #[test]
     fn number_of_quotient_degree_bounds_match_number_of_constraints_test() {
-        let base_row = Array1::zeros(NUM_BASE_COLUMNS);
+        let base_row = Array1::<BFieldElement>::zeros(NUM_BASE_COLUMNS);
         let ext_row = Array1::zeros(NUM_EXT_COLUMNS);
         let challenges = AllChallenges::placeholder(&[], &[]);
         let padded_height = 2;
@@ -1915,6 +2444,476 @@ pub(crate) mod triton_stark_tests {
         }
     }
 
+    #[test]
+    fn verify_serialized_proof_and_reject_mismatching_header_test() {
+        let code_with_input = test_halt();
+        let (stark, proof) = parse_simulate_prove(
+            &code_with_input.source_code,
+            code_with_input.input.clone(),
+            code_with_input.secret_input.clone(),
+            &mut None,
+        );
+        assert_eq!(stark.claim.padded_height, proof.padded_height());
+
+        let proof = Proof::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(stark.verify(proof.clone(), &mut None).unwrap());
+
+        let mut other_version = proof.clone();
+        other_version.header.version += 1;
+        let err = stark.verify(other_version, &mut None).unwrap_err();
+        assert_eq!(
+            Some(&ProofFormatError::UnsupportedVersion(
+                PROOF_FORMAT_VERSION + 1
+            )),
+            err.downcast_ref::<ProofFormatError>()
+        );
+
+        let mut other_parameters = proof.clone();
+        other_parameters.header.fri_expansion_factor *= 2;
+        let err = stark.verify(other_parameters, &mut None).unwrap_err();
+        assert_eq!(
+            Some(&StarkValidationError::ParametersInequality),
+            err.downcast_ref::<StarkValidationError>()
+        );
+
+        let mut other_padded_height = proof;
+        other_padded_height.header.padded_height *= 2;
+        let err = stark.verify(other_padded_height, &mut None).unwrap_err();
+        assert_eq!(
+            Some(&StarkValidationError::PaddedHeightInequality),
+            err.downcast_ref::<StarkValidationError>()
+        );
+    }
+
+    #[test]
+    fn verify_with_program_digest_and_reject_other_program_test() {
+        let code_with_input = test_hash_nop_nop_lt();
+        let (stark, proof) = parse_simulate_prove(
+            &code_with_input.source_code,
+            code_with_input.input.clone(),
+            code_with_input.secret_input.clone(),
+            &mut None,
+        );
+
+        let compact_claim = stark.claim.with_program_digest();
+        let parameters = StarkParameters::new(
+            stark.parameters.security_level,
+            stark.parameters.fri_expansion_factor,
+        );
+        let verifier = Stark::new(compact_claim.clone(), parameters).unwrap();
+        assert!(verifier.verify(proof.clone(), &mut None).unwrap());
+
+        let mut other_program = stark.claim.program.code().unwrap().to_vec();
+        other_program.push(BFieldElement::zero());
+        let other_program_digest = ClaimedProgram::Code(other_program).digest();
+        let other_claim = Claim {
+            program: ClaimedProgram::Digest(other_program_digest),
+            ..compact_claim
+        };
+        let parameters = StarkParameters::new(
+            stark.parameters.security_level,
+            stark.parameters.fri_expansion_factor,
+        );
+        let verifier = Stark::new(other_claim, parameters).unwrap();
+        let verdict = verifier.verify(proof, &mut None);
+        assert!(!matches!(verdict, Ok(true)));
+    }
+
+    #[test]
+    fn prove_verify_with_blake3_and_reject_other_hasher_test() {
+        let code_with_input = test_hash_nop_nop_lt();
+        let (aet, output, program) = parse_setup_simulate(
+            &code_with_input.source_code,
+            code_with_input.input.clone(),
+            code_with_input.secret_input,
+        );
+        let program = program.to_bwords();
+        let claim = Claim {
+            input: code_with_input.input,
+            padded_height: MasterBaseTable::padded_height(&aet, &program),
+            program: ClaimedProgram::Code(program),
+            output,
+        };
+
+        let parameters = StarkParameters::new(32, 4);
+        let stark = Stark::<blake3::Hasher>::with_hasher(claim.clone(), parameters).unwrap();
+        let proof = stark.prove(aet, &mut None);
+        assert_eq!(HashFunction::Blake3, proof.header.hash_function);
+        assert!(stark.verify(proof.clone(), &mut None).unwrap());
+
+        let rescue_prime_stark = Stark::new(claim, StarkParameters::new(32, 4)).unwrap();
+        let err = rescue_prime_stark.verify(proof, &mut None).unwrap_err();
+        assert_eq!(
+            Some(&StarkValidationError::HashFunctionInequality),
+            err.downcast_ref::<StarkValidationError>()
+        );
+    }
+
+    fn claim_and_trace_for_sum(summands: [u64; 2]) -> (Claim, AlgebraicExecutionTrace) {
+        let input = summands.map(BFieldElement::new).to_vec();
+        let (aet, output, program) =
+            parse_setup_simulate("read_io read_io add write_io halt", input.clone(), vec![]);
+        let program = program.to_bwords();
+        let claim = Claim {
+            input,
+            padded_height: MasterBaseTable::padded_height(&aet, &program),
+            program: ClaimedProgram::Code(program),
+            output,
+        };
+        (claim, aet)
+    }
+
+    #[test]
+    fn prove_batch_and_verify_each_proof_test() {
+        let claims_and_traces = vec![
+            claim_and_trace_for_sum([3, 4]),
+            claim_and_trace_for_sum([100, 200]),
+        ];
+        let claims = claims_and_traces
+            .iter()
+            .map(|(claim, _)| claim.clone())
+            .collect_vec();
+
+        let parameters = StarkParameters::new(32, 4);
+        let proofs =
+            Stark::<StarkHasher>::prove_batch(parameters, claims_and_traces, None).unwrap();
+        assert_eq!(claims.len(), proofs.len());
+
+        for (claim, proof) in claims.iter().zip_eq(proofs.iter()) {
+            let verifier = Stark::new(claim.clone(), StarkParameters::new(32, 4)).unwrap();
+            assert!(verifier.verify(proof.clone(), &mut None).unwrap());
+        }
+
+        let other_verifier = Stark::new(claims[1].clone(), StarkParameters::new(32, 4)).unwrap();
+        let verdict = other_verifier.verify(proofs[0].clone(), &mut None);
+        assert!(!matches!(verdict, Ok(true)));
+    }
+
+    #[test]
+    fn stark_with_too_few_checks_is_rejected_test() {
+        let (claim, aet) = claim_and_trace_for_sum([1, 2]);
+        assert!(Stark::new(claim.clone(), StarkParameters::new(32, 4)).is_ok());
+
+        let too_few_checks = StarkParameters {
+            num_colinearity_checks: 4,
+            ..StarkParameters::new(32, 4)
+        };
+        let Err(StarkParametersError::InsufficientSoundness { security_level, .. }) =
+            Stark::new(claim.clone(), too_few_checks.clone())
+        else {
+            panic!("A STARK with too few colinearity checks must be rejected.");
+        };
+        assert_eq!(32, security_level);
+        assert!(
+            Stark::<blake3::Hasher>::with_hasher(claim.clone(), too_few_checks.clone()).is_err()
+        );
+
+        let batch = vec![(claim.clone(), aet)];
+        assert!(Stark::<StarkHasher>::prove_batch(too_few_checks.clone(), batch, None).is_err());
+
+        let unchecked_stark = Stark::new_unchecked(claim, too_few_checks);
+        assert_eq!(4, unchecked_stark.fri.colinearity_checks_count);
+    }
+
+    #[test]
+    fn prove_batch_rejects_mismatching_padded_heights_test() {
+        let parameters = StarkParameters::new(32, 4);
+        let no_proofs = Stark::<StarkHasher>::prove_batch(
+            parameters,
+            Vec::<(Claim, AlgebraicExecutionTrace)>::new(),
+            None,
+        );
+        assert!(no_proofs.unwrap().is_empty());
+
+        let (claim, aet) = claim_and_trace_for_sum([1, 2]);
+        let (mut other_claim, other_aet) = claim_and_trace_for_sum([3, 4]);
+        other_claim.padded_height *= 2;
+        let claims_and_traces = vec![(claim, aet), (other_claim, other_aet)];
+
+        let parameters = StarkParameters::new(32, 4);
+        let result = Stark::<StarkHasher>::prove_batch(parameters, claims_and_traces, None);
+        assert!(result.is_err());
+
+        // the claims agree on the padded height, but one trace doesn't match its claim
+        let (mut claim, aet) = claim_and_trace_for_sum([5, 6]);
+        let (mut other_claim, other_aet) = claim_and_trace_for_sum([7, 8]);
+        claim.padded_height *= 2;
+        other_claim.padded_height *= 2;
+        let claims_and_traces = vec![(claim, aet), (other_claim, other_aet)];
+        let parameters = StarkParameters::new(32, 4);
+        let err =
+            Stark::<StarkHasher>::prove_batch(parameters, claims_and_traces, None).unwrap_err();
+        assert_eq!(
+            Some(&StarkValidationError::PaddedHeightInequality),
+            err.downcast_ref::<StarkValidationError>()
+        );
+    }
+
+    #[test]
+    fn prove_verify_with_grinding_and_reject_tampered_nonce_test() {
+        let (claim, aet) = claim_and_trace_for_sum([5, 6]);
+        let parameters = StarkParameters {
+            num_grinding_bits: 8,
+            ..StarkParameters::new(32, 4)
+        };
+        let stark = Stark::new(claim.clone(), parameters).unwrap();
+        let proof = stark.prove(aet, &mut None);
+        assert_eq!(8, proof.header.num_grinding_bits);
+        assert!(stark.verify(proof.clone(), &mut None).unwrap());
+
+        let verifier_without_grinding = Stark::new(claim, StarkParameters::new(32, 4)).unwrap();
+        let err = verifier_without_grinding
+            .verify(proof.clone(), &mut None)
+            .unwrap_err();
+        assert_eq!(
+            Some(&StarkValidationError::ParametersInequality),
+            err.downcast_ref::<StarkValidationError>()
+        );
+
+        // padded height, 4 Merkle roots, 5 out-of-domain items, FRI's Merkle roots, and the last
+        // FRI codeword precede FRI's nonce
+        let nonce_position = 12 + stark.fri.num_rounds().0 as usize;
+        let mut proof_stream =
+            ProofStream::<ProofItem, StarkHasher>::from_transcript(&proof.transcript).unwrap();
+        let length_prefix_and_nonce = proof_stream.items[nonce_position].uncast();
+        assert_eq!(2, length_prefix_and_nonce.len());
+        let other_nonce = length_prefix_and_nonce[1] + BFieldElement::one();
+        proof_stream.items[nonce_position] = ProofItem::ProofOfWork(other_nonce);
+        let tampered_proof = Proof {
+            transcript: proof_stream.to_transcript(),
+            ..proof
+        };
+        let verdict = stark.verify(tampered_proof, &mut None);
+        assert!(!matches!(verdict, Ok(true)));
+    }
+
+    #[test]
+    fn prove_verify_with_higher_fri_folding_factor_test() {
+        let (claim, aet) = claim_and_trace_for_sum([7, 8]);
+        let folding_by_two = Stark::new(claim.clone(), StarkParameters::new(32, 4)).unwrap();
+        for fri_folding_factor in [4, 8, 16] {
+            let parameters =
+                StarkParameters::new(32, 4).with_fri_folding_factor(fri_folding_factor);
+            let stark = Stark::new(claim.clone(), parameters).unwrap();
+            assert!(stark.fri.num_rounds().0 < folding_by_two.fri.num_rounds().0);
+
+            let proof = stark.prove(aet.clone(), &mut None);
+            assert_eq!(fri_folding_factor, proof.header.fri_folding_factor);
+            assert!(stark.verify(proof.clone(), &mut None).unwrap());
+
+            let err = folding_by_two.verify(proof, &mut None).unwrap_err();
+            assert_eq!(
+                Some(&StarkValidationError::ParametersInequality),
+                err.downcast_ref::<StarkValidationError>()
+            );
+        }
+    }
+
+    #[test]
+    fn prove_verify_with_fri_coset_leaves_test() {
+        let (claim, aet) = claim_and_trace_for_sum([5, 6]);
+        for fri_folding_factor in [2, 4] {
+            let parameters =
+                StarkParameters::new(32, 4).with_fri_folding_factor(fri_folding_factor);
+            let element_leaves_stark = Stark::new(claim.clone(), parameters).unwrap();
+
+            let parameters = StarkParameters::new(32, 4)
+                .with_fri_folding_factor(fri_folding_factor)
+                .with_fri_coset_leaves();
+            let stark = Stark::new(claim.clone(), parameters).unwrap();
+            let proof = stark.prove(aet.clone(), &mut None);
+            assert!(proof.header.fri_coset_leaves);
+            assert!(stark.verify(proof.clone(), &mut None).unwrap());
+
+            let estimate = ProofEstimate::new(&stark.parameters, claim.padded_height);
+            let digest_slack = estimate.max_authentication_digests * DIGEST_LENGTH;
+            assert!(proof.transcript.len() <= estimate.max_transcript_length());
+            assert!(estimate.max_transcript_length() - digest_slack <= proof.transcript.len());
+            let element_leaves_estimate =
+                ProofEstimate::new(&element_leaves_stark.parameters, claim.padded_height);
+            assert!(
+                estimate.verifier_hashes.total() < element_leaves_estimate.verifier_hashes.total()
+            );
+
+            let err = element_leaves_stark.verify(proof, &mut None).unwrap_err();
+            assert_eq!(
+                Some(&StarkValidationError::ParametersInequality),
+                err.downcast_ref::<StarkValidationError>()
+            );
+        }
+    }
+
+    #[test]
+    fn verify_with_parameters_from_proof_header_test() {
+        let (claim, aet) = claim_and_trace_for_sum([9, 10]);
+        let all_parameters = [
+            StarkParameters::new(32, 4),
+            StarkParameters::new(32, 8).without_zero_knowledge(),
+            StarkParameters::new(32, 4)
+                .with_fri_folding_factor(4)
+                .with_fri_coset_leaves(),
+            StarkParameters {
+                num_grinding_bits: 4,
+                ..StarkParameters::new(32, 4)
+            },
+            StarkParameters {
+                num_trace_randomizers: 5,
+                num_colinearity_checks: 20,
+                ..StarkParameters::new(32, 4)
+            },
+            minimal_parameters(32, claim.padded_height, 0, SoundnessKind::Conjectured).unwrap(),
+        ];
+        for parameters in all_parameters {
+            let stark = Stark::new(claim.clone(), parameters).unwrap();
+            let proof = stark.prove(aet.clone(), &mut None);
+
+            let parameters = StarkParameters::from_header(&proof.header).unwrap();
+            assert_eq!(stark.parameters, parameters);
+            let verifier = Stark::new(claim.clone(), parameters).unwrap();
+            assert!(verifier.verify(proof, &mut None).unwrap());
+        }
+
+        let (claim, aet) = claim_and_trace_for_sum([1, 1]);
+        let mut proof = Stark::new(claim.clone(), StarkParameters::new(32, 4))
+            .unwrap()
+            .prove(aet, &mut None);
+        proof.header.fri_folding_factor = 3;
+        assert!(StarkParameters::from_header(&proof.header).is_err());
+        proof.header.fri_folding_factor = 2;
+        proof.header.fri_expansion_factor = 1;
+        assert!(StarkParameters::from_header(&proof.header).is_err());
+        proof.header.fri_expansion_factor = 4;
+        proof.header.zero_knowledge = false;
+        assert!(StarkParameters::from_header(&proof.header).is_err());
+        proof.header.zero_knowledge = true;
+        assert!(StarkParameters::from_header(&proof.header).is_ok());
+
+        let valid_header = proof.header;
+        let fri_domain_length = Stark::new(claim, StarkParameters::new(32, 4))
+            .unwrap()
+            .fri
+            .domain
+            .length;
+        for num_colinearity_checks in [0, fri_domain_length + 1] {
+            proof.header.num_colinearity_checks = num_colinearity_checks;
+            assert!(StarkParameters::from_header(&proof.header).is_err());
+        }
+        proof.header = valid_header;
+        proof.header.num_grinding_bits = MAX_NUM_GRINDING_BITS + 1;
+        assert!(StarkParameters::from_header(&proof.header).is_err());
+        for padded_height in [0, 3, 2 * MAX_FRI_DOMAIN_LENGTH, MAX_FRI_DOMAIN_LENGTH] {
+            proof.header = valid_header;
+            proof.header.padded_height = padded_height;
+            assert!(StarkParameters::from_header(&proof.header).is_err());
+        }
+        proof.header = valid_header;
+        proof.header.num_trace_randomizers = usize::MAX;
+        assert!(StarkParameters::from_header(&proof.header).is_err());
+    }
+
+    #[test]
+    fn prove_verify_and_reject_tampered_out_of_domain_values_test() {
+        let (claim, aet) = claim_and_trace_for_sum([11, 12]);
+        let stark = Stark::new(claim, StarkParameters::new(32, 4)).unwrap();
+        let proof = stark.prove(aet, &mut None);
+        assert!(stark.verify(proof.clone(), &mut None).unwrap());
+
+        // padded height and 3 Merkle roots precede the out-of-domain base row and quotient value
+        let out_of_domain_base_row_position = 4;
+        let out_of_domain_quotient_value_position = 8;
+        for position in [
+            out_of_domain_base_row_position,
+            out_of_domain_quotient_value_position,
+        ] {
+            let mut proof_stream =
+                ProofStream::<ProofItem, StarkHasher>::from_transcript(&proof.transcript).unwrap();
+            let mut length_prefix_and_item = proof_stream.items[position].uncast();
+            length_prefix_and_item[1] += BFieldElement::one();
+            proof_stream.items[position] = ProofItem::Uncast(length_prefix_and_item[1..].to_vec());
+            let tampered_proof = Proof {
+                transcript: proof_stream.to_transcript(),
+                ..proof.clone()
+            };
+            let err = stark.verify(tampered_proof, &mut None).unwrap_err();
+            assert_eq!(
+                Some(&StarkValidationError::OutOfDomainQuotientValueInequality),
+                err.downcast_ref::<StarkValidationError>()
+            );
+        }
+    }
+
+    #[test]
+    fn proofs_with_same_prover_seed_are_identical_test() {
+        let prove_with_seed = |seed| {
+            let (claim, aet) = claim_and_trace_for_sum([7, 8]);
+            let stark = Stark::new(claim, StarkParameters::new(32, 4))
+                .unwrap()
+                .with_prover_seed(seed);
+            let proof = stark.prove(aet, &mut None);
+            assert!(stark.verify(proof.clone(), &mut None).unwrap());
+            proof
+        };
+
+        let seed: [u8; 32] = random();
+        let proof = prove_with_seed(seed);
+        assert_eq!(proof, prove_with_seed(seed));
+
+        let mut other_seed = seed;
+        other_seed[0] ^= 1;
+        assert_ne!(proof, prove_with_seed(other_seed));
+    }
+
+    #[test]
+    fn seeded_batch_uses_different_randomizers_per_claim_test() {
+        let seed: [u8; 32] = random();
+        let claims_and_traces = vec![
+            claim_and_trace_for_sum([7, 8]),
+            claim_and_trace_for_sum([7, 8]),
+        ];
+        let claim = claims_and_traces[0].0.clone();
+        let proofs = Stark::<StarkHasher>::prove_batch(
+            StarkParameters::new(32, 4),
+            claims_and_traces,
+            Some(seed),
+        )
+        .unwrap();
+
+        // the claims are identical, so only different randomizers make the proofs differ
+        assert_ne!(proofs[0], proofs[1]);
+        for proof in proofs.iter() {
+            let verifier = Stark::new(claim.clone(), StarkParameters::new(32, 4)).unwrap();
+            assert!(verifier.verify(proof.clone(), &mut None).unwrap());
+        }
+
+        let (_, aet) = claim_and_trace_for_sum([7, 8]);
+        let stark = Stark::new(claim, StarkParameters::new(32, 4))
+            .unwrap()
+            .with_prover_seed(seed);
+        assert_eq!(proofs[0], stark.prove(aet, &mut None));
+    }
+
+    #[test]
+    fn prove_verify_without_zero_knowledge_test() {
+        let (claim, aet) = claim_and_trace_for_sum([9, 10]);
+        let parameters = StarkParameters::new(32, 4).without_zero_knowledge();
+        let stark = Stark::new(claim.clone(), parameters).unwrap();
+        let proof = stark.prove(aet, &mut None);
+        assert!(!proof.header.zero_knowledge);
+        assert!(stark.verify(proof.clone(), &mut None).unwrap());
+
+        let (_, aet) = claim_and_trace_for_sum([9, 10]);
+        let zk_stark = Stark::new(claim, StarkParameters::new(32, 4)).unwrap();
+        let zk_proof = zk_stark.prove(aet, &mut None);
+        assert!(stark.fri.domain.length < zk_stark.fri.domain.length);
+        assert!(proof.transcript.len() < zk_proof.transcript.len());
+
+        let err = zk_stark.verify(proof, &mut None).unwrap_err();
+        assert_eq!(
+            Some(&StarkValidationError::ParametersInequality),
+            err.downcast_ref::<StarkValidationError>()
+        );
+    }
+
     #[test]
     #[ignore = "used for tracking&debugging deserialization errors"]
     fn triton_prove_halt_save_error_test() {
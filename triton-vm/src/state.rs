@@ -28,7 +28,10 @@ use triton_opcodes::program::Program;
 
 use crate::error::vm_err;
 use crate::error::vm_fail;
+use crate::error::InstructionError;
 use crate::error::InstructionError::*;
+use crate::input::HintHandler;
+use crate::input::InputProvider;
 use crate::op_stack::OpStack;
 use crate::table::processor_table;
 use crate::table::processor_table::ProcessorTraceRow;
@@ -127,12 +130,13 @@ impl<'pgm> VMState<'pgm> {
     /// Given a state, compute `(next_state, vm_output)`.
     pub fn step(
         &self,
-        stdin: &mut Vec<BFieldElement>,
-        secret_in: &mut Vec<BFieldElement>,
+        stdin: &mut dyn InputProvider,
+        secret_in: &mut dyn InputProvider,
+        hint_handler: &mut dyn HintHandler,
     ) -> Result<(VMState<'pgm>, Option<VMOutput>)> {
         let mut next_state = self.clone();
         next_state
-            .step_mut(stdin, secret_in)
+            .step_mut(stdin, secret_in, hint_handler)
             .map(|vm_output| (next_state, vm_output))
     }
 
@@ -200,8 +204,9 @@ impl<'pgm> VMState<'pgm> {
     /// Perform the state transition as a mutable operation on `self`.
     pub fn step_mut(
         &mut self,
-        stdin: &mut Vec<BFieldElement>,
-        secret_in: &mut Vec<BFieldElement>,
+        stdin: &mut dyn InputProvider,
+        secret_in: &mut dyn InputProvider,
+        hint_handler: &mut dyn HintHandler,
     ) -> Result<Option<VMOutput>> {
         // All instructions increase the cycle count
         self.cycle_count += 1;
@@ -223,8 +228,11 @@ impl<'pgm> VMState<'pgm> {
                 self.instruction_pointer += 2;
             }
 
-            Divine(_) => {
-                let elem = secret_in.remove(0);
+            Divine(hint) => {
+                let elem = match hint {
+                    Some(hint) => hint_handler.divine(hint, self)?,
+                    None => next_input_element(secret_in, SecretInputExhausted)?,
+                };
                 self.op_stack.push(elem);
                 self.instruction_pointer += 1;
             }
@@ -548,7 +556,7 @@ impl<'pgm> VMState<'pgm> {
             }
 
             ReadIo => {
-                let in_elem = stdin.remove(0);
+                let in_elem = next_input_element(stdin, PublicInputExhausted)?;
                 self.op_stack.push(in_elem);
                 self.instruction_pointer += 1;
             }
@@ -731,7 +739,7 @@ impl<'pgm> VMState<'pgm> {
         }
     }
 
-    fn divine_sibling(&mut self, secret_in: &mut Vec<BFieldElement>) -> Result<()> {
+    fn divine_sibling(&mut self, secret_in: &mut dyn InputProvider) -> Result<()> {
         // st0-st4
         let _ = self.op_stack.pop_n::<{ DIGEST_LENGTH }>()?;
 
@@ -740,18 +748,19 @@ impl<'pgm> VMState<'pgm> {
 
         // st10
         let node_index_elem: BFieldElement = self.op_stack.pop()?;
-        let node_index: u32 = node_index_elem
-            .try_into()
-            .unwrap_or_else(|_| panic!("{node_index_elem:?} is not a u32"));
+        let node_index: u32 = match node_index_elem.try_into() {
+            Ok(node_index) => node_index,
+            Err(_) => return vm_err(NonU32NodeIndex(node_index_elem)),
+        };
 
         // nondeterministic guess, flipped
         let sibling_digest: [BFieldElement; DIGEST_LENGTH] = {
             let mut tmp = [
-                secret_in.remove(0),
-                secret_in.remove(0),
-                secret_in.remove(0),
-                secret_in.remove(0),
-                secret_in.remove(0),
+                next_input_element(secret_in, SecretInputExhausted)?,
+                next_input_element(secret_in, SecretInputExhausted)?,
+                next_input_element(secret_in, SecretInputExhausted)?,
+                next_input_element(secret_in, SecretInputExhausted)?,
+                next_input_element(secret_in, SecretInputExhausted)?,
             ];
             tmp.reverse();
             tmp
@@ -799,6 +808,16 @@ impl<'pgm> VMState<'pgm> {
     }
 }
 
+fn next_input_element(
+    input: &mut dyn InputProvider,
+    exhausted_error: InstructionError,
+) -> Result<BFieldElement> {
+    match input.next_element()? {
+        Some(element) => Ok(element),
+        None => vm_err(exhausted_error),
+    }
+}
+
 impl<'pgm> Display for VMState<'pgm> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.current_instruction() {
@@ -824,6 +843,7 @@ mod vm_state_tests {
     use crate::shared_tests::{FIBONACCI_VIT, FIB_FIXED_7_LT};
     use crate::stark::Maker;
     use crate::vm::run;
+    use crate::vm::run_and_observe;
     use crate::vm::triton_vm_tests::GCD_X_Y;
 
     use super::*;
@@ -843,11 +863,8 @@ mod vm_state_tests {
     #[test]
     fn run_tvm_parse_pop_p_test() {
         let program = Program::from_code("push 1 push 1 add pop").unwrap();
-        let (trace, _out, _err) = run(&program, vec![], vec![]);
-
-        for state in trace.iter() {
-            println!("{state}");
-        }
+        let (_, _out, _err) =
+            run_and_observe(&program, vec![], vec![], |state| println!("{state}"));
     }
 
     #[test]
@@ -872,9 +889,7 @@ mod vm_state_tests {
             write_io write_io write_io write_io write_io write_io write_io
         ";
         let program = Program::from_code(code).unwrap();
-        let (trace, _out, _err) = run(&program, vec![], vec![]);
-
-        let last_state = trace.last().unwrap();
+        let (last_state, _out, _err) = run(&program, vec![], vec![]);
         assert_eq!(BFieldElement::zero(), last_state.op_stack.safe_peek(ST0));
 
         println!("{last_state}");
@@ -884,17 +899,13 @@ mod vm_state_tests {
     fn run_tvm_halt_then_do_stuff_test() {
         let halt_then_do_stuff = "halt push 1 push 2 add invert write_io";
         let program = Program::from_code(halt_then_do_stuff).unwrap();
-        let (trace, _out, err) = run(&program, vec![], vec![]);
-
-        for state in trace.iter() {
-            println!("{state}");
-        }
+        let (last_state, _out, err) =
+            run_and_observe(&program, vec![], vec![], |state| println!("{state}"));
         if let Some(e) = err {
             println!("Error: {e}");
         }
 
         // check for graceful termination
-        let last_state = trace.last().unwrap();
         assert_eq!(last_state.current_instruction().unwrap(), Halt);
     }
 
@@ -911,12 +922,11 @@ mod vm_state_tests {
             halt
             ";
         let program = Program::from_code(basic_ram_read_write_code).unwrap();
-        let (trace, _out, err) = run(&program, vec![], vec![]);
+        let (last_state, _out, err) = run(&program, vec![], vec![]);
         if let Some(e) = err {
             println!("Error: {e}");
         }
 
-        let last_state = trace.last().expect("Execution seems to have failed.");
         let five = BFieldElement::new(5);
         let seven = BFieldElement::new(7);
         let fifteen = BFieldElement::new(15);
@@ -939,12 +949,11 @@ mod vm_state_tests {
             halt
         ";
         let program = Program::from_code(edgy_ram_writes_code).unwrap();
-        let (trace, _out, err) = run(&program, vec![], vec![]);
+        let (last_state, _out, err) = run(&program, vec![], vec![]);
         if let Some(e) = err {
             println!("Error: {e}");
         }
 
-        let last_state = trace.last().expect("Execution seems to have failed.");
         let zero = BFieldElement::zero();
         let three = BFieldElement::new(3);
         let five = BFieldElement::new(5);
@@ -987,17 +996,13 @@ mod vm_state_tests {
         let program = Program::from_code(sample_weights_code).unwrap();
         println!("Successfully parsed the program.");
         let input_symbols = vec![BFieldElement::new(11)];
-        let (trace, _out, err) = run(&program, input_symbols, vec![]);
-
-        for state in trace.iter() {
-            println!("{state}");
-        }
+        let (last_state, _out, err) =
+            run_and_observe(&program, input_symbols, vec![], |state| println!("{state}"));
         if let Some(e) = err {
             panic!("The VM encountered an error: {e}");
         }
 
         // check for graceful termination
-        let last_state = trace.last().unwrap();
         assert_eq!(last_state.current_instruction().unwrap(), Halt);
     }
 
@@ -1145,17 +1150,13 @@ mod vm_state_tests {
             leafs[55].values()[order[4]],
         ];
 
-        let (trace, _out, err) = run(&program, input, secret_input);
-
-        for state in trace.iter() {
-            println!("{state}");
-        }
+        let (last_state, _out, err) =
+            run_and_observe(&program, input, secret_input, |state| println!("{state}"));
         if let Some(e) = err {
             panic!("The VM encountered an error: {e}");
         }
 
         // check for graceful termination
-        let last_state = trace.last().unwrap();
         assert_eq!(last_state.current_instruction().unwrap(), Halt);
     }
 
@@ -1177,17 +1178,14 @@ mod vm_state_tests {
         let program = Program::from_code(get_colinear_y_code).unwrap();
         println!("Successfully parsed the program.");
         let input_symbols = [7, 2, 1, 3, 4].map(BFieldElement::new).to_vec();
-        let (trace, out, err) = run(&program, input_symbols, vec![]);
+        let (last_state, out, err) =
+            run_and_observe(&program, input_symbols, vec![], |state| println!("{state}"));
         assert_eq!(out[0], BFieldElement::new(4));
-        for state in trace.iter() {
-            println!("{state}");
-        }
         if let Some(e) = err {
             panic!("The VM encountered an error: {e}");
         }
 
         // check for graceful termination
-        let last_state = trace.last().unwrap();
         assert_eq!(last_state.current_instruction().unwrap(), Halt);
     }
 
@@ -1210,12 +1208,8 @@ mod vm_state_tests {
             ";
 
         let program = Program::from_code(countdown_code).unwrap();
-        let (trace, out, err) = run(&program, vec![], vec![]);
-
         println!("{program}");
-        for state in trace.iter() {
-            println!("{state}");
-        }
+        let (_, out, err) = run_and_observe(&program, vec![], vec![], |state| println!("{state}"));
 
         if let Some(e) = err {
             panic!("The VM encountered an error: {e}");
@@ -1229,7 +1223,7 @@ mod vm_state_tests {
     fn run_tvm_fibonacci_vit_tvm() {
         let code = FIBONACCI_VIT;
         let program = Program::from_code(code).unwrap();
-        let (_trace, out, err) = run(&program, vec![7_u64.into()], vec![]);
+        let (_state, out, err) = run(&program, vec![7_u64.into()], vec![]);
         if let Some(e) = err {
             panic!("The VM encountered an error: {e}");
         }
@@ -1241,8 +1235,7 @@ mod vm_state_tests {
     fn run_tvm_fibonacci_lt_test() {
         let code = FIB_FIXED_7_LT;
         let program = Program::from_code(code).unwrap();
-        let (trace, _out, _err) = run(&program, vec![], vec![]);
-        let last_state = trace.last().unwrap();
+        let (last_state, _out, _err) = run(&program, vec![], vec![]);
         assert_eq!(BFieldElement::new(21), last_state.op_stack.st(ST0));
     }
 
@@ -1252,12 +1245,8 @@ mod vm_state_tests {
         let program = Program::from_code(code).unwrap();
 
         println!("{program}");
-        let (trace, out, _err) = run(&program, vec![42_u64.into(), 56_u64.into()], vec![]);
-
-        println!("{program}");
-        for state in trace.iter() {
-            println!("{state}");
-        }
+        let input = vec![42_u64.into(), 56_u64.into()];
+        let (_, out, _err) = run_and_observe(&program, input, vec![], |state| println!("{state}"));
 
         let expected = BFieldElement::new(14);
         let actual = *out.last().unwrap();
@@ -1274,8 +1263,8 @@ mod vm_state_tests {
     #[test]
     fn read_mem_unitialized() {
         let program = Program::from_code("read_mem halt").unwrap();
-        let (trace, _out, err) = run(&program, vec![], vec![]);
+        let (last_state, _out, err) = run(&program, vec![], vec![]);
         assert!(err.is_none(), "Reading from uninitialized memory address");
-        assert_eq!(2, trace.len());
+        assert_eq!(1, last_state.cycle_count);
     }
 }
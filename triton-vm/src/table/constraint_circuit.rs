@@ -62,6 +62,10 @@ pub trait InputIndicator: Debug + Clone + Copy + Hash + PartialEq + Eq + Display
     fn base_row_index(&self) -> usize;
     fn ext_row_index(&self) -> usize;
 
+    /// The row `self` refers to, relative to the current row: 0 for the current row, 1 for the
+    /// next row.
+    fn row_offset(&self) -> usize;
+
     fn evaluate(
         &self,
         base_table: ArrayView2<BFieldElement>,
@@ -115,6 +119,10 @@ impl<const BASE_COLUMN_COUNT: usize, const EXT_COLUMN_COUNT: usize> InputIndicat
         }
     }
 
+    fn row_offset(&self) -> usize {
+        0
+    }
+
     fn evaluate(
         &self,
         base_table: ArrayView2<BFieldElement>,
@@ -182,6 +190,13 @@ impl<const BASE_COLUMN_COUNT: usize, const EXT_COLUMN_COUNT: usize> InputIndicat
         }
     }
 
+    fn row_offset(&self) -> usize {
+        match self {
+            DualRowIndicator::CurrentBaseRow(_) | DualRowIndicator::CurrentExtRow(_) => 0,
+            DualRowIndicator::NextBaseRow(_) | DualRowIndicator::NextExtRow(_) => 1,
+        }
+    }
+
     fn evaluate(
         &self,
         base_table: ArrayView2<BFieldElement>,
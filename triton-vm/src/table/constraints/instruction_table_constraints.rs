@@ -1,3 +1,5 @@
+use twenty_first::shared_math::traits::FiniteField;
+
 use crate::table::extension_table::Evaluable;
 use crate::table::extension_table::Quotientable;
 use crate::table::instruction_table::ExtInstructionTable;
@@ -5,6 +7,6 @@ use crate::table::instruction_table::ExtInstructionTable;
 // This file is a placeholder for auto-generated code
 // Run `cargo run --bin constraint-evaluation-generator`
 // to fill in this file with optimized constraints.
-impl Evaluable for ExtInstructionTable {}
+impl<FF: FiniteField> Evaluable<FF> for ExtInstructionTable {}
 
 impl Quotientable for ExtInstructionTable {}
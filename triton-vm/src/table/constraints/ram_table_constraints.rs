@@ -1,3 +1,5 @@
+use twenty_first::shared_math::traits::FiniteField;
+
 use crate::table::extension_table::Evaluable;
 use crate::table::extension_table::Quotientable;
 use crate::table::ram_table::ExtRamTable;
@@ -5,6 +7,6 @@ use crate::table::ram_table::ExtRamTable;
 // This file is a placeholder for auto-generated code
 // Run `cargo run --bin constraint-evaluation-generator`
 // to fill in this file with optimized constraints.
-impl Evaluable for ExtRamTable {}
+impl<FF: FiniteField> Evaluable<FF> for ExtRamTable {}
 
 impl Quotientable for ExtRamTable {}
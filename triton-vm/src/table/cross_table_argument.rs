@@ -7,6 +7,7 @@ use strum_macros::EnumCount as EnumCountMacro;
 use strum_macros::EnumIter;
 use twenty_first::shared_math::b_field_element::BFieldElement;
 use twenty_first::shared_math::mpolynomial::Degree;
+use twenty_first::shared_math::traits::FiniteField;
 use twenty_first::shared_math::x_field_element::XFieldElement;
 
 use CrossTableChallengeId::*;
@@ -166,9 +167,9 @@ impl TableChallenges for CrossTableChallenges {
     }
 }
 
-impl Evaluable for GrandCrossTableArg {
+impl<FF: FiniteField> Evaluable<FF> for GrandCrossTableArg {
     fn evaluate_initial_constraints(
-        _base_row: ArrayView1<BFieldElement>,
+        _base_row: ArrayView1<FF>,
         _ext_row: ArrayView1<XFieldElement>,
         _challenges: &AllChallenges,
     ) -> Vec<XFieldElement> {
@@ -176,7 +177,7 @@ impl Evaluable for GrandCrossTableArg {
     }
 
     fn evaluate_consistency_constraints(
-        _base_row: ArrayView1<BFieldElement>,
+        _base_row: ArrayView1<FF>,
         _ext_row: ArrayView1<XFieldElement>,
         _challenges: &AllChallenges,
     ) -> Vec<XFieldElement> {
@@ -184,9 +185,9 @@ impl Evaluable for GrandCrossTableArg {
     }
 
     fn evaluate_transition_constraints(
-        _current_base_row: ArrayView1<BFieldElement>,
+        _current_base_row: ArrayView1<FF>,
         _current_ext_row: ArrayView1<XFieldElement>,
-        _next_base_row: ArrayView1<BFieldElement>,
+        _next_base_row: ArrayView1<FF>,
         _next_ext_row: ArrayView1<XFieldElement>,
         _challenges: &AllChallenges,
     ) -> Vec<XFieldElement> {
@@ -194,7 +195,7 @@ impl Evaluable for GrandCrossTableArg {
     }
 
     fn evaluate_terminal_constraints(
-        _base_row: ArrayView1<BFieldElement>,
+        _base_row: ArrayView1<FF>,
         ext_row: ArrayView1<XFieldElement>,
         challenges: &AllChallenges,
     ) -> Vec<XFieldElement> {
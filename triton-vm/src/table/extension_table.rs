@@ -9,6 +9,7 @@ use ndarray::ArrayViewMut2;
 use ndarray::Axis;
 use twenty_first::shared_math::b_field_element::BFieldElement;
 use twenty_first::shared_math::mpolynomial::Degree;
+use twenty_first::shared_math::traits::FiniteField;
 use twenty_first::shared_math::x_field_element::XFieldElement;
 
 use crate::arithmetic_domain::ArithmeticDomain;
@@ -19,11 +20,15 @@ const ERROR_MESSAGE_GENERATE_CONSTRAINTS: &str =
 const ERROR_MESSAGE_GENERATE_DEGREE_BOUNDS: &str =
     "Degree bounds must be in place. Run: `cargo run --bin constraint-evaluation-generator`";
 
-pub trait Evaluable {
+/// Evaluating the AIR constraints of a table. The base rows are elements of field `FF`: the
+/// prover evaluates the constraints on rows of the master base table, which are
+/// [`BFieldElement`]s, while the verifier evaluates them on the out-of-domain rows, which are
+/// [`XFieldElement`]s.
+pub trait Evaluable<FF: FiniteField> {
     /// The code for this method must be generated by running
     /// `cargo run --bin constraint-evaluation-generator`
     fn evaluate_initial_constraints(
-        _base_row: ArrayView1<BFieldElement>,
+        _base_row: ArrayView1<FF>,
         _ext_row: ArrayView1<XFieldElement>,
         _challenges: &AllChallenges,
     ) -> Vec<XFieldElement> {
@@ -33,7 +38,7 @@ pub trait Evaluable {
     /// The code for this method must be generated by running
     /// `cargo run --bin constraint-evaluation-generator`
     fn evaluate_consistency_constraints(
-        _base_row: ArrayView1<BFieldElement>,
+        _base_row: ArrayView1<FF>,
         _ext_row: ArrayView1<XFieldElement>,
         _challenges: &AllChallenges,
     ) -> Vec<XFieldElement> {
@@ -43,9 +48,9 @@ pub trait Evaluable {
     /// The code for this method must be generated by running
     /// `cargo run --bin constraint-evaluation-generator`
     fn evaluate_transition_constraints(
-        _current_base_row: ArrayView1<BFieldElement>,
+        _current_base_row: ArrayView1<FF>,
         _current_ext_row: ArrayView1<XFieldElement>,
-        _next_base_row: ArrayView1<BFieldElement>,
+        _next_base_row: ArrayView1<FF>,
         _next_ext_row: ArrayView1<XFieldElement>,
         _challenges: &AllChallenges,
     ) -> Vec<XFieldElement> {
@@ -55,7 +60,7 @@ pub trait Evaluable {
     /// The code for this method must be generated by running
     /// `cargo run --bin constraint-evaluation-generator`
     fn evaluate_terminal_constraints(
-        _base_row: ArrayView1<BFieldElement>,
+        _base_row: ArrayView1<FF>,
         _ext_row: ArrayView1<XFieldElement>,
         _challenges: &AllChallenges,
     ) -> Vec<XFieldElement> {
@@ -63,7 +68,7 @@ pub trait Evaluable {
     }
 }
 
-pub trait Quotientable: Evaluable {
+pub trait Quotientable: Evaluable<BFieldElement> {
     /// Compute the degrees of the quotients from all AIR constraints that apply to the table.
     fn all_degrees_with_origin(
         table_name: &str,
@@ -23,6 +23,7 @@ use twenty_first::shared_math::x_field_element::XFieldElement;
 
 use triton_opcodes::instruction::Instruction;
 
+use crate::execution_trace::ExecutionTrace;
 use crate::table::challenges::TableChallenges;
 use crate::table::constraint_circuit::ConstraintCircuit;
 use crate::table::constraint_circuit::ConstraintCircuitBuilder;
@@ -45,7 +46,6 @@ use crate::table::table_column::HashExtTableColumn;
 use crate::table::table_column::HashExtTableColumn::*;
 use crate::table::table_column::MasterBaseTableColumn;
 use crate::table::table_column::MasterExtTableColumn;
-use crate::vm::AlgebraicExecutionTrace;
 
 pub const HASH_TABLE_NUM_PERMUTATION_ARGUMENTS: usize = 0;
 pub const HASH_TABLE_NUM_EVALUATION_ARGUMENTS: usize = 2;
@@ -621,19 +621,16 @@ impl ExtHashTable {
 }
 
 impl HashTable {
-    pub fn fill_trace(
-        hash_table: &mut ArrayViewMut2<BFieldElement>,
-        aet: &AlgebraicExecutionTrace,
-    ) {
+    pub fn fill_trace(hash_table: &mut ArrayViewMut2<BFieldElement>, aet: &impl ExecutionTrace) {
         let sponge_part_start = 0;
-        let sponge_part_end = sponge_part_start + aet.sponge_trace.nrows();
+        let sponge_part_end = sponge_part_start + aet.sponge_trace().nrows();
         let hash_part_start = sponge_part_end;
-        let hash_part_end = hash_part_start + aet.hash_trace.nrows();
+        let hash_part_end = hash_part_start + aet.hash_trace().nrows();
 
-        let sponge_part = hash_table.slice_mut(s![sponge_part_start..sponge_part_end, ..]);
-        aet.sponge_trace.clone().move_into(sponge_part);
-        let hash_part = hash_table.slice_mut(s![hash_part_start..hash_part_end, ..]);
-        aet.hash_trace.clone().move_into(hash_part);
+        let mut sponge_part = hash_table.slice_mut(s![sponge_part_start..sponge_part_end, ..]);
+        sponge_part.assign(&aet.sponge_trace());
+        let mut hash_part = hash_table.slice_mut(s![hash_part_start..hash_part_end, ..]);
+        hash_part.assign(&aet.hash_trace());
     }
 
     pub fn pad_trace(hash_table: &mut ArrayViewMut2<BFieldElement>, hash_table_length: usize) {
@@ -15,6 +15,7 @@ use twenty_first::shared_math::x_field_element::XFieldElement;
 
 use InstructionTableChallengeId::*;
 
+use crate::execution_trace::ExecutionTrace;
 use crate::table::challenges::TableChallenges;
 use crate::table::constraint_circuit::ConstraintCircuit;
 use crate::table::constraint_circuit::ConstraintCircuitBuilder;
@@ -37,7 +38,6 @@ use crate::table::table_column::InstructionExtTableColumn::*;
 use crate::table::table_column::MasterBaseTableColumn;
 use crate::table::table_column::MasterExtTableColumn;
 use crate::table::table_column::ProcessorBaseTableColumn;
-use crate::vm::AlgebraicExecutionTrace;
 
 pub const INSTRUCTION_TABLE_NUM_PERMUTATION_ARGUMENTS: usize = 1;
 pub const INSTRUCTION_TABLE_NUM_EVALUATION_ARGUMENTS: usize = 1;
@@ -294,7 +294,7 @@ impl ExtInstructionTable {
 impl InstructionTable {
     pub fn fill_trace(
         instruction_table: &mut ArrayViewMut2<BFieldElement>,
-        aet: &AlgebraicExecutionTrace,
+        aet: &impl ExecutionTrace,
         program: &[BFieldElement],
     ) {
         // Pre-process the AET's processor trace to find the number of occurrences of each unique
@@ -303,7 +303,7 @@ impl InstructionTable {
         // program is static.
         let program_len = program.len();
         let mut processor_trace_row_counts = vec![0; program_len];
-        for row in aet.processor_trace.rows() {
+        for row in aet.processor_trace().rows() {
             let ip = row[ProcessorBaseTableColumn::IP.base_table_index()].value() as usize;
             assert!(ip < program_len, "IP out of bounds – forgot to \"halt\"?");
             processor_trace_row_counts[ip] += 1;
@@ -22,6 +22,7 @@ use std::fmt::Display;
 use std::fmt::Formatter;
 use JumpStackTableChallengeId::*;
 
+use crate::execution_trace::ExecutionTrace;
 use crate::table::challenges::TableChallenges;
 use crate::table::constraint_circuit::ConstraintCircuit;
 use crate::table::constraint_circuit::ConstraintCircuitBuilder;
@@ -42,7 +43,6 @@ use crate::table::table_column::JumpStackExtTableColumn::*;
 use crate::table::table_column::MasterBaseTableColumn;
 use crate::table::table_column::MasterExtTableColumn;
 use crate::table::table_column::ProcessorBaseTableColumn;
-use crate::vm::AlgebraicExecutionTrace;
 
 pub const JUMP_STACK_TABLE_NUM_PERMUTATION_ARGUMENTS: usize = 1;
 pub const JUMP_STACK_TABLE_NUM_EVALUATION_ARGUMENTS: usize = 0;
@@ -139,9 +139,6 @@ impl ExtJumpStackTable {
         let jsp_next = circuit_builder.input(NextBaseRow(JSP.master_base_table_index()));
         let jso_next = circuit_builder.input(NextBaseRow(JSO.master_base_table_index()));
         let jsd_next = circuit_builder.input(NextBaseRow(JSD.master_base_table_index()));
-        let clk_di_next = circuit_builder.input(NextBaseRow(
-            InverseOfClkDiffMinusOne.master_base_table_index(),
-        ));
         let rppa_next =
             circuit_builder.input(NextExtRow(RunningProductPermArg.master_ext_table_index()));
         let rpcjd_next = circuit_builder.input(NextExtRow(
@@ -178,11 +175,12 @@ impl ExtJumpStackTable {
             * (ci - return_opcode);
 
         // 5. If the memory pointer `jsp` does not change, then
-        // `clk_di'` is the inverse-or-zero of the clock jump
+        // `clk_di` is the inverse-or-zero of the clock jump
         // difference minus one.
         let jsp_changes = jsp_next.clone() - jsp.clone() - one.clone();
         let clock_diff_minus_one = clk_next.clone() - clk.clone() - one.clone();
-        let clkdi_is_inverse_of_clock_diff_minus_one = clk_di_next * clock_diff_minus_one.clone();
+        let clkdi_is_inverse_of_clock_diff_minus_one =
+            clk_di.clone() * clock_diff_minus_one.clone() - one.clone();
         let clkdi_is_zero_or_clkdi_is_inverse_of_clock_diff_minus_one_or_jsp_changes =
             clk_di.clone() * clkdi_is_inverse_of_clock_diff_minus_one.clone() * jsp_changes.clone();
         let clock_diff_minus_one_is_zero_or_clock_diff_minus_one_is_clkdi_inverse_or_jsp_changes =
@@ -252,13 +250,13 @@ impl JumpStackTable {
     /// Fills the trace table in-place and returns all clock jump differences greater than 1.
     pub fn fill_trace(
         jump_stack_table: &mut ArrayViewMut2<BFieldElement>,
-        aet: &AlgebraicExecutionTrace,
+        aet: &impl ExecutionTrace,
     ) -> Vec<BFieldElement> {
         // Store the registers relevant for the Jump Stack Table, i.e., CLK, CI, JSP, JSO, JSD,
         // with JSP as the key. Preserves, thus allows reusing, the order of the processor's
         // rows, which are sorted by CLK.
         let mut pre_processed_jump_stack_table: Vec<Vec<_>> = vec![];
-        for processor_row in aet.processor_trace.rows() {
+        for processor_row in aet.processor_trace().rows() {
             let clk = processor_row[ProcessorBaseTableColumn::CLK.base_table_index()];
             let ci = processor_row[ProcessorBaseTableColumn::CI.base_table_index()];
             let jsp = processor_row[ProcessorBaseTableColumn::JSP.base_table_index()];
@@ -289,13 +287,13 @@ impl JumpStackTable {
                 jump_stack_table_row += 1;
             }
         }
-        assert_eq!(aet.processor_trace.nrows(), jump_stack_table_row);
+        assert_eq!(aet.processor_trace().nrows(), jump_stack_table_row);
 
         // Set inverse of (clock difference - 1). Also, collect all clock jump differences
         // greater than 1.
         // The Jump Stack Table and the Processor Table have the same length.
         let mut clock_jump_differences_greater_than_1 = vec![];
-        for row_idx in 0..aet.processor_trace.nrows() - 1 {
+        for row_idx in 0..aet.processor_trace().nrows() - 1 {
             let (mut curr_row, next_row) =
                 jump_stack_table.multi_slice_mut((s![row_idx, ..], s![row_idx + 1, ..]));
             let clk_diff = next_row[CLK.base_table_index()] - curr_row[CLK.base_table_index()];
@@ -514,3 +512,53 @@ impl Display for JumpStackTraceRow {
         )
     }
 }
+
+#[cfg(test)]
+mod jump_stack_table_tests {
+    use crate::stark::triton_stark_tests::parse_simulate_pad_extend;
+    use crate::table::extension_table::Evaluable;
+    use crate::table::master_table::MasterTable;
+
+    use super::*;
+
+    #[test]
+    fn unchanged_jsp_across_clock_jump_satisfies_transition_constraints_test() {
+        // The jump stack pointer is 0 for both `call foo`s and for `halt`, and 1 for both
+        // executions of `foo`. Sorted by `jsp`, this gives three clock jumps with unchanged `jsp`,
+        // two of them consecutive.
+        let code = "call foo call foo halt foo: push 1 pop return";
+        let (_, _, master_base_table, master_ext_table, challenges) =
+            parse_simulate_pad_extend(code, vec![], vec![]);
+        let master_base_trace_table = master_base_table.trace_table();
+        let master_ext_trace_table = master_ext_table.trace_table();
+
+        let clk_index = CLK.master_base_table_index();
+        let jsp_index = JSP.master_base_table_index();
+        let mut num_clock_jumps_with_unchanged_jsp = 0;
+        for row_idx in 0..master_base_trace_table.nrows() - 1 {
+            let base_row = master_base_trace_table.row(row_idx);
+            let next_base_row = master_base_trace_table.row(row_idx + 1);
+            let jsp_is_unchanged = base_row[jsp_index] == next_base_row[jsp_index];
+            let clock_jumps =
+                next_base_row[clk_index] - base_row[clk_index] != BFieldElement::one();
+            if jsp_is_unchanged && clock_jumps {
+                num_clock_jumps_with_unchanged_jsp += 1;
+            }
+
+            let evaluated_constraints = ExtJumpStackTable::evaluate_transition_constraints(
+                base_row,
+                master_ext_trace_table.row(row_idx),
+                next_base_row,
+                master_ext_trace_table.row(row_idx + 1),
+                &challenges,
+            );
+            for (constraint_idx, evaluated_constraint) in evaluated_constraints.iter().enumerate() {
+                assert!(
+                    evaluated_constraint.is_zero(),
+                    "Failed transition constraint {constraint_idx} in row {row_idx}."
+                );
+            }
+        }
+        assert_eq!(3, num_clock_jumps_with_unchanged_jsp);
+    }
+}
@@ -12,7 +12,9 @@ use ndarray::Zip;
 use num_traits::One;
 use rand::distributions::Standard;
 use rand::prelude::Distribution;
-use rand::random;
+use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
 use strum::EnumCount;
 use strum_macros::Display;
 use strum_macros::EnumCount as EnumCountMacro;
@@ -36,7 +38,7 @@ use twenty_first::util_types::merkle_tree::MerkleTree;
 use twenty_first::util_types::merkle_tree_maker::MerkleTreeMaker;
 
 use crate::arithmetic_domain::ArithmeticDomain;
-use crate::stark::StarkHasher;
+use crate::execution_trace::ExecutionTrace;
 use crate::table::challenges::AllChallenges;
 use crate::table::cross_table_argument::GrandCrossTableArg;
 use crate::table::extension_table::DegreeWithOrigin;
@@ -59,7 +61,6 @@ use crate::table::ram_table::RamTable;
 use crate::table::u32_table::ExtU32Table;
 use crate::table::u32_table::U32Table;
 use crate::table::*;
-use crate::vm::AlgebraicExecutionTrace;
 
 pub const NUM_TABLES: usize = TableId::COUNT;
 
@@ -191,15 +192,20 @@ where
     fn master_matrix_mut(&mut self) -> ArrayViewMut2<FF>;
     fn fri_domain(&self) -> ArithmeticDomain;
 
-    /// set all rows _not_ needed for the (padded) trace to random values
-    fn randomize_trace(&mut self) {
+    /// set all rows _not_ needed for the (padded) trace to random values, derived from the `seed`
+    fn randomize_trace(&mut self, seed: <ChaCha20Rng as SeedableRng>::Seed) {
         let randomized_padded_trace_len = self.randomized_padded_trace_len();
         let unit_distance = self.rand_trace_to_padded_trace_unit_distance();
-        (1..unit_distance).for_each(|offset| {
-            self.master_matrix_mut()
-                .slice_mut(s![offset..randomized_padded_trace_len; unit_distance, ..])
-                .par_mapv_inplace(|_| random::<FF>())
-        });
+        Zip::indexed(self.master_matrix_mut().axis_iter_mut(Axis(1))).par_for_each(
+            |column_index, mut column| {
+                let mut rng = column_rng(seed, column_index);
+                for offset in 1..unit_distance {
+                    column
+                        .slice_mut(s![offset..randomized_padded_trace_len; unit_distance])
+                        .mapv_inplace(|_| rng.gen::<FF>());
+                }
+            },
+        );
     }
 
     /// Result is in row-major order.
@@ -314,7 +320,7 @@ impl MasterTable<XFieldElement> for MasterExtTable {
 }
 
 impl MasterBaseTable {
-    pub fn padded_height(aet: &AlgebraicExecutionTrace, program: &[BFieldElement]) -> usize {
+    pub fn padded_height(aet: &impl ExecutionTrace, program: &[BFieldElement]) -> usize {
         let max_height = [
             Self::instruction_table_length(aet, program),
             Self::hash_table_length(aet),
@@ -327,30 +333,32 @@ impl MasterBaseTable {
         roundup_npo2(max_height as u64) as usize
     }
 
-    pub fn instruction_table_length(
-        aet: &AlgebraicExecutionTrace,
-        program: &[BFieldElement],
-    ) -> usize {
-        program.len() + aet.processor_trace.nrows()
+    pub fn instruction_table_length(aet: &impl ExecutionTrace, program: &[BFieldElement]) -> usize {
+        program.len() + aet.processor_trace().nrows()
     }
 
-    pub fn hash_table_length(aet: &AlgebraicExecutionTrace) -> usize {
-        aet.sponge_trace.nrows() + aet.hash_trace.nrows()
+    pub fn hash_table_length(aet: &impl ExecutionTrace) -> usize {
+        aet.sponge_trace().nrows() + aet.hash_trace().nrows()
     }
 
-    pub fn u32_table_length(aet: &AlgebraicExecutionTrace) -> usize {
-        aet.u32_entries
+    pub fn u32_table_length(aet: &impl ExecutionTrace) -> usize {
+        aet.u32_entries()
             .iter()
-            .map(|(_, lhs, rhs)| max(lhs.value(), rhs.value()))
-            .map(|bigger_value| match bigger_value == 0 {
-                true => 1,
-                false => 2 + log_2_floor(bigger_value as u128) as usize,
-            })
+            .map(|&(_, lhs, rhs)| Self::u32_table_entry_length(lhs, rhs))
             .sum()
     }
 
+    /// The number of rows in the U32 Table resulting from one entry with operands `lhs` and `rhs`.
+    pub fn u32_table_entry_length(lhs: BFieldElement, rhs: BFieldElement) -> usize {
+        let bigger_value = max(lhs.value(), rhs.value());
+        match bigger_value == 0 {
+            true => 1,
+            false => 2 + log_2_floor(bigger_value as u128) as usize,
+        }
+    }
+
     pub fn new(
-        aet: AlgebraicExecutionTrace,
+        aet: impl ExecutionTrace,
         program: &[BFieldElement],
         num_trace_randomizers: usize,
         fri_domain: ArithmeticDomain,
@@ -360,7 +368,7 @@ impl MasterBaseTable {
             randomized_padded_trace_len(num_trace_randomizers, padded_height);
         let unit_distance = randomized_padded_trace_len / padded_height;
         let program_len = program.len();
-        let main_execution_len = aet.processor_trace.nrows();
+        let main_execution_len = aet.processor_trace().nrows();
         let hash_coprocessor_execution_len = Self::hash_table_length(&aet);
         let u32_coprocesor_execution_len = Self::u32_table_length(&aet);
 
@@ -441,16 +449,16 @@ impl MasterBaseTable {
         }
     }
 
-    pub fn merkle_tree(
+    pub fn merkle_tree<H: AlgebraicHasher>(
         &self,
         maybe_profiler: &mut Option<TritonProfiler>,
-    ) -> MerkleTree<StarkHasher, CpuParallel> {
+    ) -> MerkleTree<H, CpuParallel> {
         prof_start!(maybe_profiler, "leafs");
         let hashed_rows = self
             .master_base_matrix
             .axis_iter(Axis(0))
             .into_par_iter()
-            .map(|row| StarkHasher::hash_slice(&row.to_vec()))
+            .map(|row| H::hash_slice(&row.to_vec()))
             .collect::<Vec<_>>();
         prof_stop!(maybe_profiler, "leafs");
         prof_start!(maybe_profiler, "Merkle tree");
@@ -462,19 +470,26 @@ impl MasterBaseTable {
 
     /// Create a `MasterExtTable` from a `MasterBaseTable` by `.extend()`ing each individual base
     /// table. The `.extend()` for each table is specific to that table, but always involves
-    /// adding some number of columns.
+    /// adding some number of columns. The randomizer polynomials are derived from the `seed`.
     pub fn extend(
         &self,
         challenges: &AllChallenges,
         num_randomizer_polynomials: usize,
+        seed: <ChaCha20Rng as SeedableRng>::Seed,
     ) -> MasterExtTable {
         // randomizer polynomials
         let num_rows = self.master_base_matrix.nrows();
         let num_columns = NUM_EXT_COLUMNS + num_randomizer_polynomials;
         let mut master_ext_matrix = Array2::zeros([num_rows, num_columns].f());
-        master_ext_matrix
-            .slice_mut(s![.., NUM_EXT_COLUMNS..])
-            .par_mapv_inplace(|_| random::<XFieldElement>());
+        Zip::indexed(
+            master_ext_matrix
+                .slice_mut(s![.., NUM_EXT_COLUMNS..])
+                .axis_iter_mut(Axis(1)),
+        )
+        .par_for_each(|column_index, mut column| {
+            let mut rng = column_rng(seed, column_index);
+            column.mapv_inplace(|_| rng.gen::<XFieldElement>());
+        });
 
         let mut master_ext_table = MasterExtTable {
             padded_height: self.padded_height,
@@ -576,10 +591,10 @@ impl MasterExtTable {
         randomizer_polynomials
     }
 
-    pub fn merkle_tree(
+    pub fn merkle_tree<H: AlgebraicHasher>(
         &self,
         maybe_profiler: &mut Option<TritonProfiler>,
-    ) -> MerkleTree<StarkHasher, CpuParallel> {
+    ) -> MerkleTree<H, CpuParallel> {
         prof_start!(maybe_profiler, "leafs");
         let hashed_rows = self
             .master_ext_matrix
@@ -591,7 +606,7 @@ impl MasterExtTable {
                     .iter()
                     .map(|xfe| xfe.coefficients.to_vec())
                     .concat();
-                StarkHasher::hash_slice(&contiguous_row_bfe)
+                H::hash_slice(&contiguous_row_bfe)
             })
             .collect::<Vec<_>>();
         prof_stop!(maybe_profiler, "leafs");
@@ -785,6 +800,28 @@ pub fn all_quotient_degree_bounds(interpolant_degree: Degree, padded_height: usi
     .concat()
 }
 
+/// The inverses of the zerofiers of the initial, consistency, transition, and terminal
+/// constraints, evaluated on the quotient domain. They depend only on the trace domain and the
+/// quotient domain, and can thus be shared between proofs of the same padded height.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotientZerofierInverses {
+    pub initial: Array1<BFieldElement>,
+    pub consistency: Array1<BFieldElement>,
+    pub transition: Array1<BFieldElement>,
+    pub terminal: Array1<BFieldElement>,
+}
+
+impl QuotientZerofierInverses {
+    pub fn new(trace_domain: ArithmeticDomain, quotient_domain: ArithmeticDomain) -> Self {
+        Self {
+            initial: initial_quotient_zerofier_inverse(quotient_domain),
+            consistency: consistency_quotient_zerofier_inverse(trace_domain, quotient_domain),
+            transition: transition_quotient_zerofier_inverse(trace_domain, quotient_domain),
+            terminal: terminal_quotient_zerofier_inverse(trace_domain, quotient_domain),
+        }
+    }
+}
+
 pub fn initial_quotient_zerofier_inverse(
     quotient_domain: ArithmeticDomain,
 ) -> Array1<BFieldElement> {
@@ -1291,6 +1328,7 @@ pub fn all_quotients(
     quotient_domain_master_ext_table: ArrayView2<XFieldElement>,
     trace_domain: ArithmeticDomain,
     quotient_domain: ArithmeticDomain,
+    zerofier_inverses: &QuotientZerofierInverses,
     challenges: &AllChallenges,
     maybe_profiler: &mut Option<TritonProfiler>,
 ) -> Array2<XFieldElement> {
@@ -1325,12 +1363,11 @@ pub fn all_quotients(
         ..,
         initial_quotient_section_start..initial_quotient_section_end
     ]);
-    let initial_quotient_zerofier_inverse = initial_quotient_zerofier_inverse(quotient_domain);
     fill_all_initial_quotients(
         quotient_domain_master_base_table,
         quotient_domain_master_ext_table,
         &mut initial_quot_table,
-        initial_quotient_zerofier_inverse.view(),
+        zerofier_inverses.initial.view(),
         challenges,
     );
     prof_stop!(maybe_profiler, "initial");
@@ -1340,13 +1377,11 @@ pub fn all_quotients(
         ..,
         consistency_quotient_section_start..consistency_quotient_section_end
     ]);
-    let consistency_quotient_zerofier_inverse =
-        consistency_quotient_zerofier_inverse(trace_domain, quotient_domain);
     fill_all_consistency_quotients(
         quotient_domain_master_base_table,
         quotient_domain_master_ext_table,
         &mut consistency_quotients,
-        consistency_quotient_zerofier_inverse.view(),
+        zerofier_inverses.consistency.view(),
         challenges,
     );
     prof_stop!(maybe_profiler, "consistency");
@@ -1356,13 +1391,11 @@ pub fn all_quotients(
         ..,
         transition_quotient_section_start..transition_quotient_section_end
     ]);
-    let transition_quotient_zerofier_inverse =
-        transition_quotient_zerofier_inverse(trace_domain, quotient_domain);
     fill_all_transition_quotients(
         quotient_domain_master_base_table,
         quotient_domain_master_ext_table,
         &mut transition_quotients,
-        transition_quotient_zerofier_inverse.view(),
+        zerofier_inverses.transition.view(),
         challenges,
         trace_domain,
         quotient_domain,
@@ -1374,13 +1407,11 @@ pub fn all_quotients(
         ..,
         terminal_quotient_section_start..terminal_quotient_section_end
     ]);
-    let initial_quotient_zerofier_inverse =
-        terminal_quotient_zerofier_inverse(trace_domain, quotient_domain);
     fill_all_terminal_quotients(
         quotient_domain_master_base_table,
         quotient_domain_master_ext_table,
         &mut terminal_quot_table,
-        initial_quotient_zerofier_inverse.view(),
+        zerofier_inverses.terminal.view(),
         challenges,
     );
     prof_stop!(maybe_profiler, "terminal");
@@ -1388,92 +1419,162 @@ pub fn all_quotients(
     all_quotients
 }
 
-pub fn evaluate_all_initial_constraints(
-    base_row: ArrayView1<BFieldElement>,
+/// The constraints of all tables, concatenated in a fixed order. Implemented for base rows over
+/// both the B-field and the X-field, the latter for evaluating out-of-domain rows.
+impl<FF: FiniteField> Evaluable<FF> for MasterExtTable
+where
+    ExtProgramTable: Evaluable<FF>,
+    ExtInstructionTable: Evaluable<FF>,
+    ExtProcessorTable: Evaluable<FF>,
+    ExtOpStackTable: Evaluable<FF>,
+    ExtRamTable: Evaluable<FF>,
+    ExtJumpStackTable: Evaluable<FF>,
+    ExtHashTable: Evaluable<FF>,
+    ExtU32Table: Evaluable<FF>,
+    GrandCrossTableArg: Evaluable<FF>,
+{
+    fn evaluate_initial_constraints(
+        base_row: ArrayView1<FF>,
+        ext_row: ArrayView1<XFieldElement>,
+        challenges: &AllChallenges,
+    ) -> Vec<XFieldElement> {
+        [
+            ExtProgramTable::evaluate_initial_constraints(base_row, ext_row, challenges),
+            ExtInstructionTable::evaluate_initial_constraints(base_row, ext_row, challenges),
+            ExtProcessorTable::evaluate_initial_constraints(base_row, ext_row, challenges),
+            ExtOpStackTable::evaluate_initial_constraints(base_row, ext_row, challenges),
+            ExtRamTable::evaluate_initial_constraints(base_row, ext_row, challenges),
+            ExtJumpStackTable::evaluate_initial_constraints(base_row, ext_row, challenges),
+            ExtHashTable::evaluate_initial_constraints(base_row, ext_row, challenges),
+            ExtU32Table::evaluate_initial_constraints(base_row, ext_row, challenges),
+        ]
+        .concat()
+    }
+
+    fn evaluate_consistency_constraints(
+        base_row: ArrayView1<FF>,
+        ext_row: ArrayView1<XFieldElement>,
+        challenges: &AllChallenges,
+    ) -> Vec<XFieldElement> {
+        [
+            ExtProgramTable::evaluate_consistency_constraints(base_row, ext_row, challenges),
+            ExtInstructionTable::evaluate_consistency_constraints(base_row, ext_row, challenges),
+            ExtProcessorTable::evaluate_consistency_constraints(base_row, ext_row, challenges),
+            ExtOpStackTable::evaluate_consistency_constraints(base_row, ext_row, challenges),
+            ExtRamTable::evaluate_consistency_constraints(base_row, ext_row, challenges),
+            ExtJumpStackTable::evaluate_consistency_constraints(base_row, ext_row, challenges),
+            ExtHashTable::evaluate_consistency_constraints(base_row, ext_row, challenges),
+            ExtU32Table::evaluate_consistency_constraints(base_row, ext_row, challenges),
+        ]
+        .concat()
+    }
+
+    fn evaluate_transition_constraints(
+        current_base_row: ArrayView1<FF>,
+        current_ext_row: ArrayView1<XFieldElement>,
+        next_base_row: ArrayView1<FF>,
+        next_ext_row: ArrayView1<XFieldElement>,
+        challenges: &AllChallenges,
+    ) -> Vec<XFieldElement> {
+        let cbr = current_base_row;
+        let cer = current_ext_row;
+        let nbr = next_base_row;
+        let ner = next_ext_row;
+        [
+            ExtProgramTable::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
+            ExtInstructionTable::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
+            ExtProcessorTable::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
+            ExtOpStackTable::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
+            ExtRamTable::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
+            ExtJumpStackTable::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
+            ExtHashTable::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
+            ExtU32Table::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
+        ]
+        .concat()
+    }
+
+    fn evaluate_terminal_constraints(
+        base_row: ArrayView1<FF>,
+        ext_row: ArrayView1<XFieldElement>,
+        challenges: &AllChallenges,
+    ) -> Vec<XFieldElement> {
+        [
+            ExtProgramTable::evaluate_terminal_constraints(base_row, ext_row, challenges),
+            ExtInstructionTable::evaluate_terminal_constraints(base_row, ext_row, challenges),
+            ExtProcessorTable::evaluate_terminal_constraints(base_row, ext_row, challenges),
+            ExtOpStackTable::evaluate_terminal_constraints(base_row, ext_row, challenges),
+            ExtRamTable::evaluate_terminal_constraints(base_row, ext_row, challenges),
+            ExtJumpStackTable::evaluate_terminal_constraints(base_row, ext_row, challenges),
+            ExtHashTable::evaluate_terminal_constraints(base_row, ext_row, challenges),
+            ExtU32Table::evaluate_terminal_constraints(base_row, ext_row, challenges),
+            GrandCrossTableArg::evaluate_terminal_constraints(base_row, ext_row, challenges),
+        ]
+        .concat()
+    }
+}
+
+pub fn evaluate_all_initial_constraints<FF: FiniteField>(
+    base_row: ArrayView1<FF>,
     ext_row: ArrayView1<XFieldElement>,
     challenges: &AllChallenges,
-) -> Vec<XFieldElement> {
-    [
-        ExtProgramTable::evaluate_initial_constraints(base_row, ext_row, challenges),
-        ExtInstructionTable::evaluate_initial_constraints(base_row, ext_row, challenges),
-        ExtProcessorTable::evaluate_initial_constraints(base_row, ext_row, challenges),
-        ExtOpStackTable::evaluate_initial_constraints(base_row, ext_row, challenges),
-        ExtRamTable::evaluate_initial_constraints(base_row, ext_row, challenges),
-        ExtJumpStackTable::evaluate_initial_constraints(base_row, ext_row, challenges),
-        ExtHashTable::evaluate_initial_constraints(base_row, ext_row, challenges),
-        ExtU32Table::evaluate_initial_constraints(base_row, ext_row, challenges),
-    ]
-    .concat()
+) -> Vec<XFieldElement>
+where
+    MasterExtTable: Evaluable<FF>,
+{
+    MasterExtTable::evaluate_initial_constraints(base_row, ext_row, challenges)
 }
 
-pub fn evaluate_all_consistency_constraints(
-    base_row: ArrayView1<BFieldElement>,
+pub fn evaluate_all_consistency_constraints<FF: FiniteField>(
+    base_row: ArrayView1<FF>,
     ext_row: ArrayView1<XFieldElement>,
     challenges: &AllChallenges,
-) -> Vec<XFieldElement> {
-    [
-        ExtProgramTable::evaluate_consistency_constraints(base_row, ext_row, challenges),
-        ExtInstructionTable::evaluate_consistency_constraints(base_row, ext_row, challenges),
-        ExtProcessorTable::evaluate_consistency_constraints(base_row, ext_row, challenges),
-        ExtOpStackTable::evaluate_consistency_constraints(base_row, ext_row, challenges),
-        ExtRamTable::evaluate_consistency_constraints(base_row, ext_row, challenges),
-        ExtJumpStackTable::evaluate_consistency_constraints(base_row, ext_row, challenges),
-        ExtHashTable::evaluate_consistency_constraints(base_row, ext_row, challenges),
-        ExtU32Table::evaluate_consistency_constraints(base_row, ext_row, challenges),
-    ]
-    .concat()
+) -> Vec<XFieldElement>
+where
+    MasterExtTable: Evaluable<FF>,
+{
+    MasterExtTable::evaluate_consistency_constraints(base_row, ext_row, challenges)
 }
 
-pub fn evaluate_all_transition_constraints(
-    current_base_row: ArrayView1<BFieldElement>,
+pub fn evaluate_all_transition_constraints<FF: FiniteField>(
+    current_base_row: ArrayView1<FF>,
     current_ext_row: ArrayView1<XFieldElement>,
-    next_base_row: ArrayView1<BFieldElement>,
+    next_base_row: ArrayView1<FF>,
     next_ext_row: ArrayView1<XFieldElement>,
     challenges: &AllChallenges,
-) -> Vec<XFieldElement> {
-    let cbr = current_base_row;
-    let cer = current_ext_row;
-    let nbr = next_base_row;
-    let ner = next_ext_row;
-    [
-        ExtProgramTable::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
-        ExtInstructionTable::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
-        ExtProcessorTable::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
-        ExtOpStackTable::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
-        ExtRamTable::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
-        ExtJumpStackTable::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
-        ExtHashTable::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
-        ExtU32Table::evaluate_transition_constraints(cbr, cer, nbr, ner, challenges),
-    ]
-    .concat()
+) -> Vec<XFieldElement>
+where
+    MasterExtTable: Evaluable<FF>,
+{
+    MasterExtTable::evaluate_transition_constraints(
+        current_base_row,
+        current_ext_row,
+        next_base_row,
+        next_ext_row,
+        challenges,
+    )
 }
 
-pub fn evaluate_all_terminal_constraints(
-    base_row: ArrayView1<BFieldElement>,
+pub fn evaluate_all_terminal_constraints<FF: FiniteField>(
+    base_row: ArrayView1<FF>,
     ext_row: ArrayView1<XFieldElement>,
     challenges: &AllChallenges,
-) -> Vec<XFieldElement> {
-    [
-        ExtProgramTable::evaluate_terminal_constraints(base_row, ext_row, challenges),
-        ExtInstructionTable::evaluate_terminal_constraints(base_row, ext_row, challenges),
-        ExtProcessorTable::evaluate_terminal_constraints(base_row, ext_row, challenges),
-        ExtOpStackTable::evaluate_terminal_constraints(base_row, ext_row, challenges),
-        ExtRamTable::evaluate_terminal_constraints(base_row, ext_row, challenges),
-        ExtJumpStackTable::evaluate_terminal_constraints(base_row, ext_row, challenges),
-        ExtHashTable::evaluate_terminal_constraints(base_row, ext_row, challenges),
-        ExtU32Table::evaluate_terminal_constraints(base_row, ext_row, challenges),
-        GrandCrossTableArg::evaluate_terminal_constraints(base_row, ext_row, challenges),
-    ]
-    .concat()
+) -> Vec<XFieldElement>
+where
+    MasterExtTable: Evaluable<FF>,
+{
+    MasterExtTable::evaluate_terminal_constraints(base_row, ext_row, challenges)
 }
 
-pub fn evaluate_all_constraints(
-    current_base_row: ArrayView1<BFieldElement>,
+pub fn evaluate_all_constraints<FF: FiniteField>(
+    current_base_row: ArrayView1<FF>,
     current_ext_row: ArrayView1<XFieldElement>,
-    next_base_row: ArrayView1<BFieldElement>,
+    next_base_row: ArrayView1<FF>,
     next_ext_row: ArrayView1<XFieldElement>,
     challenges: &AllChallenges,
-) -> Vec<XFieldElement> {
+) -> Vec<XFieldElement>
+where
+    MasterExtTable: Evaluable<FF>,
+{
     [
         evaluate_all_initial_constraints(current_base_row, current_ext_row, challenges),
         evaluate_all_consistency_constraints(current_base_row, current_ext_row, challenges),
@@ -1505,6 +1606,14 @@ pub fn derive_domain_generator(domain_length: u64) -> BFieldElement {
     BFieldElement::primitive_root_of_unity(domain_length).unwrap()
 }
 
+/// The random number generator for one column of a master table. Every column uses its own
+/// stream, making the random values independent of the order in which columns are processed.
+fn column_rng(seed: <ChaCha20Rng as SeedableRng>::Seed, column_index: usize) -> ChaCha20Rng {
+    let mut rng = ChaCha20Rng::from_seed(seed);
+    rng.set_stream(column_index as u64);
+    rng
+}
+
 #[cfg(test)]
 mod master_table_tests {
     use ndarray::s;
@@ -19,6 +19,7 @@ use twenty_first::shared_math::x_field_element::XFieldElement;
 
 use OpStackTableChallengeId::*;
 
+use crate::execution_trace::ExecutionTrace;
 use crate::op_stack::OP_STACK_REG_COUNT;
 use crate::table::challenges::TableChallenges;
 use crate::table::constraint_circuit::ConstraintCircuit;
@@ -40,7 +41,6 @@ use crate::table::table_column::OpStackBaseTableColumn::*;
 use crate::table::table_column::OpStackExtTableColumn;
 use crate::table::table_column::OpStackExtTableColumn::*;
 use crate::table::table_column::ProcessorBaseTableColumn;
-use crate::vm::AlgebraicExecutionTrace;
 
 pub const OP_STACK_TABLE_NUM_PERMUTATION_ARGUMENTS: usize = 1;
 pub const OP_STACK_TABLE_NUM_EVALUATION_ARGUMENTS: usize = 0;
@@ -229,13 +229,13 @@ impl OpStackTable {
     /// Fills the trace table in-place and returns all clock jump differences greater than 1.
     pub fn fill_trace(
         op_stack_table: &mut ArrayViewMut2<BFieldElement>,
-        aet: &AlgebraicExecutionTrace,
+        aet: &impl ExecutionTrace,
     ) -> Vec<BFieldElement> {
         // Store the registers relevant for the Op Stack Table, i.e., CLK, IB1, OSP, and OSV,
         // with OSP as the key. Preserves, thus allows reusing, the order of the processor's
         // rows, which are sorted by CLK.
         let mut pre_processed_op_stack_table: Vec<Vec<_>> = vec![];
-        for processor_row in aet.processor_trace.rows() {
+        for processor_row in aet.processor_trace().rows() {
             let clk = processor_row[ProcessorBaseTableColumn::CLK.base_table_index()];
             let ib1 = processor_row[ProcessorBaseTableColumn::IB1.base_table_index()];
             let osp = processor_row[ProcessorBaseTableColumn::OSP.base_table_index()];
@@ -265,13 +265,13 @@ impl OpStackTable {
                 op_stack_table_row += 1;
             }
         }
-        assert_eq!(aet.processor_trace.nrows(), op_stack_table_row);
+        assert_eq!(aet.processor_trace().nrows(), op_stack_table_row);
 
         // Set inverse of (clock difference - 1). Also, collect all clock jump differences
         // greater than 1.
         // The Op Stack Table and the Processor Table have the same length.
         let mut clock_jump_differences_greater_than_1 = vec![];
-        for row_idx in 0..aet.processor_trace.nrows() - 1 {
+        for row_idx in 0..aet.processor_trace().nrows() - 1 {
             let (mut curr_row, next_row) =
                 op_stack_table.multi_slice_mut((s![row_idx, ..], s![row_idx + 1, ..]));
             let clk_diff = next_row[CLK.base_table_index()] - curr_row[CLK.base_table_index()];
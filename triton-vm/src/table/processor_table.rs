@@ -28,6 +28,7 @@ use triton_opcodes::instruction::Instruction;
 use triton_opcodes::ord_n::Ord8;
 use ProcessorTableChallengeId::*;
 
+use crate::execution_trace::ExecutionTrace;
 use crate::table::challenges::TableChallenges;
 use crate::table::constraint_circuit::ConstraintCircuit;
 use crate::table::constraint_circuit::ConstraintCircuitBuilder;
@@ -48,7 +49,6 @@ use crate::table::table_column::ProcessorBaseTableColumn;
 use crate::table::table_column::ProcessorBaseTableColumn::*;
 use crate::table::table_column::ProcessorExtTableColumn;
 use crate::table::table_column::ProcessorExtTableColumn::*;
-use crate::vm::AlgebraicExecutionTrace;
 
 pub const PROCESSOR_TABLE_NUM_PERMUTATION_ARGUMENTS: usize = 6;
 pub const PROCESSOR_TABLE_NUM_EVALUATION_ARGUMENTS: usize = 8;
@@ -64,15 +64,13 @@ pub struct ProcessorTable {}
 impl ProcessorTable {
     pub fn fill_trace(
         processor_table: &mut ArrayViewMut2<BFieldElement>,
-        aet: &AlgebraicExecutionTrace,
+        aet: &impl ExecutionTrace,
         mut all_clk_jump_diffs: Vec<BFieldElement>,
     ) {
         // fill the processor table from the AET
         let mut processor_table_to_fill =
-            processor_table.slice_mut(s![0..aet.processor_trace.nrows(), ..]);
-        aet.processor_trace
-            .clone()
-            .move_into(&mut processor_table_to_fill);
+            processor_table.slice_mut(s![0..aet.processor_trace().nrows(), ..]);
+        processor_table_to_fill.assign(&aet.processor_trace());
 
         let zero = BFieldElement::zero();
         all_clk_jump_diffs.sort_by_key(|bfe| std::cmp::Reverse(bfe.value()));
@@ -4846,7 +4844,7 @@ mod constraint_polynomial_tests {
         let code = "push 2 push -1 add assert halt";
         let program = Program::from_code(code).unwrap();
         let (aet, _, _) = simulate_no_input(&program);
-        for row in aet.processor_trace.rows() {
+        for row in aet.processor_trace().rows() {
             println!("{}", ProcessorTraceRow { row });
         }
     }
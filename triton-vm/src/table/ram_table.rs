@@ -21,6 +21,7 @@ use twenty_first::shared_math::x_field_element::XFieldElement;
 
 use RamTableChallengeId::*;
 
+use crate::execution_trace::ExecutionTrace;
 use crate::table::challenges::TableChallenges;
 use crate::table::constraint_circuit::ConstraintCircuit;
 use crate::table::constraint_circuit::ConstraintCircuitBuilder;
@@ -41,7 +42,6 @@ use crate::table::table_column::RamBaseTableColumn;
 use crate::table::table_column::RamBaseTableColumn::*;
 use crate::table::table_column::RamExtTableColumn;
 use crate::table::table_column::RamExtTableColumn::*;
-use crate::vm::AlgebraicExecutionTrace;
 
 pub const RAM_TABLE_NUM_PERMUTATION_ARGUMENTS: usize = 1;
 pub const RAM_TABLE_NUM_EVALUATION_ARGUMENTS: usize = 0;
@@ -61,14 +61,14 @@ impl RamTable {
     /// Fills the trace table in-place and returns all clock jump differences greater than 1.
     pub fn fill_trace(
         ram_table: &mut ArrayViewMut2<BFieldElement>,
-        aet: &AlgebraicExecutionTrace,
+        aet: &impl ExecutionTrace,
     ) -> Vec<BFieldElement> {
         // Store the registers relevant for the Ram Table, i.e., CLK, RAMP, RAMV, and
         // PreviousInstruction, with RAMP as the key. Preserves, thus allows reusing, the order
         // of the processor's rows, which are sorted by CLK. Note that the Ram Table must not be
         // sorted by RAMP, but must form contiguous regions of RAMP values.
         let mut pre_processed_ram_table: HashMap<_, Vec<_>> = HashMap::new();
-        for processor_row in aet.processor_trace.rows() {
+        for processor_row in aet.processor_trace().rows() {
             let clk = processor_row[ProcessorBaseTableColumn::CLK.base_table_index()];
             let ramp = processor_row[ProcessorBaseTableColumn::RAMP.base_table_index()];
             let ramv = processor_row[ProcessorBaseTableColumn::RAMV.base_table_index()];
@@ -128,7 +128,7 @@ impl RamTable {
                 ram_table_row_idx += 1;
             }
         }
-        assert_eq!(aet.processor_trace.nrows(), ram_table_row_idx);
+        assert_eq!(aet.processor_trace().nrows(), ram_table_row_idx);
 
         // - Set inverse of clock difference - 1.
         // - Set inverse of RAMP difference.
@@ -136,7 +136,7 @@ impl RamTable {
         // - Collect all clock jump differences greater than 1.
         // The Ram Table and the Processor Table have the same length.
         let mut clock_jump_differences_greater_than_1 = vec![];
-        for row_idx in 0..aet.processor_trace.nrows() - 1 {
+        for row_idx in 0..aet.processor_trace().nrows() - 1 {
             let (mut curr_row, mut next_row) =
                 ram_table.multi_slice_mut((s![row_idx, ..], s![row_idx + 1, ..]));
 
@@ -21,6 +21,7 @@ use twenty_first::shared_math::x_field_element::XFieldElement;
 use triton_opcodes::instruction::Instruction;
 use U32TableChallengeId::*;
 
+use crate::execution_trace::ExecutionTrace;
 use crate::table::challenges::TableChallenges;
 use crate::table::constraint_circuit::ConstraintCircuit;
 use crate::table::constraint_circuit::ConstraintCircuitBuilder;
@@ -41,7 +42,6 @@ use crate::table::table_column::U32BaseTableColumn;
 use crate::table::table_column::U32BaseTableColumn::*;
 use crate::table::table_column::U32ExtTableColumn;
 use crate::table::table_column::U32ExtTableColumn::*;
-use crate::vm::AlgebraicExecutionTrace;
 
 pub const U32_TABLE_NUM_PERMUTATION_ARGUMENTS: usize = 2;
 pub const U32_TABLE_NUM_EVALUATION_ARGUMENTS: usize = 0;
@@ -421,9 +421,9 @@ impl ExtU32Table {
 }
 
 impl U32Table {
-    pub fn fill_trace(u32_table: &mut ArrayViewMut2<BFieldElement>, aet: &AlgebraicExecutionTrace) {
+    pub fn fill_trace(u32_table: &mut ArrayViewMut2<BFieldElement>, aet: &impl ExecutionTrace) {
         let mut next_section_start = 0;
-        for &(instruction, lhs, rhs) in aet.u32_entries.iter() {
+        for &(instruction, lhs, rhs) in aet.u32_entries().iter() {
             let mut first_row = Array2::zeros([1, BASE_WIDTH]);
             first_row[[0, CopyFlag.base_table_index()]] = BFieldElement::one();
             first_row[[0, Bits.base_table_index()]] = BFieldElement::zero();
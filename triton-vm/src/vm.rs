@@ -1,6 +1,9 @@
+use anyhow::Result;
 use ndarray::s;
 use ndarray::Array2;
 use ndarray::ArrayBase;
+use ndarray::ArrayView1;
+use ndarray::ArrayView2;
 use ndarray::Axis;
 use ndarray::Ix2;
 use ndarray::OwnedRepr;
@@ -12,7 +15,20 @@ use twenty_first::shared_math::rescue_prime_regular::STATE_SIZE;
 
 use triton_opcodes::instruction::Instruction;
 use triton_opcodes::program::Program;
-
+use triton_opcodes::source_map::SourceMap;
+
+use crate::error::SourceMappedError;
+use crate::execution_limits::ExecutionLimits;
+use crate::execution_limits::ExecutionMeter;
+use crate::execution_profile::ExecutionProfile;
+use crate::execution_profile::ExecutionProfiler;
+use crate::execution_profile::TableHeights;
+use crate::execution_trace::ExecutionTrace;
+use crate::execution_trace::TraceWriter;
+use crate::input::DefaultHintHandler;
+use crate::input::HintHandler;
+use crate::input::InputCursor;
+use crate::input::InputProvider;
 use crate::state::VMOutput;
 use crate::state::VMState;
 use crate::table::hash_table;
@@ -32,47 +48,283 @@ use crate::table::table_column::HashBaseTableColumn::STATE0;
 /// up to the point of failure.
 pub fn simulate(
     program: &Program,
-    mut stdin: Vec<BFieldElement>,
-    mut secret_in: Vec<BFieldElement>,
+    stdin: Vec<BFieldElement>,
+    secret_in: Vec<BFieldElement>,
+) -> (
+    AlgebraicExecutionTrace,
+    Vec<BFieldElement>,
+    Option<anyhow::Error>,
+) {
+    simulate_with_input(
+        program,
+        &mut InputCursor::new(stdin),
+        &mut InputCursor::new(secret_in),
+        &mut DefaultHintHandler,
+    )
+}
+
+/// Like `simulate()`, but reads the public and secret input from `InputProvider`s, which are only
+/// asked for an element when an instruction consumes it. Hinted `divine`s, like `divine_inverse`,
+/// are routed to the `hint_handler` instead of reading from secret input.
+pub fn simulate_with_input(
+    program: &Program,
+    stdin: &mut dyn InputProvider,
+    secret_in: &mut dyn InputProvider,
+    hint_handler: &mut dyn HintHandler,
+) -> (
+    AlgebraicExecutionTrace,
+    Vec<BFieldElement>,
+    Option<anyhow::Error>,
+) {
+    let mut aet = AlgebraicExecutionTrace::default();
+    let (stdout, err) = simulate_and_explain(
+        program,
+        None,
+        None,
+        stdin,
+        secret_in,
+        hint_handler,
+        &mut unlimited_meter(program),
+        &mut aet,
+    );
+    (aet, stdout, err)
+}
+
+/// Like `simulate()`, but hands every recorded row to the `trace_writer` instead of collecting
+/// the `AlgebraicExecutionTrace` in memory. Returns the output and, on premature termination of
+/// the VM or failure of the `trace_writer`, the error.
+pub fn simulate_to_writer<W: TraceWriter>(
+    program: &Program,
+    stdin: Vec<BFieldElement>,
+    secret_in: Vec<BFieldElement>,
+    trace_writer: &mut W,
+) -> (Vec<BFieldElement>, Option<anyhow::Error>) {
+    simulate_and_explain(
+        program,
+        None,
+        None,
+        &mut InputCursor::new(stdin),
+        &mut InputCursor::new(secret_in),
+        &mut DefaultHintHandler,
+        &mut unlimited_meter(program),
+        trace_writer,
+    )
+}
+
+/// Like `simulate()`, but on premature termination of the VM, the returned error is a
+/// `SourceMappedError` pointing to the failing instruction in the source code.
+pub fn simulate_with_source_map(
+    program: &Program,
+    source_map: &SourceMap,
+    stdin: Vec<BFieldElement>,
+    secret_in: Vec<BFieldElement>,
 ) -> (
     AlgebraicExecutionTrace,
     Vec<BFieldElement>,
     Option<anyhow::Error>,
 ) {
     let mut aet = AlgebraicExecutionTrace::default();
+    let (stdout, err) = simulate_and_explain(
+        program,
+        Some(source_map),
+        None,
+        &mut InputCursor::new(stdin),
+        &mut InputCursor::new(secret_in),
+        &mut DefaultHintHandler,
+        &mut unlimited_meter(program),
+        &mut aet,
+    );
+    (aet, stdout, err)
+}
+
+/// Like `simulate_with_source_map()`, but additionally profiles the execution: the processor
+/// cycles and the rows in the Hash Table and U32 Table are attributed to the functions and labels
+/// of the source code. Functions are identified by the labels `call` jumps to.
+///
+/// On premature termination of the VM, the profile covers the execution up to the point of
+/// failure.
+pub fn simulate_with_profile(
+    program: &Program,
+    source_map: &SourceMap,
+    stdin: Vec<BFieldElement>,
+    secret_in: Vec<BFieldElement>,
+) -> (
+    AlgebraicExecutionTrace,
+    Vec<BFieldElement>,
+    Option<anyhow::Error>,
+    ExecutionProfile,
+) {
+    let mut aet = AlgebraicExecutionTrace::default();
+    let mut profiler = ExecutionProfiler::new(source_map);
+    let (stdout, err) = simulate_and_explain(
+        program,
+        Some(source_map),
+        Some(&mut profiler),
+        &mut InputCursor::new(stdin),
+        &mut InputCursor::new(secret_in),
+        &mut DefaultHintHandler,
+        &mut unlimited_meter(program),
+        &mut aet,
+    );
+    let table_heights = TableHeights::new(&aet, &program.to_bwords());
+    let profile = profiler.finish(table_heights);
+    (aet, stdout, err, profile)
+}
+
+/// Like `simulate()`, but stops with an `ExecutionLimitError` as soon as the execution exceeds any
+/// of the `limits`. The returned `AlgebraicExecutionTrace` then covers the execution up to the
+/// instruction exceeding the limit, excluding that instruction.
+pub fn simulate_with_limits(
+    program: &Program,
+    stdin: Vec<BFieldElement>,
+    secret_in: Vec<BFieldElement>,
+    limits: ExecutionLimits,
+) -> (
+    AlgebraicExecutionTrace,
+    Vec<BFieldElement>,
+    Option<anyhow::Error>,
+) {
+    let mut aet = AlgebraicExecutionTrace::default();
+    let (stdout, err) = simulate_and_explain(
+        program,
+        None,
+        None,
+        &mut InputCursor::new(stdin),
+        &mut InputCursor::new(secret_in),
+        &mut DefaultHintHandler,
+        &mut ExecutionMeter::new(limits, program.len()),
+        &mut aet,
+    );
+    (aet, stdout, err)
+}
+
+/// The padded height of the trace of executing `program`, determined without recording the trace.
+/// Allows rejecting programs whose proof would need more resources than available before spending
+/// them. With a `max_padded_height` in the `limits`, execution stops as soon as the padded height
+/// is known to exceed it.
+pub fn predict_padded_height(
+    program: &Program,
+    stdin: Vec<BFieldElement>,
+    secret_in: Vec<BFieldElement>,
+    limits: ExecutionLimits,
+) -> Result<usize> {
+    let mut meter = ExecutionMeter::new(limits, program.len());
+    let (_, err) = simulate_and_explain(
+        program,
+        None,
+        None,
+        &mut InputCursor::new(stdin),
+        &mut InputCursor::new(secret_in),
+        &mut DefaultHintHandler,
+        &mut meter,
+        &mut DiscardingTraceWriter,
+    );
+    match err {
+        Some(err) => Err(err),
+        None => Ok(meter.padded_height()),
+    }
+}
+
+fn unlimited_meter(program: &Program) -> ExecutionMeter {
+    ExecutionMeter::new(ExecutionLimits::default(), program.len())
+}
+
+/// Drops every row, for executions that are only metered.
+struct DiscardingTraceWriter;
+
+impl TraceWriter for DiscardingTraceWriter {
+    fn write_processor_row(&mut self, _: ArrayView1<BFieldElement>) -> Result<()> {
+        Ok(())
+    }
+
+    fn write_hash_rows(&mut self, _: ArrayView2<BFieldElement>) -> Result<()> {
+        Ok(())
+    }
+
+    fn write_sponge_rows(&mut self, _: ArrayView2<BFieldElement>) -> Result<()> {
+        Ok(())
+    }
+
+    fn write_u32_entries(
+        &mut self,
+        _: &[(Instruction, BFieldElement, BFieldElement)],
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn simulate_and_explain<W: TraceWriter>(
+    program: &Program,
+    source_map: Option<&SourceMap>,
+    mut profiler: Option<&mut ExecutionProfiler>,
+    stdin: &mut dyn InputProvider,
+    secret_in: &mut dyn InputProvider,
+    hint_handler: &mut dyn HintHandler,
+    meter: &mut ExecutionMeter,
+    trace_writer: &mut W,
+) -> (Vec<BFieldElement>, Option<anyhow::Error>) {
     let mut state = VMState::new(program);
+    let mut stdout = vec![];
+
     // record initial state
-    aet.processor_trace
-        .push_row(state.to_processor_row().view())
-        .expect("shapes must be identical");
+    if let Err(err) = trace_writer.write_processor_row(state.to_processor_row().view()) {
+        return (stdout, Some(err));
+    }
 
-    let mut stdout = vec![];
     while !state.is_complete() {
-        let vm_output = match state.step_mut(&mut stdin, &mut secret_in) {
-            Err(err) => return (aet, stdout, Some(err)),
+        // Some errors are only detected after the instruction pointer has been advanced.
+        let instruction_pointer = state.instruction_pointer;
+        if let Some(profiler) = profiler.as_deref_mut() {
+            profiler.record_instruction(&state);
+        }
+        let vm_output = match state.step_mut(stdin, secret_in, hint_handler) {
+            Err(err) => {
+                let err = match source_map {
+                    Some(source_map) => {
+                        let jump_stack = &state.jump_stack;
+                        SourceMappedError::new(err, instruction_pointer, jump_stack, source_map)
+                            .into()
+                    }
+                    None => err,
+                };
+                return (stdout, Some(err));
+            }
             Ok(vm_output) => vm_output,
         };
+        if let Err(err) = meter.record_step(&vm_output) {
+            return (stdout, Some(err));
+        }
+        if let Some(profiler) = profiler.as_deref_mut() {
+            profiler.record_output(&vm_output);
+        }
 
-        match vm_output {
+        let written = match vm_output {
             Some(VMOutput::XlixTrace(Instruction::Hash, xlix_trace)) => {
-                aet.append_hash_trace(*xlix_trace)
+                let hash_trace_addendum = AlgebraicExecutionTrace::hash_trace_rows(*xlix_trace);
+                trace_writer.write_hash_rows(hash_trace_addendum.view())
             }
             Some(VMOutput::XlixTrace(instruction, xlix_trace)) => {
-                aet.append_sponge_trace(instruction, *xlix_trace)
+                let sponge_trace_addendum =
+                    AlgebraicExecutionTrace::sponge_trace_rows(instruction, *xlix_trace);
+                trace_writer.write_sponge_rows(sponge_trace_addendum.view())
             }
-            Some(VMOutput::U32TableEntries(mut entries)) => {
-                aet.u32_entries.append(&mut entries);
+            Some(VMOutput::U32TableEntries(entries)) => trace_writer.write_u32_entries(&entries),
+            Some(VMOutput::WriteOutputSymbol(written_word)) => {
+                stdout.push(written_word);
+                Ok(())
             }
-            Some(VMOutput::WriteOutputSymbol(written_word)) => stdout.push(written_word),
-            None => (),
-        }
+            None => Ok(()),
+        };
         // Record next, to be executed state.
-        aet.processor_trace
-            .push_row(state.to_processor_row().view())
-            .expect("shapes must be identical");
+        let written =
+            written.and_then(|_| trace_writer.write_processor_row(state.to_processor_row().view()));
+        if let Err(err) = written {
+            return (stdout, Some(err));
+        }
     }
 
-    (aet, stdout, None)
+    (stdout, None)
 }
 
 /// Wrapper around `.simulate_with_input()` and thus also around
@@ -89,33 +341,104 @@ pub fn simulate_no_input(
     simulate(program, vec![], vec![])
 }
 
-pub fn run(
-    program: &Program,
-    mut stdin: Vec<BFieldElement>,
-    mut secret_in: Vec<BFieldElement>,
-) -> (Vec<VMState>, Vec<BFieldElement>, Option<anyhow::Error>) {
-    let mut states = vec![VMState::new(program)];
-    let mut current_state = states.last().unwrap();
+/// Execute the program, returning the final state of the VM and the program's output. The state
+/// is updated in place, so memory use does not grow with the number of cycles. If execution fails,
+/// the error is returned, and the final state is the one the failing instruction left behind.
+pub fn run<'pgm>(
+    program: &'pgm Program,
+    stdin: Vec<BFieldElement>,
+    secret_in: Vec<BFieldElement>,
+) -> (VMState<'pgm>, Vec<BFieldElement>, Option<anyhow::Error>) {
+    run_with_input(
+        program,
+        &mut InputCursor::new(stdin),
+        &mut InputCursor::new(secret_in),
+        &mut DefaultHintHandler,
+    )
+}
+
+/// Like `run()`, but hands every state of the VM to the `observer`: the initial state and the
+/// state after every instruction that executed successfully.
+pub fn run_and_observe<'pgm>(
+    program: &'pgm Program,
+    stdin: Vec<BFieldElement>,
+    secret_in: Vec<BFieldElement>,
+    mut observer: impl FnMut(&VMState<'pgm>),
+) -> (VMState<'pgm>, Vec<BFieldElement>, Option<anyhow::Error>) {
+    run_and_meter(
+        program,
+        &mut InputCursor::new(stdin),
+        &mut InputCursor::new(secret_in),
+        &mut DefaultHintHandler,
+        &mut unlimited_meter(program),
+        &mut observer,
+    )
+}
+
+/// Like `run()`, but reads the public and secret input from `InputProvider`s and routes hinted
+/// `divine`s to the `hint_handler`.
+pub fn run_with_input<'pgm>(
+    program: &'pgm Program,
+    stdin: &mut dyn InputProvider,
+    secret_in: &mut dyn InputProvider,
+    hint_handler: &mut dyn HintHandler,
+) -> (VMState<'pgm>, Vec<BFieldElement>, Option<anyhow::Error>) {
+    run_and_meter(
+        program,
+        stdin,
+        secret_in,
+        hint_handler,
+        &mut unlimited_meter(program),
+        &mut |_| (),
+    )
+}
+
+/// Like `run()`, but stops with an `ExecutionLimitError` as soon as the execution exceeds any of
+/// the `limits`. The final state is then the one after the instruction exceeding the limit.
+pub fn run_with_limits<'pgm>(
+    program: &'pgm Program,
+    stdin: Vec<BFieldElement>,
+    secret_in: Vec<BFieldElement>,
+    limits: ExecutionLimits,
+) -> (VMState<'pgm>, Vec<BFieldElement>, Option<anyhow::Error>) {
+    run_and_meter(
+        program,
+        &mut InputCursor::new(stdin),
+        &mut InputCursor::new(secret_in),
+        &mut DefaultHintHandler,
+        &mut ExecutionMeter::new(limits, program.len()),
+        &mut |_| (),
+    )
+}
+
+fn run_and_meter<'pgm>(
+    program: &'pgm Program,
+    stdin: &mut dyn InputProvider,
+    secret_in: &mut dyn InputProvider,
+    hint_handler: &mut dyn HintHandler,
+    meter: &mut ExecutionMeter,
+    observer: &mut dyn FnMut(&VMState<'pgm>),
+) -> (VMState<'pgm>, Vec<BFieldElement>, Option<anyhow::Error>) {
+    let mut state = VMState::new(program);
+    observer(&state);
 
     let mut stdout = vec![];
-    while !current_state.is_complete() {
-        let step = current_state.step(&mut stdin, &mut secret_in);
-        let (next_state, vm_output) = match step {
-            Err(err) => {
-                return (states, stdout, Some(err));
-            }
-            Ok((next_state, vm_output)) => (next_state, vm_output),
+    while !state.is_complete() {
+        let vm_output = match state.step_mut(stdin, secret_in, hint_handler) {
+            Ok(vm_output) => vm_output,
+            Err(err) => return (state, stdout, Some(err)),
         };
+        if let Err(err) = meter.record_step(&vm_output) {
+            return (state, stdout, Some(err));
+        }
 
         if let Some(VMOutput::WriteOutputSymbol(written_word)) = vm_output {
             stdout.push(written_word);
         }
-
-        states.push(next_state);
-        current_state = states.last().unwrap();
+        observer(&state);
     }
 
-    (states, stdout, None)
+    (state, stdout, None)
 }
 
 #[derive(Debug, Clone)]
@@ -147,12 +470,52 @@ impl Default for AlgebraicExecutionTrace {
     }
 }
 
+impl TraceWriter for AlgebraicExecutionTrace {
+    fn write_processor_row(&mut self, row: ArrayView1<BFieldElement>) -> Result<()> {
+        self.processor_trace.push_row(row)?;
+        Ok(())
+    }
+
+    fn write_hash_rows(&mut self, rows: ArrayView2<BFieldElement>) -> Result<()> {
+        self.hash_trace.append(Axis(0), rows)?;
+        Ok(())
+    }
+
+    fn write_sponge_rows(&mut self, rows: ArrayView2<BFieldElement>) -> Result<()> {
+        self.sponge_trace.append(Axis(0), rows)?;
+        Ok(())
+    }
+
+    fn write_u32_entries(
+        &mut self,
+        entries: &[(Instruction, BFieldElement, BFieldElement)],
+    ) -> Result<()> {
+        self.u32_entries.extend_from_slice(entries);
+        Ok(())
+    }
+}
+
+impl ExecutionTrace for AlgebraicExecutionTrace {
+    fn processor_trace(&self) -> ArrayView2<'_, BFieldElement> {
+        self.processor_trace.view()
+    }
+
+    fn hash_trace(&self) -> ArrayView2<'_, BFieldElement> {
+        self.hash_trace.view()
+    }
+
+    fn sponge_trace(&self) -> ArrayView2<'_, BFieldElement> {
+        self.sponge_trace.view()
+    }
+
+    fn u32_entries(&self) -> &[(Instruction, BFieldElement, BFieldElement)] {
+        &self.u32_entries
+    }
+}
+
 impl AlgebraicExecutionTrace {
     pub fn append_hash_trace(&mut self, xlix_trace: [[BFieldElement; STATE_SIZE]; NUM_ROUNDS + 1]) {
-        let mut hash_trace_addendum = Self::add_round_number_and_constants(xlix_trace);
-        hash_trace_addendum
-            .slice_mut(s![.., CI.base_table_index()])
-            .fill(Instruction::Hash.opcode_b());
+        let hash_trace_addendum = Self::hash_trace_rows(xlix_trace);
         self.hash_trace
             .append(Axis(0), hash_trace_addendum.view())
             .expect("shapes must be identical");
@@ -163,6 +526,28 @@ impl AlgebraicExecutionTrace {
         instruction: Instruction,
         xlix_trace: [[BFieldElement; STATE_SIZE]; NUM_ROUNDS + 1],
     ) {
+        let sponge_trace_addendum = Self::sponge_trace_rows(instruction, xlix_trace);
+        self.sponge_trace
+            .append(Axis(0), sponge_trace_addendum.view())
+            .expect("shapes must be identical");
+    }
+
+    /// The rows of the Hash Table resulting from executing instruction `hash`.
+    pub fn hash_trace_rows(
+        xlix_trace: [[BFieldElement; STATE_SIZE]; NUM_ROUNDS + 1],
+    ) -> Array2<BFieldElement> {
+        let mut hash_trace_addendum = Self::add_round_number_and_constants(xlix_trace);
+        hash_trace_addendum
+            .slice_mut(s![.., CI.base_table_index()])
+            .fill(Instruction::Hash.opcode_b());
+        hash_trace_addendum
+    }
+
+    /// The rows of the Hash Table resulting from executing one of the Sponge instructions.
+    pub fn sponge_trace_rows(
+        instruction: Instruction,
+        xlix_trace: [[BFieldElement; STATE_SIZE]; NUM_ROUNDS + 1],
+    ) -> Array2<BFieldElement> {
         assert!(matches!(
             instruction,
             Instruction::AbsorbInit | Instruction::Absorb | Instruction::Squeeze
@@ -171,9 +556,7 @@ impl AlgebraicExecutionTrace {
         sponge_trace_addendum
             .slice_mut(s![.., CI.base_table_index()])
             .fill(instruction.opcode_b());
-        self.sponge_trace
-            .append(Axis(0), sponge_trace_addendum.view())
-            .expect("shapes must be identical");
+        sponge_trace_addendum
     }
 
     /// Given an XLIX trace, this function adds
@@ -239,7 +622,11 @@ pub mod triton_vm_tests {
     use twenty_first::shared_math::traits::FiniteField;
     use twenty_first::util_types::algebraic_hasher::SpongeHasher;
 
+    use crate::error::InstructionError;
+    use crate::execution_limits::ExecutionLimitError;
     use crate::shared_tests::SourceCodeAndInput;
+    use crate::table::master_table::MasterBaseTable;
+    use crate::table::master_table::TableId;
     use crate::table::processor_table::ProcessorTraceRow;
 
     use super::*;
@@ -348,6 +735,66 @@ pub mod triton_vm_tests {
         assert_eq!(expected_symbol, computed_symbol);
     }
 
+    #[test]
+    fn source_mapped_error_test() {
+        let code = "push 3 call outer halt\n\
+                    outer:\n    call inner\n    return\n\
+                    inner:\n    push 0\n    assert\n    return\n";
+        let (program, source_map) = Program::from_code_with_source_map(code).unwrap();
+        let (_, _, err) = simulate_with_source_map(&program, &source_map, vec![], vec![]);
+
+        let err = err.expect("execution must fail");
+        let err = err.downcast_ref::<SourceMappedError>().unwrap();
+        assert!(matches!(
+            err.error.downcast_ref::<InstructionError>(),
+            Some(InstructionError::AssertionFailed(10, _, _))
+        ));
+
+        let location = err.location().unwrap();
+        assert_eq!((7, 5), (location.line, location.column));
+        assert_eq!(Some("inner".to_string()), location.label);
+
+        let call_stack = err
+            .backtrace
+            .iter()
+            .map(|frame| frame.instruction_pointer)
+            .collect_vec();
+        assert_eq!(vec![10, 5, 2], call_stack);
+        assert_eq!(
+            Some("outer"),
+            err.backtrace[1].location.as_ref().unwrap().label.as_deref()
+        );
+        assert_eq!(None, err.backtrace[2].location.as_ref().unwrap().label);
+
+        let expected_message = format!(
+            "{}\n  \
+            --> line 7, column 5, in inner\n\
+            backtrace:\n  \
+            #0 ip 10: line 7, column 5, in inner\n  \
+            #1 ip 5: line 3, column 5, in outer\n  \
+            #2 ip 2: line 1, column 8",
+            err.error
+        );
+        assert_eq!(expected_message, err.to_string());
+    }
+
+    #[test]
+    fn source_mapped_error_points_to_failing_instruction_test() {
+        let code = "push 1\npop pop\nhalt";
+        let (program, source_map) = Program::from_code_with_source_map(code).unwrap();
+        let (_, _, err) = simulate_with_source_map(&program, &source_map, vec![], vec![]);
+
+        let err = err.expect("execution must fail");
+        let err = err.downcast_ref::<SourceMappedError>().unwrap();
+        assert!(matches!(
+            err.error.downcast_ref::<InstructionError>(),
+            Some(InstructionError::OpStackTooShallow)
+        ));
+        let location = err.location().unwrap();
+        assert_eq!((2, 5), (location.line, location.column));
+        assert_eq!(1, err.backtrace.len());
+    }
+
     pub fn test_hash_nop_nop_lt() -> SourceCodeAndInput {
         SourceCodeAndInput::without_input("hash nop hash nop nop hash push 3 push 2 lt assert halt")
     }
@@ -373,6 +820,12 @@ pub mod triton_vm_tests {
         }
     }
 
+    pub fn test_program_for_divine_hints() -> SourceCodeAndInput {
+        SourceCodeAndInput::without_input(
+            "push 7 divine_inverse mul push 1 eq assert push 6 divine_lsb push 0 eq assert halt",
+        )
+    }
+
     pub fn test_program_for_skiz() -> SourceCodeAndInput {
         SourceCodeAndInput::without_input("push 1 skiz push 0 skiz assert push 1 skiz halt")
     }
@@ -956,6 +1409,7 @@ pub mod triton_vm_tests {
             test_hash_nop_nop_lt(),
             test_program_for_push_pop_dup_swap_nop(),
             test_program_for_divine(),
+            test_program_for_divine_hints(),
             test_program_for_skiz(),
             test_program_for_call_recurse_return(),
             test_program_for_write_mem_read_mem(),
@@ -1147,4 +1601,81 @@ pub mod triton_vm_tests {
 
         assert_eq!(expected_stdout, actual_stdout);
     }
+
+    #[test]
+    fn pseudo_instructions_test() {
+        let actual_stdout = SourceCodeAndInput::without_input(
+            "
+            push 7 push 19 sub write_io
+            push 5 neg write_io
+            push 7 lsb write_io write_io
+            push 17 is_u32 write_io
+            push 8589934592 is_u32 write_io
+            halt
+            ",
+        )
+        .run();
+        let expected_stdout = [12, BFieldElement::P - 5, 1, 3, 1, 0]
+            .map(BFieldElement::new)
+            .to_vec();
+
+        assert_eq!(expected_stdout, actual_stdout);
+    }
+
+    #[test]
+    fn execution_limits_stop_programs_that_never_halt_test() {
+        let expect_limit_error = |err: Option<anyhow::Error>, expected: ExecutionLimitError| {
+            let err = err.expect("execution must exceed the limit");
+            assert_eq!(Some(&expected), err.downcast_ref::<ExecutionLimitError>());
+        };
+
+        let program = Program::from_code("call loop loop: push 1 pop recurse").unwrap();
+        let limits = ExecutionLimits::default().with_max_cycles(100);
+        let (aet, _, err) = simulate_with_limits(&program, vec![], vec![], limits);
+        expect_limit_error(err, ExecutionLimitError::CycleLimitExceeded(100));
+        assert_eq!(101, aet.processor_trace.nrows());
+
+        let (state, _, err) = run_with_limits(&program, vec![], vec![], limits);
+        expect_limit_error(err, ExecutionLimitError::CycleLimitExceeded(100));
+        assert_eq!(101, state.cycle_count);
+
+        let limits = ExecutionLimits::default().with_max_processor_table_height(50);
+        let (aet, _, err) = simulate_with_limits(&program, vec![], vec![], limits);
+        let expected_error =
+            ExecutionLimitError::TableHeightLimitExceeded(TableId::ProcessorTable, 50);
+        expect_limit_error(err, expected_error);
+        assert_eq!(50, aet.processor_trace.nrows());
+
+        let limits = ExecutionLimits::default().with_max_padded_height(64);
+        let err = predict_padded_height(&program, vec![], vec![], limits).unwrap_err();
+        expect_limit_error(
+            Some(err),
+            ExecutionLimitError::PaddedHeightLimitExceeded(64),
+        );
+    }
+
+    #[test]
+    fn predicted_padded_height_matches_padded_height_of_trace_test() {
+        let source_code_and_input = test_hash_nop_nop_lt();
+        let program = Program::from_code(&source_code_and_input.source_code).unwrap();
+        let (aet, _, err) = source_code_and_input.simulate();
+        assert!(err.is_none());
+        let padded_height = MasterBaseTable::padded_height(&aet, &program.to_bwords());
+
+        let limits = ExecutionLimits::default();
+        let predicted_padded_height = predict_padded_height(&program, vec![], vec![], limits);
+        assert_eq!(padded_height, predicted_padded_height.unwrap());
+
+        let limits = limits.with_max_padded_height(padded_height);
+        let predicted_padded_height = predict_padded_height(&program, vec![], vec![], limits);
+        assert_eq!(padded_height, predicted_padded_height.unwrap());
+
+        let limits = ExecutionLimits::default().with_max_hash_table_height(NUM_ROUNDS + 1);
+        let (aet, _, err) = simulate_with_limits(&program, vec![], vec![], limits);
+        let expected_error =
+            ExecutionLimitError::TableHeightLimitExceeded(TableId::HashTable, NUM_ROUNDS + 1);
+        let err = err.expect("execution must exceed the limit");
+        assert_eq!(Some(&expected_error), err.downcast_ref());
+        assert_eq!(NUM_ROUNDS + 1, aet.hash_trace.nrows());
+    }
 }